@@ -0,0 +1,243 @@
+//! Derive macro for the `PropertyAccess` trait of [ply-rs](https://github.com/Fluci/ply-rs).
+//!
+//! Don't use this crate directly, enable the `derive` feature of `ply-rs` instead
+//! and import the macro together with the trait from `ply_rs::ply::PropertyAccess`.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{ parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, GenericArgument, Ident, LitStr, PathArguments, Result, Type };
+
+/// Supported rust types with their `Property` variants and getters: `(type, scalar variant, scalar getter, list variant, list getter)`.
+const TYPES: [(&str, &str, &str, &str, &str); 10] = [
+    ("i8", "Char", "get_char", "ListChar", "get_list_char"),
+    ("u8", "UChar", "get_uchar", "ListUChar", "get_list_uchar"),
+    ("i16", "Short", "get_short", "ListShort", "get_list_short"),
+    ("u16", "UShort", "get_ushort", "ListUShort", "get_list_ushort"),
+    ("i32", "Int", "get_int", "ListInt", "get_list_int"),
+    ("u32", "UInt", "get_uint", "ListUInt", "get_list_uint"),
+    ("i64", "Long", "get_long", "ListLong", "get_list_long"),
+    ("u64", "ULong", "get_ulong", "ListULong", "get_list_ulong"),
+    ("f32", "Float", "get_float", "ListFloat", "get_list_float"),
+    ("f64", "Double", "get_double", "ListDouble", "get_list_double"),
+];
+
+/// A struct field accessible as PLY property.
+struct PropertyField {
+    ident: Ident,
+    key: String,
+    ty: Type,
+    variant: Ident,
+    getter: Ident,
+    list: bool,
+}
+
+/// Derives `ply_rs::ply::PropertyAccess` for a struct with named fields.
+///
+/// Every field is mapped to the property with the same name, its type determines the `Property` variant:
+/// `i8`, `u8`, `i16`, `u16`, `i32`, `u32`, `i64`, `u64`, `f32`, `f64`, or a `Vec` of one of them for list properties.
+/// All fields must implement `Default`, which is used by `new()`.
+///
+/// Attributes:
+///
+/// - `#[ply(rename = "vertex_index")]` on a field uses another property name.
+/// - `#[ply(skip)]` on a field excludes it, it keeps its default value.
+/// - `#[ply(ignore_unknown)]` on the struct silently drops unknown properties,
+///   otherwise `set_property()` panics on them.
+///
+/// `set_property()` always panics if a known property has an unexpected variant.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use ply_rs::ply::PropertyAccess;
+///
+/// #[derive(PropertyAccess)]
+/// #[ply(ignore_unknown)]
+/// struct Face {
+///     #[ply(rename = "vertex_index")]
+///     indices: Vec<i32>,
+/// }
+/// ```
+#[proc_macro_derive(PropertyAccess, attributes(ply))]
+pub fn derive_property_access(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Returns the name of a plain type path, e.g. `f32` or `Vec`.
+fn type_name(ty: &Type) -> Option<(String, &PathArguments)> {
+    match *ty {
+        Type::Path(ref p) if p.qself.is_none() => p.path.segments.last().map(|s| (s.ident.to_string(), &s.arguments)),
+        _ => None,
+    }
+}
+
+/// Looks up the variant and getter for a field type.
+fn property_type(ty: &Type) -> Option<(&'static str, &'static str, bool)> {
+    let (name, arguments) = type_name(ty)?;
+    if name == "Vec" {
+        let inner = match *arguments {
+            PathArguments::AngleBracketed(ref a) if a.args.len() == 1 => match a.args[0] {
+                GenericArgument::Type(ref t) => type_name(t)?.0,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        TYPES.iter().find(|t| t.0 == inner).map(|t| (t.3, t.4, true))
+    } else if arguments.is_empty() {
+        TYPES.iter().find(|t| t.0 == name).map(|t| (t.1, t.2, false))
+    } else {
+        None
+    }
+}
+
+/// Options of a field: `(rename, skip)`.
+fn field_options(attrs: &[Attribute]) -> Result<(Option<String>, bool)> {
+    let (mut rename, mut skip) = (None, false);
+    for attr in attrs.iter().filter(|a| a.path().is_ident("ply")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("Unknown field attribute, expected `rename = \"...\"` or `skip`."))
+            }
+        })?;
+    }
+    Ok((rename, skip))
+}
+
+/// Options of the struct: `ignore_unknown`.
+fn struct_options(attrs: &[Attribute]) -> Result<bool> {
+    let mut ignore_unknown = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("ply")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ignore_unknown") {
+                ignore_unknown = true;
+                Ok(())
+            } else {
+                Err(meta.error("Unknown struct attribute, expected `ignore_unknown`."))
+            }
+        })?;
+    }
+    Ok(ignore_unknown)
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match input.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Named(ref f) => &f.named,
+            _ => return Err(Error::new_spanned(name, "PropertyAccess can only be derived for structs with named fields.")),
+        },
+        _ => return Err(Error::new_spanned(name, "PropertyAccess can only be derived for structs.")),
+    };
+    let ignore_unknown = struct_options(&input.attrs)?;
+
+    let mut all_fields = Vec::new();
+    let mut properties = Vec::<PropertyField>::new();
+    for field in fields {
+        let ident = field.ident.clone().unwrap();
+        all_fields.push(ident.clone());
+        let (rename, skip) = field_options(&field.attrs)?;
+        if skip {
+            continue;
+        }
+        let key = rename.unwrap_or_else(|| ident.to_string());
+        if properties.iter().any(|p| p.key == key) {
+            return Err(Error::new_spanned(&ident, format!("Property `{}` is mapped to several fields.", key)));
+        }
+        let (variant, getter, list) = match property_type(&field.ty) {
+            Some(t) => t,
+            None => return Err(Error::new_spanned(&field.ty, "Unsupported field type, expected a PLY scalar type (i8, u8, i16, u16, i32, u32, i64, u64, f32, f64) or a Vec of one.")),
+        };
+        properties.push(PropertyField {
+            ident,
+            key,
+            ty: field.ty.clone(),
+            variant: Ident::new(variant, proc_macro2::Span::call_site()),
+            getter: Ident::new(getter, proc_macro2::Span::call_site()),
+            list,
+        });
+    }
+
+    let set_arms = properties.iter().map(|p| {
+        let (ident, key, variant) = (&p.ident, &p.key, &p.variant);
+        quote! { (#key, ::ply_rs::ply::Property::#variant(v)) => self.#ident = v, }
+    });
+    let mismatch_arms = properties.iter().map(|p| {
+        let (key, variant) = (&p.key, p.variant.to_string());
+        quote! {
+            (#key, p) => panic!("Property `{}` of `{}` must be `Property::{}`, but found `{:?}`.", #key, stringify!(#name), #variant, p),
+        }
+    });
+    let fallback = if ignore_unknown {
+        quote! { _ => (), }
+    } else {
+        quote! { (k, _) => panic!("Unknown property `{}` for `{}`.", k, stringify!(#name)), }
+    };
+
+    let mut getters = Vec::new();
+    for getter in properties.iter().map(|p| p.getter.to_string()).collect::<std::collections::BTreeSet<_>>() {
+        let matching: Vec<&PropertyField> = properties.iter().filter(|p| p.getter == getter).collect();
+        let first = matching[0];
+        let getter = &first.getter;
+        let arms = matching.iter().map(|p| {
+            let (ident, key) = (&p.ident, &p.key);
+            if p.list {
+                quote! { #key => ::std::option::Option::Some(self.#ident.as_slice()), }
+            } else {
+                quote! { #key => ::std::option::Option::Some(self.#ident), }
+            }
+        });
+        let ty = &first.ty;
+        let result = if first.list {
+            let element = match *ty {
+                Type::Path(ref t) => match t.path.segments.last().unwrap().arguments {
+                    PathArguments::AngleBracketed(ref a) => a.args[0].clone(),
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            };
+            quote! { ::std::option::Option<&[#element]> }
+        } else {
+            quote! { ::std::option::Option<#ty> }
+        };
+        getters.push(quote! {
+            fn #getter(&self, key: &str) -> #result {
+                match key {
+                    #(#arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ply_rs::ply::PropertyAccess for #name #ty_generics #where_clause {
+            fn new() -> Self {
+                #name {
+                    #(#all_fields: ::std::default::Default::default(),)*
+                }
+            }
+            fn set_property(&mut self, key: ::std::string::String, property: ::ply_rs::ply::Property) {
+                match (key.as_str(), property) {
+                    #(#set_arms)*
+                    #(#mismatch_arms)*
+                    #fallback
+                }
+            }
+            #(#getters)*
+        }
+    })
+}
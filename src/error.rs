@@ -0,0 +1,114 @@
+//! Error type returned by `Parser` and `Writer`.
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::io::ErrorKind;
+use crate::ply::ConsistencyError;
+
+/// Describes why reading or writing a PLY file failed.
+///
+/// This is the error of all methods of `Parser` and `Writer`, it tells where a problem occurred,
+/// i.e. the header line or the element.
+/// For callers which only need to know whether the IO failed or the data is malformed,
+/// the `_typed` methods convert it into the coarser `parser::PlyError`, see `From<Error> for PlyError`.
+#[derive(Debug)]
+pub enum Error {
+    /// A header line is malformed or contradicts a previous one.
+    ///
+    /// `line` is the one-based number of the offending line, counted from the first line read or written by the call.
+    /// It is `0` for methods handling a single line without context, e.g. `Parser::read_header_line()`.
+    InvalidHeader {
+        line: usize,
+        message: String,
+    },
+    /// An element in the payload doesn't match its declaration in the header.
+    InvalidPayload {
+        element: String,
+        message: String,
+    },
+    /// Reading from the source or writing to the target failed, e.g. unexpected end of file.
+    IoError(io::Error),
+    /// The `Ply` to be written isn't consistent and couldn't be fixed automatically.
+    InconsistentData(ConsistencyError),
+}
+
+impl Error {
+    /// Classifies an error from the header level:
+    /// `ErrorKind::InvalidInput` and `ErrorKind::InvalidData` denote malformed input,
+    /// all other kinds originate from the underlying reader or writer.
+    pub(crate) fn header(line: usize, e: io::Error) -> Self {
+        match e.kind() {
+            ErrorKind::InvalidInput | ErrorKind::InvalidData => Error::InvalidHeader {
+                line,
+                message: e.to_string(),
+            },
+            _ => Error::IoError(e),
+        }
+    }
+    /// Classifies an error from the payload level, see `header()`.
+    pub(crate) fn payload(element: &str, e: io::Error) -> Self {
+        match e.kind() {
+            ErrorKind::InvalidInput | ErrorKind::InvalidData => Error::InvalidPayload {
+                element: element.to_string(),
+                message: e.to_string(),
+            },
+            _ => Error::IoError(e),
+        }
+    }
+    /// Adds `offset` lines to the line number of an `InvalidHeader`.
+    pub(crate) fn shift_line(self, offset: usize) -> Self {
+        match self {
+            Error::InvalidHeader { line, message } => Error::InvalidHeader {
+                line: line + offset,
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidHeader { line, ref message } => write!(f, "Line {}: {}", line, message),
+            Error::InvalidPayload { ref element, ref message } => write!(f, "Element `{}`: {}", element, message),
+            Error::IoError(ref e) => write!(f, "IO error: {}", e),
+            Error::InconsistentData(ref e) => write!(f, "The given ply isn't consistent: {}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::IoError(ref e) => Some(e),
+            Error::InconsistentData(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IoError(e)
+    }
+}
+
+impl From<ConsistencyError> for Error {
+    fn from(e: ConsistencyError) -> Self {
+        Error::InconsistentData(e)
+    }
+}
+
+/// Allows using `?` on `Parser` and `Writer` results in functions returning `io::Result`.
+///
+/// IO errors are unwrapped, all other variants become `ErrorKind::InvalidInput`.
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::IoError(e) => e,
+            e => io::Error::new(ErrorKind::InvalidInput, e.to_string()),
+        }
+    }
+}
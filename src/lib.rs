@@ -33,4 +33,7 @@ pub mod parser;
 pub mod ply;
 pub mod writer;
 
+mod error;
+pub use crate::error::Error;
+
 mod util;
@@ -1,7 +1,9 @@
 //! Reads ascii or binary data into a `Ply`.
 
 use std::io;
+use std::fs::File;
 use std::io::{ Read, BufReader };
+use std::path::Path;
 use std::fmt::Debug;
 use std::result;
 
@@ -9,9 +11,13 @@ use std::io::{ BufRead, Result, ErrorKind };
 
 mod ply_grammar;
 
+mod ply_error;
+pub use self::ply_error::*;
+
 use self::ply_grammar::grammar;
 use self::ply_grammar::Line;
 use crate::util::LocationTracker;
+use crate::error::Error;
 
 fn parse_ascii_rethrow<T, E: Debug>(location: &LocationTracker, line_str: &str, e: E, message: &str) -> Result<T> {
     Err(io::Error::new(
@@ -19,11 +25,17 @@ fn parse_ascii_rethrow<T, E: Debug>(location: &LocationTracker, line_str: &str,
         format!("Line {}: {}\n\tString: '{}'\n\tError: {:?}", location.line_index, message, line_str, e)
     ))
 }
-fn parse_ascii_error<T>(location: &LocationTracker, line_str: &str, message: &str) -> Result<T> {
-    Err(io::Error::new(
-        ErrorKind::InvalidInput,
-        format!("Line {}: {}\n\tString: '{}'", location.line_index, message, line_str)
-    ))
+fn parse_header_rethrow<T, E: Debug>(location: &LocationTracker, line_str: &str, e: E, message: &str) -> result::Result<T, Error> {
+    Err(Error::InvalidHeader {
+        line: location.line_index,
+        message: format!("{}\n\tString: '{}'\n\tError: {:?}", message, line_str, e),
+    })
+}
+fn parse_header_error<T>(location: &LocationTracker, line_str: &str, message: &str) -> result::Result<T, Error> {
+    Err(Error::InvalidHeader {
+        line: location.line_index,
+        message: format!("{}\n\tString: '{}'", message, line_str),
+    })
 }
 
 use std::marker::PhantomData;
@@ -91,9 +103,44 @@ use std::marker::PhantomData;
 /// ```
 ///
 pub struct Parser<E: PropertyAccess> {
+      options: ParserOptions,
       phantom: PhantomData<E>,
 }
 
+// Not derived, since the derived implementation would require `E: Clone`.
+impl<E: PropertyAccess> Clone for Parser<E> {
+    fn clone(&self) -> Self {
+        Self::with_options(self.options)
+    }
+}
+
+/// Controls how strictly a `Parser` handles malformed payloads.
+///
+/// The default options accept trailing data after the last element and reject everything else.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParserOptions {
+    /// Skips ascii lines that can't be read as the expected element, e.g. elements missing in the header.
+    ///
+    /// A line is only read as the expected element if it consists of exactly the tokens of its properties,
+    /// i.e. one per scalar and the declared number of entries per list, all of which must parse.
+    ///
+    /// Binary payloads have no line structure, hence unknown data can't be detected there.
+    pub skip_unknown_elements: bool,
+    /// Ignores everything after the last element in `read_ply()`.
+    ///
+    /// If disabled, only white space may follow the last element.
+    pub ignore_trailing_garbage: bool,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            skip_unknown_elements: false,
+            ignore_trailing_garbage: true,
+        }
+    }
+}
+
 
 //use std::marker::PhantomData;
 //use std::io::{ Read, BufReader };
@@ -105,25 +152,88 @@ impl<E: PropertyAccess> Parser<E> {
     ///
     /// To get started quickly try `DefaultElement` from the `ply` module.
     pub fn new() -> Self {
+        Self::with_options(ParserOptions::default())
+    }
+    /// Creates a new `Parser<E>` with the given `options`.
+    pub fn with_options(options: ParserOptions) -> Self {
         Parser {
+            options,
             phantom: PhantomData
         }
     }
+    /// Creates a new `Parser<E>` tolerating unknown elements and trailing data.
+    pub fn lenient() -> Self {
+        Self::with_options(ParserOptions {
+            skip_unknown_elements: true,
+            ignore_trailing_garbage: true,
+        })
+    }
+    /// Returns the options this parser was created with.
+    pub fn options(&self) -> ParserOptions {
+        self.options
+    }
 
     /// Expects the complete content of a PLY file.
     ///
     /// A PLY file starts with "ply\n". `read_ply` reads until all elements have been read as
     /// defined in the header of the PLY file.
-    pub fn read_ply<T: Read>(&self, source: &mut T) -> Result<Ply<E>> {
+    pub fn read_ply<T: Read>(&self, source: &mut T) -> result::Result<Ply<E>, Error> {
+        self.read_ply_with_filter(source, |_| true)
+    }
+    /// Reads a PLY file like `read_ply()`, but only keeps the elements whose name is accepted by `filter`.
+    ///
+    /// All other elements are skipped without constructing `E`, see `skip_payload_for_element()`.
+    /// The header still declares all elements of the file,
+    /// but the payload only contains entries for the accepted ones.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use ply_rs::*;
+    /// let mut f = std::fs::File::open("example_plys/house_2_ok_little_endian.ply").unwrap();
+    /// let p = parser::Parser::<ply::DefaultElement>::new();
+    /// let ply = p.read_ply_with_filter(&mut f, |name| name == "vertex").unwrap();
+    /// assert!(ply.header.elements.contains_key("face"));
+    /// assert!(ply.payload.contains_key("vertex"));
+    /// assert!(!ply.payload.contains_key("face"));
+    /// ```
+    pub fn read_ply_with_filter<T: Read, F: Fn(&str) -> bool>(&self, source: &mut T, filter: F) -> result::Result<Ply<E>, Error> {
         let mut source = BufReader::new(source);
         let mut location = LocationTracker::new();
         let header = self.__read_header(&mut source, &mut location)?;
-        let payload = self.__read_payload(&mut source, &mut location, &header)?;
+        let mut payload = Payload::new();
+        for (k, e) in &header.elements {
+            if filter(k) {
+                let elems = self.__read_payload_for_element(&mut source, &mut location, e, header.encoding)
+                    .map_err(|err| Error::payload(k, err))?;
+                payload.insert(k.clone(), elems);
+            } else {
+                self.__skip_payload_for_element(&mut source, e, header.encoding)
+                    .map_err(|err| Error::payload(k, err))?;
+                if header.encoding == Encoding::Ascii {
+                    location.line_index += e.count;
+                }
+            }
+        }
+        if !self.options.ignore_trailing_garbage {
+            let mut rest = Vec::new();
+            source.read_to_end(&mut rest)?;
+            if !rest.iter().all(u8::is_ascii_whitespace) {
+                return Err(Error::InvalidPayload {
+                    element: header.elements.keys().next_back().cloned().unwrap_or_default(),
+                    message: format!("Found {} unexpected bytes after the last element.", rest.len()),
+                });
+            }
+        }
         let mut ply = Ply::new();
         ply.header = header;
         ply.payload = payload;
         Ok(ply)
     }
+    /// Opens the file at `path` and reads it, see `read_ply()`.
+    pub fn read_ply_from_path<P: AsRef<Path>>(&self, path: P) -> result::Result<Ply<E>, Error> {
+        self.read_ply(&mut BufReader::new(File::open(path)?))
+    }
 }
 
 // use ply::{ Header, Encoding };
@@ -147,17 +257,28 @@ impl<E: PropertyAccess> Parser<E> {
     ///
     /// A ply file starts with "ply\n". The header and the payload are separated by a line `end_header\n`.
     /// This method reads all headere elemnts up to `end_header`.
-    pub fn read_header<T: BufRead>(&self, reader: &mut T) -> Result<Header> {
+    pub fn read_header<T: BufRead>(&self, reader: &mut T) -> result::Result<Header, Error> {
         let mut line = LocationTracker::new();
         self.__read_header(reader, &mut line)
     }
-    pub fn read_header_line(&self, line: &str) -> Result<Line> {
+    /// Reads only the header from a plain `Read`, like `read_ply()` does for the whole file.
+    ///
+    /// The payload isn't parsed, but `source` is wrapped in a `BufReader`,
+    /// which may consume bytes after `end_header`.
+    /// Use `read_header()` with your own `BufRead` to continue reading the payload.
+    pub fn read_header_only<T: Read>(&self, source: &mut T) -> result::Result<Header, Error> {
+        self.read_header(&mut BufReader::new(source))
+    }
+    /// Parses a single header line.
+    ///
+    /// On failure, the line number of the returned `Error::InvalidHeader` is `0`.
+    pub fn read_header_line(&self, line: &str) -> result::Result<Line, Error> {
         match self.__read_header_line(line) {
             Ok(l) => Ok(l),
-            Err(e) => Err(io::Error::new(
-                ErrorKind::InvalidInput,
-                format!("Couldn't parse line.\n\tString: {}\n\tError: {:?}", line, e)
-            )),
+            Err(e) => Err(Error::InvalidHeader {
+                line: 0,
+                message: format!("Couldn't parse line.\n\tString: {}\n\tError: {:?}", line, e),
+            }),
         }
     }
 
@@ -165,22 +286,25 @@ impl<E: PropertyAccess> Parser<E> {
     fn __read_header_line(&self, line_str: &str) -> result::Result<Line, peg::error::ParseError<peg::str::LineCol>> {
         grammar::line(line_str)
     }
-    fn __read_header<T: BufRead>(&self, reader: &mut T, location: &mut LocationTracker) -> Result<Header> {
+    fn __read_header<T: BufRead>(&self, reader: &mut T, location: &mut LocationTracker) -> result::Result<Header, Error> {
         location.next_line();
         let mut line_str = String::new();
         reader.read_line(&mut line_str)?;
-        match self.__read_header_line(&line_str) {
+        // some tools prepend a UTF-8 byte order mark
+        let magic_str = line_str.strip_prefix('\u{feff}').unwrap_or(&line_str);
+        match self.__read_header_line(magic_str) {
             Ok(Line::MagicNumber) => (),
-            Ok(l) => return parse_ascii_error(location, &line_str, &format!("Expected magic number 'ply', but saw '{:?}'.", l)),
-            Err(e) => return parse_ascii_rethrow(location, &line_str, e, "Expected magic number 'ply'.")
+            Ok(l) => return parse_header_error(location, &line_str, &format!("Expected magic number 'ply', but saw '{:?}'.", l)),
+            Err(e) => return parse_header_rethrow(location, &line_str, e, "Expected magic number 'ply'.")
         }
-        match grammar::line(&line_str) {
-            Err(e) => return Err(io::Error::new(ErrorKind::InvalidInput, e)),
+        match grammar::line(magic_str) {
+            Err(e) => return parse_header_rethrow(location, &line_str, e, "Couldn't parse line."),
             Ok(l @ Line::MagicNumber) => (l),
-            Ok(ob) => return Err(io::Error::new(
-                ErrorKind::InvalidInput,
-                format!("Invalid line encountered. Expected type: 'Line::MagicNumber', found: '{:?}'", ob)
-            )),
+            Ok(ob) => return parse_header_error(
+                location,
+                &line_str,
+                &format!("Invalid line encountered. Expected type: 'Line::MagicNumber', found: '{:?}'", ob)
+            ),
         };
 
         let mut header_form_ver : Option<(Encoding, Version)> = None;
@@ -194,15 +318,15 @@ impl<E: PropertyAccess> Parser<E> {
             let line = self.__read_header_line(&line_str);
 
             match line {
-                Err(e) => return parse_ascii_rethrow(location, &line_str, e, "Couldn't parse line."),
-                Ok(Line::MagicNumber) => return parse_ascii_error(location, &line_str, "Unexpected 'ply' found."),
+                Err(e) => return parse_header_rethrow(location, &line_str, e, "Couldn't parse line."),
+                Ok(Line::MagicNumber) => return parse_header_error(location, &line_str, "Unexpected 'ply' found."),
                 Ok(Line::Format(ref t)) => (
                     if header_form_ver.is_none() {
                         header_form_ver = Some(t.clone());
                     } else {
                         let f = header_form_ver.unwrap();
                         if f != *t {
-                            return parse_ascii_error(
+                            return parse_header_error(
                                 location,
                                 &line_str,
                                 &format!(
@@ -226,7 +350,7 @@ impl<E: PropertyAccess> Parser<E> {
                 },
                 Ok(Line::Property(p)) => (
                     if header_elements.is_empty() {
-                        return parse_ascii_error(
+                        return parse_header_error(
                             location,
                             &line_str,
                             &format!("Property '{:?}' found without preceding element.", p)
@@ -242,10 +366,10 @@ impl<E: PropertyAccess> Parser<E> {
             location.next_line();
         }
         if header_form_ver.is_none() {
-            return Err(io::Error::new(
-                ErrorKind::InvalidInput,
-                "No format line found."
-            ));
+            return Err(Error::InvalidHeader {
+                line: location.line_index,
+                message: "No format line found.".to_string(),
+            });
         }
         let (encoding, version) = header_form_ver.unwrap();
         Ok(Header{
@@ -264,42 +388,173 @@ impl<E: PropertyAccess> Parser<E> {
 
 impl<E: PropertyAccess> Parser<E> {
     /// Reads payload. Encoding is chosen according to the encoding field in `header`.
-    pub fn read_payload<T: BufRead>(&self, reader: &mut T, header: &Header) -> Result<Payload<E>> {
+    pub fn read_payload<T: BufRead>(&self, reader: &mut T, header: &Header) -> result::Result<Payload<E>, Error> {
         let mut location = LocationTracker::new();
         self.__read_payload(reader, &mut location, header)
     }
     /// Reads entire list of elements from payload. Encoding is chosen according to `header`.
     ///
     /// Make sure to read the elements in the order as they are defined in the header.
-    pub fn read_payload_for_element<T: BufRead>(&self, reader: &mut T, element_def: &ElementDef, header: &Header) -> Result<Vec<E>> {
+    pub fn read_payload_for_element<T: BufRead>(&self, reader: &mut T, element_def: &ElementDef, header: &Header) -> result::Result<Vec<E>, Error> {
         let mut location = LocationTracker::new();
-        match header.encoding {
-            Encoding::Ascii => self.__read_ascii_payload_for_element(reader, &mut location, element_def),
-            Encoding::BinaryBigEndian => self.__read_big_endian_payload_for_element(reader, &mut location, element_def),
-            Encoding::BinaryLittleEndian => self.__read_little_endian_payload_for_element(reader, &mut location, element_def),
+        self.__read_payload_for_element(reader, &mut location, element_def, header.encoding)
+            .map_err(|e| Error::payload(&element_def.name, e))
+    }
+    fn __read_payload_for_element<T: BufRead>(&self, reader: &mut T, location: &mut LocationTracker, element_def: &ElementDef, encoding: Encoding) -> Result<Vec<E>> {
+        match encoding {
+            Encoding::Ascii => self.__read_ascii_payload_for_element(reader, location, element_def),
+            Encoding::BinaryBigEndian => self.__read_big_endian_payload_for_element(reader, location, element_def),
+            Encoding::BinaryLittleEndian => self.__read_little_endian_payload_for_element(reader, location, element_def),
         }
     }
     /// internal dispatcher based on the encoding
-    fn __read_payload<T: BufRead>(&self, reader: &mut T, location: &mut LocationTracker, header: &Header) -> Result<Payload<E>> {
+    fn __read_payload<T: BufRead>(&self, reader: &mut T, location: &mut LocationTracker, header: &Header) -> result::Result<Payload<E>, Error> {
         let mut payload = Payload::new();
-        match header.encoding {
-            Encoding::Ascii => for (k, ref e) in &header.elements {
-                let elems = self.__read_ascii_payload_for_element(reader, location, e)?;
-                payload.insert(k.clone(), elems);
-            },
-            Encoding::BinaryBigEndian => for (k, ref e) in &header.elements {
-                let elems = self.__read_big_endian_payload_for_element(reader, location, e)?;
-                payload.insert(k.clone(), elems);
-            },
-            Encoding::BinaryLittleEndian => for (k, ref e) in &header.elements {
-                let elems = self.__read_little_endian_payload_for_element(reader, location, e)?;
-                payload.insert(k.clone(), elems);
-            }
+        for (k, e) in &header.elements {
+            let elems = self.__read_payload_for_element(reader, location, e, header.encoding)
+                .map_err(|err| Error::payload(k, err))?;
+            payload.insert(k.clone(), elems);
         }
         Ok(payload)
     }
 }
 
+// ////////////////////////
+/// # Streaming
+// //////////////////////
+impl<E: PropertyAccess> Parser<E> {
+    /// Reads the elements declared in `element_def` one at a time. Encoding is chosen according to `header`.
+    ///
+    /// In contrast to `read_payload_for_element()`, only a single element is kept in memory,
+    /// which allows processing arbitrarily large files.
+    /// The iterator yields at most `element_def.count` elements.
+    /// After an error has been returned, the iteration ends.
+    ///
+    /// Make sure to read the elements in the order as they are defined in the header,
+    /// and to consume the iterator completely before reading the next element.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use ply_rs::*;
+    /// # let path = "example_plys/house_2_ok_little_endian.ply";
+    /// let f = std::fs::File::open(path).unwrap();
+    /// let mut buf_read = std::io::BufReader::new(f);
+    /// let p = parser::Parser::<ply::DefaultElement>::new();
+    /// let header = p.read_header(&mut buf_read).unwrap();
+    ///
+    /// let vertex_def = &header.elements["vertex"];
+    /// for vertex in p.iter_payload_for_element(&mut buf_read, vertex_def, &header) {
+    ///     let vertex = vertex.unwrap();
+    ///     // process the vertex ...
+    /// #   assert!(vertex.contains_key("x"));
+    /// }
+    /// ```
+    pub fn iter_payload_for_element<'a, T: BufRead>(&'a self, reader: &'a mut T, element_def: &'a ElementDef, header: &Header) -> impl Iterator<Item = result::Result<E, Error>> + 'a {
+        let encoding = header.encoding;
+        let mut location = LocationTracker::new();
+        let mut line_str = String::new();
+        let mut remaining = element_def.count;
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            let element = match encoding {
+                Encoding::Ascii => self.__read_ascii_element_line(reader, &mut location, &mut line_str, element_def),
+                Encoding::BinaryBigEndian => Self::__read_binary_element::<T, BigEndian>(reader, element_def),
+                Encoding::BinaryLittleEndian => Self::__read_binary_element::<T, LittleEndian>(reader, element_def),
+            };
+            remaining = if element.is_ok() { remaining - 1 } else { 0 };
+            Some(element.map_err(|e| Error::payload(&element_def.name, e)))
+        })
+    }
+    /// Advances `reader` past all elements declared in `element_def` without parsing them.
+    ///
+    /// Ascii elements are skipped line by line, binary elements by their byte size,
+    /// only the lengths of lists are read.
+    /// Make sure to skip the elements in the order as they are defined in the header.
+    pub fn skip_payload_for_element<T: BufRead>(&self, reader: &mut T, element_def: &ElementDef, header: &Header) -> result::Result<(), Error> {
+        self.__skip_payload_for_element(reader, element_def, header.encoding)
+            .map_err(|e| Error::payload(&element_def.name, e))
+    }
+    fn __skip_payload_for_element<T: BufRead>(&self, reader: &mut T, element_def: &ElementDef, encoding: Encoding) -> Result<()> {
+        match encoding {
+            Encoding::Ascii => skip_lines(reader, element_def.count),
+            Encoding::BinaryBigEndian => skip_binary_elements::<T, BigEndian>(reader, element_def),
+            Encoding::BinaryLittleEndian => skip_binary_elements::<T, LittleEndian>(reader, element_def),
+        }
+    }
+}
+
+fn skip_lines<T: BufRead>(reader: &mut T, count: usize) -> Result<()> {
+    let mut line = Vec::new();
+    for _ in 0..count {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, "Reached end of file while skipping elements."));
+        }
+    }
+    Ok(())
+}
+
+fn skip_bytes<T: BufRead>(reader: &mut T, count: u64) -> Result<()> {
+    if io::copy(&mut reader.take(count), &mut io::sink())? < count {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, "Reached end of file while skipping elements."));
+    }
+    Ok(())
+}
+
+fn skip_overflow_error() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "Size of elements to skip exceeds the addressable range.")
+}
+
+fn non_negative_count(count: i64) -> Result<u64> {
+    if count < 0 {
+        return Err(io::Error::new(ErrorKind::InvalidData, format!("Found negative list length {}.", count)));
+    }
+    Ok(count as u64)
+}
+
+fn list_count(count: u64) -> Result<usize> {
+    usize::try_from(count).map_err(|_| io::Error::new(ErrorKind::InvalidData, format!("List length {} exceeds the addressable range.", count)))
+}
+
+fn skip_binary_elements<T: BufRead, B: ByteOrder>(reader: &mut T, element_def: &ElementDef) -> Result<()> {
+    if !element_def.properties.values().any(|p| p.data_type.is_list()) {
+        let bytes = element_def.count.checked_mul(element_def.min_binary_bytes_per_element())
+            .ok_or_else(skip_overflow_error)?;
+        return skip_bytes(reader, bytes as u64);
+    }
+    for _ in 0..element_def.count {
+        skip_binary_element::<T, B>(reader, element_def)?;
+    }
+    Ok(())
+}
+
+fn skip_binary_element<T: BufRead, B: ByteOrder>(reader: &mut T, element_def: &ElementDef) -> Result<()> {
+    for p in element_def.properties.values() {
+        match p.data_type {
+            PropertyType::Scalar(ref t) => skip_bytes(reader, t.byte_width() as u64)?,
+            PropertyType::List(ref index_type, ref t) => {
+                let count = match *index_type {
+                    ScalarType::Char => non_negative_count(reader.read_i8()? as i64)?,
+                    ScalarType::UChar => reader.read_u8()? as u64,
+                    ScalarType::Short => non_negative_count(reader.read_i16::<B>()? as i64)?,
+                    ScalarType::UShort => reader.read_u16::<B>()? as u64,
+                    ScalarType::Int => non_negative_count(reader.read_i32::<B>()? as i64)?,
+                    ScalarType::UInt => reader.read_u32::<B>()? as u64,
+                    ScalarType::Long => non_negative_count(reader.read_i64::<B>()?)?,
+                    ScalarType::ULong => reader.read_u64::<B>()?,
+                    ScalarType::Float | ScalarType::Double => return Err(io::Error::new(ErrorKind::InvalidInput, "Index of list must be an integer type.")),
+                };
+                let bytes = count.checked_mul(t.byte_width() as u64).ok_or_else(skip_overflow_error)?;
+                skip_bytes(reader, bytes)?;
+            },
+        }
+    }
+    Ok(())
+}
+
 
 
 // ////////////////////////////////////////////////////////////////
@@ -316,6 +571,7 @@ use util::LocationTracker;
 use ply::{ PropertyAccess, ElementDef };
 // */
 
+use std::iter::Enumerate;
 use std::slice::Iter;
 use std::str::FromStr;
 
@@ -329,22 +585,45 @@ impl<E: PropertyAccess> Parser<E> {
         let mut elems = Vec::<E>::new();
         let mut line_str = String::new();
         for _ in 0..element_def.count {
-            line_str.clear();
-            reader.read_line(&mut line_str)?;
-
-            let element = match self.read_ascii_element(&line_str, element_def) {
-                Ok(e) => e,
-                Err(e) => return parse_ascii_rethrow(location, &line_str, e, "Couln't read element line.")
-            };
+            let element = self.__read_ascii_element_line(reader, location, &mut line_str, element_def)?;
             elems.push(element);
-            location.next_line();
         }
         Ok(elems)
     }
+    /// Reads lines until one can be read as element, only skips lines if `skip_unknown_elements` is set.
+    fn __read_ascii_element_line<T: BufRead>(&self, reader: &mut T, location: &mut LocationTracker, line_str: &mut String, element_def: &ElementDef) -> Result<E> {
+        loop {
+            line_str.clear();
+            let eof = reader.read_line(line_str)? == 0;
+            match self.__read_ascii_element(line_str, element_def, self.options.skip_unknown_elements) {
+                Ok(e) => {
+                    location.next_line();
+                    return Ok(e);
+                },
+                Err(_) if self.options.skip_unknown_elements && !eof => location.next_line(),
+                Err(_) if self.options.skip_unknown_elements => return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("Reached end of file while looking for element `{}`.", element_def.name)
+                )),
+                Err(e) => return parse_ascii_rethrow(location, line_str, e, "Couln't read element line."),
+            }
+        }
+    }
     /// Read a single element. Assume it is encoded in ascii.
     ///
     /// Make sure all elements are parsed in the order they are defined in the header.
-    pub fn read_ascii_element(&self, line: &str, element_def: &ElementDef) -> Result<E> {
+    pub fn read_ascii_element(&self, line: &str, element_def: &ElementDef) -> result::Result<E, Error> {
+        self.__read_ascii_element(line, element_def, false).map_err(|e| Error::payload(&element_def.name, e))
+    }
+    /// Read a single element from a line that has already been split into tokens. Assume it is encoded in ascii.
+    ///
+    /// Bypasses the grammar, hence the tokens are only checked by parsing them into their declared types.
+    /// Tokens beyond the last property are ignored.
+    pub fn read_ascii_element_from_tokens(&self, tokens: &[&str], element_def: &ElementDef) -> result::Result<E, Error> {
+        self.__read_ascii_tokens(tokens, element_def, false).map_err(|e| Error::payload(&element_def.name, e))
+    }
+    /// Reads the element from `line`, with `exact` set, tokens beyond the last property are an error.
+    fn __read_ascii_element(&self, line: &str, element_def: &ElementDef, exact: bool) -> Result<E> {
         let elems = match grammar::data_line(line) {
             Ok(e) => e,
             Err(ref e) => return Err(io::Error::new(
@@ -352,37 +631,48 @@ impl<E: PropertyAccess> Parser<E> {
                     format!("Couldn't parse element line.\n\tString: '{}'\n\tError: {}", line, e)
                 )),
         };
-
-        let mut elem_it : Iter<String> = elems.iter();
+        self.__read_ascii_tokens(&elems, element_def, exact)
+    }
+    fn __read_ascii_tokens<S: AsRef<str>>(&self, tokens: &[S], element_def: &ElementDef, exact: bool) -> Result<E> {
+        let mut elem_it : Enumerate<Iter<S>> = tokens.iter().enumerate();
         let mut vals = E::new();
         for (k, p) in &element_def.properties {
-            let new_p : Property = self.__read_ascii_property(&mut elem_it, &p.data_type)?;
+            let new_p : Property = self.__read_ascii_property(&mut elem_it, &p.data_type)
+                .map_err(|e| io::Error::new(e.kind(), format!("Property `{}`: {}", k, e)))?;
             vals.set_property(k.clone(), new_p);
         }
+        if exact && elem_it.len() > 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("Found {} tokens after the last property.", elem_it.len())
+            ));
+        }
         Ok(vals)
     }
-    fn __read_ascii_property(&self, elem_iter: &mut Iter<String>, data_type: &PropertyType) -> Result<Property> {
-        let s : &String = match elem_iter.next() {
+    fn __read_ascii_property<S: AsRef<str>>(&self, elem_iter: &mut Enumerate<Iter<S>>, data_type: &PropertyType) -> Result<Property> {
+        let (i, s) : (usize, &str) = match elem_iter.next() {
             None => return Err(io::Error::new(
                 ErrorKind::InvalidInput,
                 format!("Expected element of type '{:?}', but found nothing.", data_type)
             )),
-            Some(x) => x
+            Some((i, x)) => (i, x.as_ref())
         };
 
         let result = match *data_type {
             PropertyType::Scalar(ref scalar_type) => match *scalar_type {
-                ScalarType::Char => Property::Char(self.parse(s)?),
-                ScalarType::UChar => Property::UChar(self.parse(s)?),
-                ScalarType::Short => Property::Short(self.parse(s)?),
-                ScalarType::UShort => Property::UShort(self.parse(s)?),
-                ScalarType::Int => Property::Int(self.parse(s)?),
-                ScalarType::UInt => Property::UInt(self.parse(s)?),
-                ScalarType::Float => Property::Float(self.parse(s)?),
-                ScalarType::Double => Property::Double(self.parse(s)?),
+                ScalarType::Char => Property::Char(self.parse(i, s)?),
+                ScalarType::UChar => Property::UChar(self.parse(i, s)?),
+                ScalarType::Short => Property::Short(self.parse(i, s)?),
+                ScalarType::UShort => Property::UShort(self.parse(i, s)?),
+                ScalarType::Int => Property::Int(self.parse(i, s)?),
+                ScalarType::UInt => Property::UInt(self.parse(i, s)?),
+                ScalarType::Long => Property::Long(self.parse(i, s)?),
+                ScalarType::ULong => Property::ULong(self.parse(i, s)?),
+                ScalarType::Float => Property::Float(self.parse(i, s)?),
+                ScalarType::Double => Property::Double(self.parse(i, s)?),
             },
             PropertyType::List(_, ref scalar_type) => {
-                let count : usize = self.parse(s)?;
+                let count : usize = self.parse(i, s)?;
                 match *scalar_type {
                     ScalarType::Char => Property::ListChar(self.__read_ascii_list(elem_iter, count)?),
                     ScalarType::UChar => Property::ListUChar(self.__read_ascii_list(elem_iter, count)?),
@@ -390,6 +680,8 @@ impl<E: PropertyAccess> Parser<E> {
                     ScalarType::UShort => Property::ListUShort(self.__read_ascii_list(elem_iter, count)?),
                     ScalarType::Int => Property::ListInt(self.__read_ascii_list(elem_iter, count)?),
                     ScalarType::UInt => Property::ListUInt(self.__read_ascii_list(elem_iter, count)?),
+                    ScalarType::Long => Property::ListLong(self.__read_ascii_list(elem_iter, count)?),
+                    ScalarType::ULong => Property::ListULong(self.__read_ascii_list(elem_iter, count)?),
                     ScalarType::Float => Property::ListFloat(self.__read_ascii_list(elem_iter, count)?),
                     ScalarType::Double => Property::ListDouble(self.__read_ascii_list(elem_iter, count)?),
                 }
@@ -398,27 +690,28 @@ impl<E: PropertyAccess> Parser<E> {
         Ok(result)
     }
 
-    fn parse<D: FromStr>(&self, s: &str) -> Result<D>
+    /// Parses the token `s`, `index` is its zero based position in the line.
+    fn parse<D: FromStr>(&self, index: usize, s: &str) -> Result<D>
     where <D as FromStr>::Err: error::Error + Send + Sync + 'static {
         let v = s.parse();
         match v {
             Ok(r) => Ok(r),
             Err(e) => Err(io::Error::new(ErrorKind::InvalidInput,
-                format!("Parse error.\n\tValue: '{}'\n\tError: {:?}, ", s, e))),
+                format!("Parse error at token {}.\n\tValue: '{}'\n\tError: {:?}, ", index + 1, s, e))),
         }
     }
-    fn __read_ascii_list<D: FromStr>(&self, elem_iter: &mut Iter<String>, count: usize) -> Result<Vec<D>>
+    fn __read_ascii_list<S: AsRef<str>, D: FromStr>(&self, elem_iter: &mut Enumerate<Iter<S>>, count: usize) -> Result<Vec<D>>
         where <D as FromStr>::Err: error::Error + marker::Send + marker::Sync + 'static {
         let mut list = Vec::<D>::new();
         for i in 0..count {
-            let s : &String = match elem_iter.next() {
+            let (index, s) : (usize, &str) = match elem_iter.next() {
                 None => return Err(io::Error::new(
                     ErrorKind::InvalidInput,
                     format!("Couldn't find a list element at index {}.", i)
                 )),
-                Some(x) => x
+                Some((index, x)) => (index, x.as_ref())
             };
-            let value : D = self.parse(s)?;
+            let value : D = self.parse(index, s)?;
             list.push(value);
         }
         Ok(list)
@@ -439,6 +732,7 @@ use util::LocationTracker;
 use super::Parser;
 */
 use byteorder::{ BigEndian, LittleEndian, ReadBytesExt, ByteOrder };
+use std::convert::TryFrom;
 use peg;
 
 /// # Binary
@@ -446,16 +740,16 @@ impl<E: PropertyAccess> Parser<E> {
     /// Reads a single element as declared in èlement_def. Assumes big endian encoding.
     ///
     /// Make sure all elements are parsed in the order they are defined in the header.
-    pub fn read_big_endian_element<T: Read>(&self, reader: &mut T, element_def: &ElementDef) -> Result<E> {
+    pub fn read_big_endian_element<T: Read>(&self, reader: &mut T, element_def: &ElementDef) -> result::Result<E, Error> {
         // Reduce coupling with ByteOrder
-        self.__read_binary_element::<T, BigEndian>(reader, element_def)
+        Self::__read_binary_element::<T, BigEndian>(reader, element_def).map_err(|e| Error::payload(&element_def.name, e))
     }
     /// Reads a single element as declared in èlement_def. Assumes big endian encoding.
     ///
     /// Make sure all elements are parsed in the order they are defined in the header.
-    pub fn read_little_endian_element<T: Read>(&self, reader: &mut T, element_def: &ElementDef) -> Result<E> {
+    pub fn read_little_endian_element<T: Read>(&self, reader: &mut T, element_def: &ElementDef) -> result::Result<E, Error> {
         // Reduce coupling with ByteOrder
-        self.__read_binary_element::<T, LittleEndian>(reader, element_def)
+        Self::__read_binary_element::<T, LittleEndian>(reader, element_def).map_err(|e| Error::payload(&element_def.name, e))
     }
 
     /// internal wrapper
@@ -469,22 +763,22 @@ impl<E: PropertyAccess> Parser<E> {
     fn __read_binary_payload_for_element<T: Read, B: ByteOrder>(&self, reader: &mut T, location: &mut LocationTracker, element_def: &ElementDef) -> Result<Vec<E>> {
         let mut elems = Vec::<E>::new();
         for _ in 0..element_def.count {
-            let element = self.__read_binary_element::<T, B>(reader, element_def)?;
+            let element = Self::__read_binary_element::<T, B>(reader, element_def)?;
             elems.push(element);
             location.next_line();
         }
         Ok(elems)
     }
-    fn __read_binary_element<T: Read, B: ByteOrder>(&self, reader: &mut T, element_def: &ElementDef) -> Result<E> {
+    fn __read_binary_element<T: Read, B: ByteOrder>(reader: &mut T, element_def: &ElementDef) -> Result<E> {
         let mut raw_element = E::new();
 
         for (k, p) in &element_def.properties {
-            let property = self.__read_binary_property::<T, B>(reader, &p.data_type)?;
+            let property = Self::__read_binary_property::<T, B>(reader, &p.data_type)?;
             raw_element.set_property(k.clone(), property);
         }
         Ok(raw_element)
     }
-    fn __read_binary_property<T: Read, B: ByteOrder>(&self, reader: &mut T, data_type: &PropertyType) -> Result<Property> {
+    fn __read_binary_property<T: Read, B: ByteOrder>(reader: &mut T, data_type: &PropertyType) -> Result<Property> {
         let result = match *data_type {
             PropertyType::Scalar(ref scalar_type) => match *scalar_type {
                 ScalarType::Char => Property::Char(reader.read_i8()?),
@@ -493,35 +787,41 @@ impl<E: PropertyAccess> Parser<E> {
                 ScalarType::UShort => Property::UShort(reader.read_u16::<B>()?),
                 ScalarType::Int => Property::Int(reader.read_i32::<B>()?),
                 ScalarType::UInt => Property::UInt(reader.read_u32::<B>()?),
+                ScalarType::Long => Property::Long(reader.read_i64::<B>()?),
+                ScalarType::ULong => Property::ULong(reader.read_u64::<B>()?),
                 ScalarType::Float => Property::Float(reader.read_f32::<B>()?),
                 ScalarType::Double => Property::Double(reader.read_f64::<B>()?),
             },
             PropertyType::List(ref index_type, ref property_type) => {
                 let count : usize = match *index_type {
-                    ScalarType::Char => reader.read_i8()? as usize,
+                    ScalarType::Char => list_count(non_negative_count(reader.read_i8()? as i64)?)?,
                     ScalarType::UChar => reader.read_u8()? as usize,
-                    ScalarType::Short => reader.read_i16::<B>()? as usize,
+                    ScalarType::Short => list_count(non_negative_count(reader.read_i16::<B>()? as i64)?)?,
                     ScalarType::UShort => reader.read_u16::<B>()? as usize,
-                    ScalarType::Int => reader.read_i32::<B>()? as usize,
-                    ScalarType::UInt => reader.read_u32::<B>()? as usize,
+                    ScalarType::Int => list_count(non_negative_count(reader.read_i32::<B>()? as i64)?)?,
+                    ScalarType::UInt => list_count(reader.read_u32::<B>()? as u64)?,
+                    ScalarType::Long => list_count(non_negative_count(reader.read_i64::<B>()?)?)?,
+                    ScalarType::ULong => list_count(reader.read_u64::<B>()?)?,
                     ScalarType::Float => return Err(io::Error::new(ErrorKind::InvalidInput, "Index of list must be an integer type, float declared in ScalarType.")),
                     ScalarType::Double => return Err(io::Error::new(ErrorKind::InvalidInput, "Index of list must be an integer type, double declared in ScalarType.")),
                 };
                 match *property_type {
-                    ScalarType::Char => Property::ListChar(self.__read_binary_list(reader, &|r| r.read_i8(), count)?),
-                    ScalarType::UChar => Property::ListUChar(self.__read_binary_list(reader, &|r| r.read_u8(), count)?),
-                    ScalarType::Short => Property::ListShort(self.__read_binary_list(reader, &|r| r.read_i16::<B>(), count)?),
-                    ScalarType::UShort => Property::ListUShort(self.__read_binary_list(reader, &|r| r.read_u16::<B>(), count)?),
-                    ScalarType::Int => Property::ListInt(self.__read_binary_list(reader, &|r| r.read_i32::<B>(), count)?),
-                    ScalarType::UInt => Property::ListUInt(self.__read_binary_list(reader, &|r| r.read_u32::<B>(), count)?),
-                    ScalarType::Float => Property::ListFloat(self.__read_binary_list(reader, &|r| r.read_f32::<B>(), count)?),
-                    ScalarType::Double => Property::ListDouble(self.__read_binary_list(reader, &|r| r.read_f64::<B>(), count)?),
+                    ScalarType::Char => Property::ListChar(Self::__read_binary_list(reader, &|r| r.read_i8(), count)?),
+                    ScalarType::UChar => Property::ListUChar(Self::__read_binary_list(reader, &|r| r.read_u8(), count)?),
+                    ScalarType::Short => Property::ListShort(Self::__read_binary_list(reader, &|r| r.read_i16::<B>(), count)?),
+                    ScalarType::UShort => Property::ListUShort(Self::__read_binary_list(reader, &|r| r.read_u16::<B>(), count)?),
+                    ScalarType::Int => Property::ListInt(Self::__read_binary_list(reader, &|r| r.read_i32::<B>(), count)?),
+                    ScalarType::UInt => Property::ListUInt(Self::__read_binary_list(reader, &|r| r.read_u32::<B>(), count)?),
+                    ScalarType::Long => Property::ListLong(Self::__read_binary_list(reader, &|r| r.read_i64::<B>(), count)?),
+                    ScalarType::ULong => Property::ListULong(Self::__read_binary_list(reader, &|r| r.read_u64::<B>(), count)?),
+                    ScalarType::Float => Property::ListFloat(Self::__read_binary_list(reader, &|r| r.read_f32::<B>(), count)?),
+                    ScalarType::Double => Property::ListDouble(Self::__read_binary_list(reader, &|r| r.read_f64::<B>(), count)?),
                 }
             }
         };
         Ok(result)
     }
-    fn __read_binary_list<T: Read, D: FromStr>(&self, reader: &mut T, read_from: &dyn Fn(&mut T) -> Result<D>, count: usize) -> Result<Vec<D>>
+    fn __read_binary_list<T: Read, D: FromStr>(reader: &mut T, read_from: &dyn Fn(&mut T) -> Result<D>, count: usize) -> Result<Vec<D>>
         where <D as FromStr>::Err: error::Error + marker::Send + marker::Sync + 'static {
         let mut list = Vec::<D>::new();
         for i in 0..count {
@@ -539,13 +839,107 @@ impl<E: PropertyAccess> Parser<E> {
 }
 
 
+// //////////////////////
+/// # Typed errors
+// //////////////////////
+impl<E: PropertyAccess> Parser<E> {
+    /// Same as `read_ply()`, but distinguishes IO errors from malformed input.
+    pub fn read_ply_typed<T: Read>(&self, source: &mut T) -> result::Result<Ply<E>, PlyError> {
+        Ok(self.read_ply(source)?)
+    }
+    /// Same as `read_ply_from_path()`, but distinguishes IO errors from malformed input.
+    pub fn read_ply_from_path_typed<P: AsRef<Path>>(&self, path: P) -> result::Result<Ply<E>, PlyError> {
+        Ok(self.read_ply_from_path(path)?)
+    }
+    /// Same as `read_ply_with_filter()`, but distinguishes IO errors from malformed input.
+    pub fn read_ply_with_filter_typed<T: Read, F: Fn(&str) -> bool>(&self, source: &mut T, filter: F) -> result::Result<Ply<E>, PlyError> {
+        Ok(self.read_ply_with_filter(source, filter)?)
+    }
+    /// Same as `read_header()`, but distinguishes IO errors from malformed input.
+    pub fn read_header_typed<T: BufRead>(&self, reader: &mut T) -> result::Result<Header, PlyError> {
+        Ok(self.read_header(reader)?)
+    }
+    /// Same as `read_header_line()`, but distinguishes IO errors from malformed input.
+    pub fn read_header_line_typed(&self, line: &str) -> result::Result<Line, PlyError> {
+        Ok(self.read_header_line(line)?)
+    }
+    /// Same as `read_payload()`, but distinguishes IO errors from malformed input.
+    pub fn read_payload_typed<T: BufRead>(&self, reader: &mut T, header: &Header) -> result::Result<Payload<E>, PlyError> {
+        Ok(self.read_payload(reader, header)?)
+    }
+    /// Same as `read_payload_for_element()`, but distinguishes IO errors from malformed input.
+    pub fn read_payload_for_element_typed<T: BufRead>(&self, reader: &mut T, element_def: &ElementDef, header: &Header) -> result::Result<Vec<E>, PlyError> {
+        Ok(self.read_payload_for_element(reader, element_def, header)?)
+    }
+    /// Same as `skip_payload_for_element()`, but distinguishes IO errors from malformed input.
+    pub fn skip_payload_for_element_typed<T: BufRead>(&self, reader: &mut T, element_def: &ElementDef, header: &Header) -> result::Result<(), PlyError> {
+        Ok(self.skip_payload_for_element(reader, element_def, header)?)
+    }
+    /// Same as `read_ascii_element()`, but distinguishes IO errors from malformed input.
+    pub fn read_ascii_element_typed(&self, line: &str, element_def: &ElementDef) -> result::Result<E, PlyError> {
+        Ok(self.read_ascii_element(line, element_def)?)
+    }
+    /// Same as `read_ascii_element_from_tokens()`, but distinguishes IO errors from malformed input.
+    pub fn read_ascii_element_from_tokens_typed(&self, tokens: &[&str], element_def: &ElementDef) -> result::Result<E, PlyError> {
+        Ok(self.read_ascii_element_from_tokens(tokens, element_def)?)
+    }
+    /// Same as `read_big_endian_element()`, but distinguishes IO errors from malformed input.
+    pub fn read_big_endian_element_typed<T: Read>(&self, reader: &mut T, element_def: &ElementDef) -> result::Result<E, PlyError> {
+        Ok(self.read_big_endian_element(reader, element_def)?)
+    }
+    /// Same as `read_little_endian_element()`, but distinguishes IO errors from malformed input.
+    pub fn read_little_endian_element_typed<T: Read>(&self, reader: &mut T, element_def: &ElementDef) -> result::Result<E, PlyError> {
+        Ok(self.read_little_endian_element(reader, element_def)?)
+    }
+}
+
+// //////////////////////
+/// # Parallel
+// //////////////////////
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+impl<E: PropertyAccess + Send> Parser<E> {
+    /// Decodes all elements declared in `element_def` from a big endian payload in parallel.
+    ///
+    /// `data` must start with the first element, trailing data is ignored.
+    /// The element boundaries are determined sequentially, only the lengths of lists are read.
+    /// Afterwards, the elements are decoded concurrently.
+    ///
+    /// Requires the `rayon` feature.
+    pub fn read_big_endian_payload_parallel(&self, data: &[u8], element_def: &ElementDef) -> result::Result<Vec<E>, Error> {
+        self.__read_binary_payload_parallel::<BigEndian>(data, element_def)
+            .map_err(|e| Error::payload(&element_def.name, e))
+    }
+    /// Decodes all elements declared in `element_def` from a little endian payload in parallel.
+    ///
+    /// See `read_big_endian_payload_parallel()`.
+    pub fn read_little_endian_payload_parallel(&self, data: &[u8], element_def: &ElementDef) -> result::Result<Vec<E>, Error> {
+        self.__read_binary_payload_parallel::<LittleEndian>(data, element_def)
+            .map_err(|e| Error::payload(&element_def.name, e))
+    }
+    fn __read_binary_payload_parallel<B: ByteOrder>(&self, data: &[u8], element_def: &ElementDef) -> Result<Vec<E>> {
+        let mut bounds = Vec::with_capacity(element_def.count.min(data.len()) + 1);
+        let mut rest = data;
+        for _ in 0..element_def.count {
+            bounds.push(data.len() - rest.len());
+            skip_binary_element::<&[u8], B>(&mut rest, element_def)?;
+        }
+        bounds.push(data.len() - rest.len());
+        bounds.par_windows(2)
+            .map(|w| Self::__read_binary_element::<&[u8], B>(&mut &data[w[0]..w[1]], element_def))
+            .collect()
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::grammar as g;
     use super::Line;
     use crate::parser::Parser;
-    use crate::ply::{ DefaultElement, PropertyDef, Version, Encoding, ScalarType, PropertyType, ElementDef, KeyMap, Addable };
+    use crate::ply::{ DefaultElement, PropertyDef, Version, Encoding, ScalarType, PropertyType, ElementDef, KeyMap, Addable, Property };
     macro_rules! assert_ok {
         ($e:expr) => (
             match $e {
@@ -599,6 +993,15 @@ mod tests {
         assert_ok!(p.read_header(&mut bytes));
     }
     #[test]
+    fn parser_header_bom_ok() {
+        let txt = "\u{feff}ply\nformat ascii 1.0\nelement point 1\nproperty int x\nend_header\n3\n";
+        assert_eq!(&txt.as_bytes()[..3], &[0xEF, 0xBB, 0xBF]);
+        let p = Parser::<DefaultElement>::new();
+        let ply = p.read_ply(&mut txt.as_bytes()).unwrap();
+        assert_eq!(ply.payload["point"][0]["x"], Property::Int(3));
+        assert!(p.read_ply(&mut "\u{feff}\u{feff}ply\n".as_bytes()).is_err());
+    }
+    #[test]
     fn parser_single_elements_ok(){
         let txt = "ply\r\n\
         format ascii 1.0\r\n\
@@ -630,6 +1033,34 @@ mod tests {
         assert!(properties.is_ok(), format!("error: {:?}", properties));
     }
     #[test]
+    fn read_tokens_equal() {
+        let p = Parser::<DefaultElement>::new();
+        let mut elem_def = ElementDef::new("face".to_string());
+        elem_def.add_property("flag", PropertyType::Scalar(ScalarType::UChar))
+            .add_property("vertex_indices", PropertyType::List(ScalarType::UChar, ScalarType::Int));
+        let line = "1 3 0 -1 2";
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let element = p.read_ascii_element_from_tokens(&tokens, &elem_def).unwrap();
+        assert_eq!(element, p.read_ascii_element(line, &elem_def).unwrap());
+        assert!(p.read_ascii_element_from_tokens(&tokens[..4], &elem_def).is_err());
+        assert!(p.read_ascii_element_from_tokens(&["1", "3", "0", "x", "2"], &elem_def).is_err());
+    }
+    #[test]
+    fn read_ascii_error_token() {
+        let txt = "ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\nproperty uchar y\nproperty list uchar int z\nend_header\n\
+            0 1 2 3 4\n0 1 2 3 4.5\n";
+        let p = Parser::<DefaultElement>::new();
+        let message = p.read_ply(&mut txt.as_bytes()).unwrap_err().to_string();
+        assert!(message.contains("Line 9"), "{}", message);
+        assert!(message.contains("Property `z`"), "{}", message);
+        assert!(message.contains("Parse error at token 5."), "{}", message);
+        let mut elem_def = ElementDef::new("vertex".to_string());
+        elem_def.add_property("x", PropertyType::Scalar(ScalarType::Float))
+            .add_property("y", PropertyType::Scalar(ScalarType::UChar));
+        let message = p.read_ascii_element("1.5 300", &elem_def).unwrap_err().to_string();
+        assert!(message.contains("Property `y`: Parse error at token 2."), "{}", message);
+    }
+    #[test]
     fn magic_number_ok() {
         assert_ok!(g::magic_number("ply"));
     }
@@ -718,6 +1149,47 @@ mod tests {
         );
     }
     #[test]
+    fn property_type_aliases_ok() {
+        let aliases = [
+            ("char", ScalarType::Char), ("int8", ScalarType::Char),
+            ("uchar", ScalarType::UChar), ("uint8", ScalarType::UChar),
+            ("short", ScalarType::Short), ("int16", ScalarType::Short),
+            ("ushort", ScalarType::UShort), ("uint16", ScalarType::UShort),
+            ("int", ScalarType::Int), ("int32", ScalarType::Int),
+            ("uint", ScalarType::UInt), ("uint32", ScalarType::UInt),
+            ("int64", ScalarType::Long), ("uint64", ScalarType::ULong),
+            ("float", ScalarType::Float), ("float32", ScalarType::Float),
+            ("double", ScalarType::Double), ("float64", ScalarType::Double),
+        ];
+        for (alias, scalar_type) in aliases {
+            assert_ok!(
+                g::property(&format!("property {} a", alias)),
+                PropertyDef::new("a".to_string(), PropertyType::Scalar(scalar_type.clone()))
+            );
+            assert_ok!(
+                g::property(&format!("property list {} {} a", alias, alias)),
+                PropertyDef::new("a".to_string(), PropertyType::List(scalar_type.clone(), scalar_type))
+            );
+        }
+    }
+    #[test]
+    fn property_type_aliases_err() {
+        assert_err!(g::property("property int128 a"));
+        assert_err!(g::property("property uint7 a"));
+        assert_err!(g::property("property float16 a"));
+    }
+    #[test]
+    fn property_64_bit_ok() {
+        assert_ok!(
+            g::property("property int64 offset"),
+            PropertyDef::new("offset".to_string(), PropertyType::Scalar(ScalarType::Long))
+        );
+        assert_ok!(
+            g::property("property list uchar uint64 id"),
+            PropertyDef::new("id".to_string(), PropertyType::List(ScalarType::UChar, ScalarType::ULong))
+        );
+    }
+    #[test]
     fn property_list_ok() {
         assert_ok!(
             g::property("property list uchar int c"),
@@ -752,11 +1224,57 @@ mod tests {
             vec!["034", "8e3", "8e-3"]
         );
         assert_ok!(g::data_line(""), Vec::<String>::new());
+        assert_ok!(
+            g::data_line("nan NaN inf -inf +INF"),
+            vec!["nan", "NaN", "inf", "-inf", "+INF"]
+        );
+    }
+    #[test]
+    fn data_line_tabs_ok() {
+        assert_ok!(
+            g::data_line("1\t-2.5\t3"),
+            vec!["1", "-2.5", "3"]
+        );
+        assert_ok!(
+            g::data_line("\t4\t\t5 \t6\t\r\n"),
+            vec!["4", "5", "6"]
+        );
+    }
+    #[test]
+    fn read_tab_separated_ok() {
+        let txt = "ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\nproperty float y\n\
+            element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+            0.5\t1\n\t-1\t2\t\n3\t0\t1\t1\n";
+        let p = Parser::<DefaultElement>::new();
+        let ply = p.read_ply(&mut txt.as_bytes()).unwrap();
+        assert_eq!(ply.payload["vertex"][1]["x"], Property::Float(-1.0));
+        assert_eq!(ply.payload["vertex"][1]["y"], Property::Float(2.0));
+        assert_eq!(ply.payload["face"][0]["vertex_indices"], Property::ListInt(vec![0, 1, 1]));
     }
     #[test]
     fn data_line_err() {
         assert_err!(g::data_line("++3"));
         assert_err!(g::data_line("+-3"));
         assert_err!(g::data_line("five"));
+        assert_err!(g::data_line("-nan"));
+        assert_err!(g::data_line("infinite"));
+    }
+    #[test]
+    fn read_non_finite_ok() {
+        let p = Parser::<DefaultElement>::new();
+        let mut elem_def = ElementDef::new("dummy".to_string());
+        elem_def.properties.add(PropertyDef::new("a".to_string(), PropertyType::Scalar(ScalarType::Float)));
+        elem_def.properties.add(PropertyDef::new("b".to_string(), PropertyType::Scalar(ScalarType::Double)));
+        elem_def.properties.add(PropertyDef::new("c".to_string(), PropertyType::List(ScalarType::UChar, ScalarType::Float)));
+        let element = p.read_ascii_element("NaN -inf 2 +Inf nan", &elem_def).unwrap();
+        assert!(match element["a"] { Property::Float(v) => v.is_nan(), _ => false });
+        assert_eq!(element["b"], Property::Double(f64::NEG_INFINITY));
+        match element["c"] {
+            Property::ListFloat(ref l) => assert!(l[0] == f32::INFINITY && l[1].is_nan()),
+            ref p => panic!("Expected ListFloat, got {:?}", p),
+        }
+        let mut int_def = ElementDef::new("dummy".to_string());
+        int_def.properties.add(PropertyDef::new("i".to_string(), PropertyType::Scalar(ScalarType::Int)));
+        assert!(p.read_ascii_element("inf", &int_def).is_err());
     }
 }
@@ -0,0 +1,81 @@
+//! Error types that distinguish malformed input from failing IO.
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::io::ErrorKind;
+use crate::error::Error;
+
+/// The input doesn't conform to the PLY format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlyParseError {
+    pub message: String,
+}
+
+impl fmt::Display for PlyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for PlyParseError {}
+
+/// Error returned by the `_typed` methods of `Parser` and `Writer`.
+///
+/// Every `Error` converts into a `PlyError`, the `_typed` methods only perform this conversion.
+/// While `Error` reports the header line or element of a problem, `PlyError` only tells
+/// whether the IO failed or the data is malformed. In contrast to `Error`, this classification
+/// also counts a truncated input as malformed. Use `Error` for diagnostics and `PlyError` to decide how to react.
+#[derive(Debug)]
+pub enum PlyError {
+    /// Reading from the source or writing to the target failed, e.g. file not found or permission denied.
+    Io(io::Error),
+    /// The data isn't valid PLY, e.g. a malformed header line or a truncated file.
+    ///
+    /// When writing, the `Ply` can't be written as valid PLY, e.g. because it is inconsistent.
+    Parse(PlyParseError),
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PlyError::Io(ref e) => write!(f, "IO error: {}", e),
+            PlyError::Parse(ref e) => write!(f, "Parse error: {}", e),
+        }
+    }
+}
+
+impl error::Error for PlyError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            PlyError::Io(ref e) => Some(e),
+            PlyError::Parse(ref e) => Some(e),
+        }
+    }
+}
+
+/// The parser reports malformed input with `ErrorKind::InvalidInput` or `ErrorKind::InvalidData`.
+/// `ErrorKind::UnexpectedEof` means the input ended before all declared data has been read,
+/// hence it is malformed as well. All other kinds originate from the underlying reader or writer.
+impl From<io::Error> for PlyError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            ErrorKind::InvalidInput | ErrorKind::InvalidData | ErrorKind::UnexpectedEof => PlyError::Parse(PlyParseError {
+                message: e.to_string(),
+            }),
+            _ => PlyError::Io(e),
+        }
+    }
+}
+
+/// IO errors are classified like `io::Error`, all other variants of `Error` become parse errors.
+impl From<Error> for PlyError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::IoError(e) => PlyError::from(e),
+            e => PlyError::Parse(PlyParseError {
+                message: e.to_string(),
+            }),
+        }
+    }
+}
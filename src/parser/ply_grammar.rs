@@ -41,8 +41,10 @@ rule scalar() -> ScalarType
 	/ "uint16"  { ScalarType::UShort }
 	/ "ushort"  { ScalarType::UShort }
 	/ "int32"   { ScalarType::Int }
+	/ "int64"   { ScalarType::Long }
 	/ "int"     { ScalarType::Int }
 	/ "uint32"  { ScalarType::UInt }
+	/ "uint64"  { ScalarType::ULong }
 	/ "uint"    { ScalarType::UInt }
 	/ "float32" { ScalarType::Float }
 	/ "float64" { ScalarType::Double }
@@ -116,6 +118,8 @@ rule trimmed_line() -> Line
 
 rule any_number() -> String
 	= s:$(['-'|'+']? ['0'..='9']+("."['0'..='9']+)?("e"['-'|'+']?['0'..='9']+)?) { s.to_string() }
+	/ s:$(['-'|'+']? ['i'|'I']['n'|'N']['f'|'F']) { s.to_string() }
+	/ s:$(['n'|'N']['a'|'A']['n'|'N']) { s.to_string() }
 
 rule trimmed_data_line() -> Vec<String>
 	= any_number() ** space()
@@ -0,0 +1,70 @@
+//! Centroids of faces.
+
+use std::io::Result;
+use super::{ Ply, PropertyAccess, ElementDef, PropertyDef, PropertyType, ScalarType, Property, Addable };
+use super::mesh::{ add, scale, invalid_input };
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the barycentre of each face, the mean position of its vertices.
+    ///
+    /// Returns one point per face, in the order of the `face` elements.
+    /// Faces without any vertex index result in an error.
+    pub fn compute_face_barycentres(&self) -> Result<Vec<[f64; 3]>> {
+        let mesh = self.read_mesh()?;
+        let mut barycentres = Vec::with_capacity(mesh.faces.len());
+        for (fi, f) in mesh.faces.iter().enumerate() {
+            if f.is_empty() {
+                return invalid_input(&format!("Face {} has no vertices.", fi));
+            }
+            let sum = f.iter().fold([0.0; 3], |s, &v| add(&s, &mesh.vertices[v]));
+            barycentres.push(scale(&sum, 1.0 / f.len() as f64));
+        }
+        Ok(barycentres)
+    }
+    /// Stores the result of `compute_face_barycentres()` as new element `barycentre` with double properties `x`, `y`, and `z`.
+    ///
+    /// An existing `barycentre` element is replaced.
+    pub fn add_face_barycentre_element(&mut self) -> Result<()> {
+        let barycentres = self.compute_face_barycentres()?;
+        let mut def = ElementDef::new("barycentre".to_string());
+        for name in &["x", "y", "z"] {
+            def.properties.add(PropertyDef::new(name.to_string(), PropertyType::Scalar(ScalarType::Double)));
+        }
+        def.count = barycentres.len();
+        self.header.elements.add(def);
+        self.payload.insert("barycentre".to_string(), barycentres.iter().map(|b| {
+            let mut e = E::new();
+            e.set_property("x".to_string(), Property::Double(b[0]));
+            e.set_property("y".to_string(), Property::Double(b[1]));
+            e.set_property("z".to_string(), Property::Double(b[2]));
+            e
+        }).collect());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Property;
+    use super::super::mesh::fixtures;
+    #[test]
+    fn face_barycentres_grid() {
+        let ply = fixtures::grid(1);
+        let b = ply.compute_face_barycentres().unwrap();
+        assert_eq!(b.len(), 2);
+        for (p, e) in b.iter().zip([[2.0 / 3.0, 1.0 / 3.0, 0.0], [1.0 / 3.0, 2.0 / 3.0, 0.0]].iter()) {
+            for k in 0..3 {
+                assert!((p[k] - e[k]).abs() < 1e-12);
+            }
+        }
+    }
+    #[test]
+    fn face_barycentre_element() {
+        let mut ply = fixtures::icosahedron();
+        ply.add_face_barycentre_element().unwrap();
+        assert_eq!(ply.header.elements["barycentre"].count, 20);
+        assert_eq!(ply.payload["barycentre"].len(), 20);
+        assert!(matches!(ply.payload["barycentre"][0]["x"], Property::Double(_)));
+        assert!(ply.make_consistent().is_ok());
+    }
+}
@@ -0,0 +1,55 @@
+//! Axis aligned bounding box of the vertices.
+
+use super::{ Ply, DefaultElement };
+
+impl Ply<DefaultElement> {
+    /// Computes the axis aligned bounding box of all vertices as `(min, max)`.
+    ///
+    /// Reads the properties `x`, `y` and `z` of the `vertex` element, any scalar type is converted to `f64`.
+    /// Returns `None` if there are no vertices or a vertex lacks a scalar coordinate.
+    pub fn vertex_bounding_box(&self) -> Option<([f64; 3], [f64; 3])> {
+        let vertices = self.payload.get("vertex")?;
+        if vertices.is_empty() {
+            return None;
+        }
+        let (mut min, mut max) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for v in vertices {
+            for (k, name) in ["x", "y", "z"].iter().enumerate() {
+                let c = v.get(*name)?.coerce_to_f64()?;
+                min[k] = min[k].min(c);
+                max[k] = max[k].max(c);
+            }
+        }
+        Some((min, max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    use super::super::Property;
+
+    #[test]
+    fn icosahedron_box() {
+        let t = (1.0 + 5f64.sqrt()) / 2.0;
+        let (min, max) = fixtures::icosahedron().vertex_bounding_box().unwrap();
+        assert_eq!(min, [-t, -t, -t]);
+        assert_eq!(max, [t, t, t]);
+    }
+    #[test]
+    fn mixed_types_box() {
+        let mut ply = fixtures::grid(2);
+        ply.payload.get_mut("vertex").unwrap()[0].insert("z".to_string(), Property::Char(-3));
+        assert_eq!(ply.vertex_bounding_box(), Some(([0.0, 0.0, -3.0], [2.0, 2.0, 0.0])));
+    }
+    #[test]
+    fn missing_vertices_none() {
+        let mut ply = fixtures::grid(1);
+        ply.payload.get_mut("vertex").unwrap()[1].remove("y");
+        assert_eq!(ply.vertex_bounding_box(), None);
+        ply.payload.get_mut("vertex").unwrap().clear();
+        assert_eq!(ply.vertex_bounding_box(), None);
+        ply.payload.remove("vertex");
+        assert_eq!(ply.vertex_bounding_box(), None);
+    }
+}
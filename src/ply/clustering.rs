@@ -0,0 +1,133 @@
+//! Spatial clustering of vertices.
+
+use std::io::Result;
+use rand::{ Rng, SeedableRng };
+use rand::rngs::StdRng;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Vec3, add, sub, scale, dot, invalid_input };
+
+fn squared_distance(a: &Vec3, b: &Vec3) -> f64 {
+    let d = sub(a, b);
+    dot(&d, &d)
+}
+
+fn nearest(point: &Vec3, centers: &[Vec3]) -> (usize, f64) {
+    let mut best = (0, f64::INFINITY);
+    for (i, c) in centers.iter().enumerate() {
+        let d = squared_distance(point, c);
+        if d < best.1 {
+            best = (i, d);
+        }
+    }
+    best
+}
+
+/// Chooses `k` initial centers with the k-means++ strategy.
+fn init_centers(points: &[Vec3], k: usize, rng: &mut StdRng) -> Vec<Vec3> {
+    let mut centers = vec![points[rng.gen_range(0..points.len())]];
+    let mut weights: Vec<f64> = points.iter().map(|p| squared_distance(p, &centers[0])).collect();
+    while centers.len() < k {
+        let total: f64 = weights.iter().sum();
+        let next = if total > 0.0 {
+            let mut target = rng.gen_range(0.0..total);
+            let mut chosen = points.len() - 1;
+            for (i, w) in weights.iter().enumerate() {
+                if target < *w {
+                    chosen = i;
+                    break;
+                }
+                target -= w;
+            }
+            chosen
+        } else {
+            // all points coincide with a center
+            rng.gen_range(0..points.len())
+        };
+        centers.push(points[next]);
+        for (w, p) in weights.iter_mut().zip(points.iter()) {
+            *w = w.min(squared_distance(p, &points[next]));
+        }
+    }
+    centers
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Partitions the vertices into `k` clusters by their position.
+    ///
+    /// Uses Lloyd's algorithm with k-means++ initialization.
+    /// Iterates until the assignment is stable or `max_iter` iterations have been performed.
+    /// The same `seed` always produces the same clustering.
+    ///
+    /// Returns the cluster label (`0..k`) of each vertex, in the order of the `vertex` elements.
+    ///
+    /// Requires the `rand` feature.
+    pub fn kmeans_cluster_vertices(&self, k: usize, max_iter: u32, seed: u64) -> Result<Vec<usize>> {
+        let points = self.read_vertex_positions()?;
+        if k == 0 {
+            return invalid_input("Number of clusters must be at least 1.");
+        }
+        if k > points.len() {
+            return invalid_input(&format!("Can't build {} clusters from {} vertices.", k, points.len()));
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut centers = init_centers(&points, k, &mut rng);
+        let mut labels: Vec<usize> = points.iter().map(|p| nearest(p, &centers).0).collect();
+        for _ in 0..max_iter {
+            let mut sums = vec![[0.0; 3]; k];
+            let mut counts = vec![0usize; k];
+            for (p, &l) in points.iter().zip(labels.iter()) {
+                sums[l] = add(&sums[l], p);
+                counts[l] += 1;
+            }
+            for ((center, sum), &count) in centers.iter_mut().zip(sums.iter()).zip(counts.iter()) {
+                // empty clusters keep their previous center
+                if count > 0 {
+                    *center = scale(sum, 1.0 / count as f64);
+                }
+            }
+            let new_labels: Vec<usize> = points.iter().map(|p| nearest(p, &centers).0).collect();
+            if new_labels == labels {
+                break;
+            }
+            labels = new_labels;
+        }
+        Ok(labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn kmeans_two_blobs() {
+        let mut vertices = Vec::new();
+        for i in 0..10 {
+            let o = i as f64 * 0.01;
+            vertices.push([o, o, 0.0]);
+            vertices.push([100.0 + o, 100.0, o]);
+        }
+        let ply = fixtures::from_mesh(&vertices, &[]);
+        let labels = ply.kmeans_cluster_vertices(2, 20, 7).unwrap();
+        assert_eq!(labels.len(), 20);
+        for i in 0..10 {
+            assert_eq!(labels[2 * i], labels[0]);
+            assert_eq!(labels[2 * i + 1], labels[1]);
+        }
+        assert_ne!(labels[0], labels[1]);
+    }
+    #[test]
+    fn kmeans_deterministic() {
+        let ply = fixtures::grid(5);
+        let a = ply.kmeans_cluster_vertices(4, 50, 42).unwrap();
+        let b = ply.kmeans_cluster_vertices(4, 50, 42).unwrap();
+        assert_eq!(a, b);
+        assert!(a.iter().all(|&l| l < 4));
+    }
+    #[test]
+    fn kmeans_too_many_clusters_err() {
+        let ply = fixtures::icosahedron();
+        assert!(ply.kmeans_cluster_vertices(0, 10, 0).is_err());
+        assert!(ply.kmeans_cluster_vertices(13, 10, 0).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Size comparison of the PLY encodings.
+
+use std::io::Result;
+use super::{ Ply, PropertyAccess, Encoding };
+use crate::writer::Writer;
+
+/// Number of bytes needed to write a PLY file in each encoding, see `Ply::measure_compressibility()`.
+///
+/// All sizes include the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressibilityReport {
+    pub ascii_bytes: usize,
+    pub binary_le_bytes: usize,
+    pub binary_be_bytes: usize,
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Writes the PLY file in all three encodings and reports the resulting sizes.
+    ///
+    /// The encoding in the header is restored afterwards, even if writing fails.
+    /// Both binary encodings produce payloads of the same size, they only differ in byte order.
+    /// The little endian file is three bytes larger, since `binary_little_endian` is longer than `binary_big_endian`.
+    pub fn measure_compressibility(&mut self) -> Result<CompressibilityReport> {
+        let original = self.header.encoding;
+        let mut sizes = [0; 3];
+        let encodings = [Encoding::Ascii, Encoding::BinaryLittleEndian, Encoding::BinaryBigEndian];
+        let mut result = Ok(());
+        for (size, encoding) in sizes.iter_mut().zip(encodings.iter()) {
+            self.header.encoding = *encoding;
+            let mut buffer = Vec::new();
+            match Writer::new().write_ply(&mut buffer, self) {
+                Ok(written) => *size = written,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                },
+            }
+        }
+        self.header.encoding = original;
+        result?;
+        Ok(CompressibilityReport {
+            ascii_bytes: sizes[0],
+            binary_le_bytes: sizes[1],
+            binary_be_bytes: sizes[2],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Encoding;
+    use super::super::mesh::fixtures;
+    use crate::writer::Writer;
+    #[test]
+    fn measure_compressibility_sizes() {
+        let mut ply = fixtures::icosahedron();
+        ply.header.encoding = Encoding::BinaryBigEndian;
+        let report = ply.measure_compressibility().unwrap();
+        assert_eq!(ply.header.encoding, Encoding::BinaryBigEndian);
+        assert_eq!(report.binary_le_bytes, report.binary_be_bytes + 3);
+        let mut buffer = Vec::new();
+        Writer::new().write_ply(&mut buffer, &mut ply).unwrap();
+        assert_eq!(buffer.len(), report.binary_be_bytes);
+        ply.header.encoding = Encoding::Ascii;
+        buffer.clear();
+        Writer::new().write_ply(&mut buffer, &mut ply).unwrap();
+        assert_eq!(buffer.len(), report.ascii_bytes);
+    }
+}
@@ -5,6 +5,7 @@ use std::fmt;
 use std::error;
 use super::Ply;
 use super::PropertyAccess;
+use super::{ Addable, ElementDef, PropertyType, ScalarType };
 
 /// Contains a description, why a given `Ply` object isn't consistent and could not be made consistent.
 #[derive(Debug)]
@@ -36,6 +37,28 @@ impl error::Error for ConsistencyError {
     }
 }
 
+/// Controls which problems `make_consistent_with_options()` fixes automatically.
+///
+/// The default options behave like `make_consistent()`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ConsistencyOptions {
+    /// Declares elements found in the payload but not in the header.
+    ///
+    /// The added declaration has no properties, add them afterwards if the elements carry any data.
+    pub auto_add_missing_elements: bool,
+    /// Inserts an empty payload for elements declared in the header but missing in the payload.
+    pub auto_add_missing_payload: bool,
+}
+
+impl Default for ConsistencyOptions {
+    fn default() -> Self {
+        ConsistencyOptions {
+            auto_add_missing_elements: false,
+            auto_add_missing_payload: true,
+        }
+    }
+}
+
 fn has_white_space(s: &str) -> bool {
     return s.contains(" ") || s.contains("\t");
 }
@@ -44,6 +67,16 @@ fn has_line_break(s: &str) -> bool {
     return s.contains("\n") || s.contains("\r");
 }
 
+fn check_element_name(name: &str) -> Result<(), ConsistencyError> {
+    if has_line_break(name) {
+        return Err(ConsistencyError::new(&format!("Name of element `{}` should not contain any line breaks.", name)));
+    }
+    if has_white_space(name) {
+        return Err(ConsistencyError::new(&format!("Name of element `{}` should not contain any white spaces.", name)));
+    }
+    Ok(())
+}
+
 impl<E: PropertyAccess> Ply<E>{
     /// Takes a mutable `Ply` object, performs common operations to make it consistent,
     ///
@@ -61,21 +94,63 @@ impl<E: PropertyAccess> Ply<E>{
     /// For maximal compatability, only ascii characters should be used but this is not checked.
     /// Every relevant string is checked to not contain line breaks.
     /// Identifiers are also checked to not contain white spaces.
+    /// The index types of lists must be integers.
     pub fn make_consistent(&mut self) -> Result<(), ConsistencyError>{
+        self.make_consistent_with_options(ConsistencyOptions::default())
+    }
+    /// Like `make_consistent()`, but `options` decide which missing declarations or payloads are added.
+    ///
+    /// Missing entries that aren't added automatically result in a `ConsistencyError`.
+    pub fn make_consistent_with_options(&mut self, options: ConsistencyOptions) -> Result<(), ConsistencyError>{
+        let mut missing_payload = Vec::new();
         for (ek, _) in &self.header.elements {
             if !self.payload.contains_key(ek) {
-                self.payload.insert(ek.clone(), Vec::new());
+                if !options.auto_add_missing_payload {
+                    return Err(ConsistencyError::new(&format!("No payload for element `{}` found.", ek)));
+                }
+                missing_payload.push(ek.clone());
             }
         }
+        let mut missing_elements = Vec::new();
+        if options.auto_add_missing_elements {
+            for (pk, _) in &self.payload {
+                if !self.header.elements.contains_key(pk) {
+                    missing_elements.push(pk.clone());
+                }
+            }
+        }
+        // check before applying the additions, so that `self` stays unchanged on error
+        self.check_consistency_adding(&missing_elements)?;
+        for ek in missing_payload {
+            self.payload.insert(ek, Vec::new());
+        }
+        for pk in missing_elements {
+            self.header.elements.add(ElementDef::new(pk));
+        }
         for (pk, pe) in &self.payload {
+            self.header.elements.get_mut(pk).unwrap().count = pe.len();
+        }
+        Ok(())
+    }
+    /// Returns `true` if `make_consistent()` would succeed, without modifying `self`.
+    ///
+    /// Problems that `make_consistent()` fixes automatically, like outdated element counts, are accepted.
+    pub fn is_consistent(&self) -> bool {
+        self.check_consistency().is_ok()
+    }
+    /// Performs all checks of `make_consistent()` which can't be fixed automatically.
+    pub(crate) fn check_consistency(&self) -> Result<(), ConsistencyError> {
+        self.check_consistency_adding(&[])
+    }
+    /// Like `check_consistency()`, but as if elements named `added` were declared in the header.
+    fn check_consistency_adding(&self, added: &[String]) -> Result<(), ConsistencyError> {
+        for (pk, _) in &self.payload {
             if pk.is_empty() {
                 return Err(ConsistencyError::new("Element cannot have empty name."));
             }
-            let ed = self.header.elements.get_mut(pk);
-            if ed.is_none() {
+            if !self.header.elements.contains_key(pk) && !added.contains(pk) {
                 return Err(ConsistencyError::new(&format!("No decleration for element `{}` found.", pk)));
             }
-            ed.unwrap().count = pe.len();
         }
         for ref oi in &self.header.obj_infos {
             if has_line_break(oi) {
@@ -83,28 +158,89 @@ impl<E: PropertyAccess> Ply<E>{
             }
         }
         for ref c in &self.header.comments {
-            if has_line_break(&c) {
+            if has_line_break(c) {
                return Err(ConsistencyError::new(&format!("Comment `{}` should not contain any line breaks.", c)));
             }
         }
-        for (_, ref e) in &self.header.elements {
-            if has_line_break(&e.name) {
-                return Err(ConsistencyError::new(&format!("Name of element `{}` should not contain any line breaks.", e.name)));
-            }
-            if has_white_space(&e.name) {
-                return Err(ConsistencyError::new(&format!("Name of element `{}` should not contain any white spaces.", e.name)));
-            }
-            for (_, ref p) in &e.properties {
+        for name in added {
+            check_element_name(name)?;
+        }
+        for (_, e) in &self.header.elements {
+            check_element_name(&e.name)?;
+            for (_, p) in &e.properties {
                 if has_line_break(&p.name) {
                     return Err(ConsistencyError::new(&format!("Name of property `{}` of element `{}` should not contain any line breaks.", p.name, e.name)));
                 }
                 if has_white_space(&p.name) {
                     return Err(ConsistencyError::new(&format!("Name of property `{}` of element `{}` should not contain any spaces.", p.name, e.name)));
                 }
+                if let PropertyType::List(ScalarType::Float, _) | PropertyType::List(ScalarType::Double, _) = p.data_type {
+                    return Err(ConsistencyError::new(&format!("Index type of list property `{}` of element `{}` must be an integer type.", p.name, e.name)));
+                }
             }
         }
         Ok(())
     }
+    /// Checks that every element has all properties declared in the header, with the declared type.
+    ///
+    /// Uses the getters of `PropertyAccess`, hence a property counts as missing if its getter returns `None`.
+    /// Elements in the payload without declaration are reported as well.
+    /// Returns all violations found, not just the first one.
+    pub fn validate_payload_types(&self) -> Result<(), Vec<ConsistencyError>> {
+        let mut errors = Vec::new();
+        for (name, elements) in &self.payload {
+            let def = match self.header.elements.get(name) {
+                Some(d) => d,
+                None => {
+                    errors.push(ConsistencyError::new(&format!("No decleration for element `{}` found.", name)));
+                    continue;
+                }
+            };
+            for (i, e) in elements.iter().enumerate() {
+                for (k, p) in &def.properties {
+                    if !has_property_of_type(e, k, &p.data_type) {
+                        errors.push(ConsistencyError::new(&format!(
+                            "Element {} of `{}` has no property `{}` of type `{:?}`.", i, name, k, p.data_type
+                        )));
+                    }
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn has_property_of_type<E: PropertyAccess>(e: &E, key: &str, data_type: &PropertyType) -> bool {
+    match *data_type {
+        PropertyType::Scalar(ref t) => match *t {
+            ScalarType::Char => e.get_char(key).is_some(),
+            ScalarType::UChar => e.get_uchar(key).is_some(),
+            ScalarType::Short => e.get_short(key).is_some(),
+            ScalarType::UShort => e.get_ushort(key).is_some(),
+            ScalarType::Int => e.get_int(key).is_some(),
+            ScalarType::UInt => e.get_uint(key).is_some(),
+            ScalarType::Long => e.get_long(key).is_some(),
+            ScalarType::ULong => e.get_ulong(key).is_some(),
+            ScalarType::Float => e.get_float(key).is_some(),
+            ScalarType::Double => e.get_double(key).is_some(),
+        },
+        PropertyType::List(_, ref t) => match *t {
+            ScalarType::Char => e.get_list_char(key).is_some(),
+            ScalarType::UChar => e.get_list_uchar(key).is_some(),
+            ScalarType::Short => e.get_list_short(key).is_some(),
+            ScalarType::UShort => e.get_list_ushort(key).is_some(),
+            ScalarType::Int => e.get_list_int(key).is_some(),
+            ScalarType::UInt => e.get_list_uint(key).is_some(),
+            ScalarType::Long => e.get_list_long(key).is_some(),
+            ScalarType::ULong => e.get_list_ulong(key).is_some(),
+            ScalarType::Float => e.get_list_float(key).is_some(),
+            ScalarType::Double => e.get_list_double(key).is_some(),
+        },
+    }
 }
 
 #[cfg(test)]
@@ -157,4 +293,79 @@ mod tests {
         let r = p.make_consistent();
         assert!(r.is_err());
     }
+    #[test]
+    fn is_consistent_no_mutation() {
+        let mut p = P::new();
+        p.header.elements.add(ElementDef::new("ok".to_string()));
+        p.payload.insert("ok".to_string(), vec![DefaultElement::new()]);
+        let before = p.clone();
+        assert!(p.is_consistent());
+        assert_eq!(p, before);
+        p.payload.insert("undeclared".to_string(), Vec::new());
+        assert!(!p.is_consistent());
+        assert!(p.make_consistent().is_err());
+    }
+    #[test]
+    fn validate_payload_types_all_errors() {
+        let mut p = P::new();
+        let mut e = ElementDef::new("vertex".to_string());
+        e.properties.add(PropertyDef::new("x".to_string(), PropertyType::Scalar(ScalarType::Float)));
+        e.properties.add(PropertyDef::new("i".to_string(), PropertyType::List(ScalarType::UChar, ScalarType::Int)));
+        p.header.elements.add(e);
+        let mut ok = DefaultElement::new();
+        ok.insert("x".to_string(), Property::Float(1.0));
+        ok.insert("i".to_string(), Property::ListInt(vec![1, 2]));
+        p.payload.insert("vertex".to_string(), vec![ok.clone()]);
+        assert!(p.validate_payload_types().is_ok());
+
+        let mut wrong = ok.clone();
+        wrong.insert("x".to_string(), Property::Int(1));
+        wrong.remove("i");
+        p.payload.get_mut("vertex").unwrap().push(wrong);
+        p.payload.insert("undeclared".to_string(), vec![ok]);
+        assert_eq!(p.validate_payload_types().unwrap_err().len(), 3);
+    }
+    #[test]
+    fn consistent_with_options() {
+        let mut p = P::new();
+        p.header.elements.add(ElementDef::new("declared".to_string()));
+        p.payload.insert("undeclared".to_string(), vec![DefaultElement::new(); 2]);
+        let strict = ConsistencyOptions { auto_add_missing_elements: false, auto_add_missing_payload: false };
+        assert!(p.make_consistent_with_options(strict).is_err());
+        assert!(p.make_consistent_with_options(ConsistencyOptions::default()).is_err());
+        assert!(!p.header.elements.contains_key("undeclared"));
+        let lenient = ConsistencyOptions { auto_add_missing_elements: true, auto_add_missing_payload: true };
+        assert!(p.make_consistent_with_options(lenient).is_ok());
+        assert_eq!(p.header.elements["undeclared"].count, 2);
+        assert_eq!(p.header.elements["declared"].count, 0);
+        assert!(p.payload["declared"].is_empty());
+        assert!(p.make_consistent_with_options(strict).is_ok());
+    }
+    #[test]
+    fn consistent_with_options_err_unchanged() {
+        let mut p = P::new();
+        p.header.elements.add(ElementDef::new("declared".to_string()));
+        p.payload.insert("undeclared".to_string(), vec![DefaultElement::new(); 2]);
+        p.header.comments.push("line\nbreak".to_string());
+        let lenient = ConsistencyOptions { auto_add_missing_elements: true, auto_add_missing_payload: true };
+        assert!(p.make_consistent_with_options(lenient).is_err());
+        assert!(!p.header.elements.contains_key("undeclared"));
+        assert!(!p.payload.contains_key("declared"));
+
+        let mut p = P::new();
+        p.payload.insert("bad name".to_string(), Vec::new());
+        assert!(p.make_consistent_with_options(lenient).is_err());
+        assert!(p.header.elements.is_empty());
+    }
+    #[test]
+    fn consistent_float_list_index_fail() {
+        for index_type in [ScalarType::Float, ScalarType::Double].iter().cloned() {
+            let mut p = P::new();
+            let mut e = ElementDef::new("face".to_string());
+            e.properties.add(PropertyDef::new("vertex_indices".to_string(), PropertyType::List(index_type, ScalarType::Int)));
+            p.header.elements.add(e);
+            assert!(!p.is_consistent());
+            assert!(p.make_consistent().is_err());
+        }
+    }
 }
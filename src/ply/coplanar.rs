@@ -0,0 +1,132 @@
+//! Merging of coplanar faces into polygons.
+
+use std::collections::{ HashMap, HashSet };
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::angle;
+
+/// Joins two faces along the half-edge `(a, b)` of `f`, which is `(b, a)` in `g`.
+fn join(f: &[usize], g: &[usize], a: usize, b: usize) -> Vec<usize> {
+    // f from b around to a, followed by the inner vertices of g from a around to b
+    let start_f = f.iter().position(|&v| v == b).unwrap();
+    let start_g = g.iter().position(|&v| v == a).unwrap();
+    let mut joined: Vec<usize> = (0..f.len()).map(|k| f[(start_f + k) % f.len()]).collect();
+    joined.extend((1..g.len() - 1).map(|k| g[(start_g + k) % g.len()]));
+    joined
+}
+
+fn half_edges(face: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..face.len()).map(move |k| (face[k], face[(k + 1) % face.len()]))
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Merges adjacent faces whose normals differ by less than `angle_threshold_deg` into polygons.
+    ///
+    /// Two faces are merged by removing their common edge, which yields a quadrilateral
+    /// from two triangles and larger polygons when merging repeatedly.
+    /// Faces are only merged if they share exactly one edge, both traverse it in opposite directions
+    /// (consistent orientation) and no other face uses the edge.
+    ///
+    /// The merged polygon keeps all other properties of the face that comes first in the payload,
+    /// the other face is removed and the element count in the header is adjusted.
+    ///
+    /// Returns the number of merges, which equals the number of removed faces.
+    pub fn merge_coplanar_faces(&mut self, angle_threshold_deg: f64) -> Result<usize> {
+        let mesh = self.read_mesh()?;
+        let threshold = angle_threshold_deg.to_radians();
+        let mut faces: Vec<Option<Vec<usize>>> = mesh.faces.iter().cloned().map(Some).collect();
+
+        // half-edge -> face, edges used by several faces in the same direction are marked with `None`
+        let mut half_edge_face = HashMap::<(usize, usize), Option<usize>>::new();
+        for (fi, f) in mesh.faces.iter().enumerate() {
+            for e in half_edges(f) {
+                half_edge_face.entry(e).and_modify(|o| *o = None).or_insert(Some(fi));
+            }
+        }
+
+        let mut merges = 0;
+        for fi in 0..faces.len() {
+            'merging: while let Some(f) = faces[fi].clone() {
+                let normal_f = mesh.face_normal(&f);
+                for (a, b) in half_edges(&f) {
+                    let gi = match half_edge_face.get(&(b, a)) {
+                        Some(&Some(gi)) if gi != fi => gi,
+                        _ => continue,
+                    };
+                    let g = faces[gi].as_ref().unwrap();
+                    let shared = f.iter().collect::<HashSet<_>>().intersection(&g.iter().collect()).count();
+                    if shared == 2 && angle(&normal_f, &mesh.face_normal(g)) < threshold {
+                        let joined = join(&f, g, a, b);
+                        for e in half_edges(&f).chain(half_edges(g)) {
+                            half_edge_face.remove(&e);
+                        }
+                        for e in half_edges(&joined) {
+                            half_edge_face.insert(e, Some(fi));
+                        }
+                        faces[fi] = Some(joined);
+                        faces[gi] = None;
+                        merges += 1;
+                        continue 'merging;
+                    }
+                }
+                break;
+            }
+        }
+
+        if merges == 0 {
+            return Ok(0);
+        }
+        let mut keep = faces.iter().map(Option::is_some);
+        let elements = self.payload.entry("face".to_string()).or_default();
+        elements.retain(|_| keep.next().unwrap());
+        if let Some(def) = self.header.elements.get_mut("face") {
+            def.count = elements.len();
+        }
+        let merged: Vec<Vec<usize>> = faces.into_iter().flatten().collect();
+        self.write_face_indices(&merged)?;
+        Ok(merges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn merge_coplanar_quad() {
+        let mut ply = fixtures::grid(1);
+        assert_eq!(ply.merge_coplanar_faces(1.0).unwrap(), 1);
+        assert_eq!(ply.header.elements["face"].count, 1);
+        let mesh = ply.read_mesh().unwrap();
+        assert_eq!(mesh.faces, vec![vec![0, 1, 3, 2]]);
+    }
+    #[test]
+    fn merge_coplanar_grid() {
+        let mut ply = fixtures::grid(2);
+        let merges = ply.merge_coplanar_faces(1.0).unwrap();
+        let mesh = ply.read_mesh().unwrap();
+        assert_eq!(mesh.faces.len(), 8 - merges);
+        assert!(merges >= 4);
+        // every polygon is simple
+        for f in &mesh.faces {
+            let mut sorted = f.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            assert_eq!(sorted.len(), f.len());
+        }
+    }
+    #[test]
+    fn merge_coplanar_fold_kept() {
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let mut ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![1, 0, 3]]);
+        assert_eq!(ply.merge_coplanar_faces(45.0).unwrap(), 0);
+        assert_eq!(ply.merge_coplanar_faces(95.0).unwrap(), 1);
+        assert_eq!(ply.payload["face"].len(), 1);
+    }
+    #[test]
+    fn merge_coplanar_icosahedron_none() {
+        let mut ply = fixtures::icosahedron();
+        assert_eq!(ply.merge_coplanar_faces(10.0).unwrap(), 0);
+        assert_eq!(ply, fixtures::icosahedron());
+    }
+}
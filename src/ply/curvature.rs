@@ -0,0 +1,256 @@
+//! Discrete curvature measures for triangle meshes.
+//!
+//! All measures follow Meyer et al., "Discrete Differential-Geometry Operators for Triangulated 2-Manifolds" (2003):
+//! The mean curvature is derived from the cotangent Laplacian,
+//! the Gaussian curvature from the angle defect,
+//! both normalized by the mixed Voronoi area of each vertex.
+
+use std::f64::consts::PI;
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Mesh, Vec3, add, sub, scale, dot, angle, cot };
+
+/// Per vertex quantities shared by the curvature measures.
+pub(crate) struct CurvatureData {
+    /// Signed mean curvature.
+    pub mean: Vec<f64>,
+    /// Gaussian curvature.
+    pub gaussian: Vec<f64>,
+    /// Angle defect, the Gaussian curvature integrated over the mixed area.
+    pub angle_defect: Vec<f64>,
+}
+
+impl CurvatureData {
+    pub fn new(mesh: &Mesh) -> Self {
+        let n = mesh.vertices.len();
+        let triangles = mesh.triangles();
+        let boundary = mesh.boundary_vertices(&triangles);
+        let normals = mesh.vertex_normals(&triangles);
+        let mut area = vec![0.0; n];
+        let mut laplace: Vec<Vec3> = vec![[0.0; 3]; n];
+        let mut angle_sum = vec![0.0; n];
+
+        for t in &triangles {
+            let p = [&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]];
+            let t_area = mesh.triangle_area(t);
+            if t_area <= 0.0 {
+                continue;
+            }
+            let mut angles = [0.0; 3];
+            for k in 0..3 {
+                let (j, l) = ((k + 1) % 3, (k + 2) % 3);
+                angles[k] = angle(&sub(p[j], p[k]), &sub(p[l], p[k]));
+                angle_sum[t[k]] += angles[k];
+                // edge (k, j) is opposite to l
+                let w = cot(&sub(p[k], p[l]), &sub(p[j], p[l]));
+                laplace[t[k]] = add(&laplace[t[k]], &scale(&sub(p[j], p[k]), w));
+                laplace[t[j]] = add(&laplace[t[j]], &scale(&sub(p[k], p[j]), w));
+            }
+            let obtuse = angles.iter().position(|&a| a > PI / 2.0);
+            for k in 0..3 {
+                let (j, l) = ((k + 1) % 3, (k + 2) % 3);
+                area[t[k]] += match obtuse {
+                    None => {
+                        let e_kj = sub(p[j], p[k]);
+                        let e_kl = sub(p[l], p[k]);
+                        (dot(&e_kj, &e_kj) / angles[l].tan() + dot(&e_kl, &e_kl) / angles[j].tan()) / 8.0
+                    },
+                    Some(o) if o == k => t_area / 2.0,
+                    Some(_) => t_area / 4.0,
+                };
+            }
+        }
+
+        let mut mean = vec![0.0; n];
+        let mut gaussian = vec![0.0; n];
+        let mut angle_defect = vec![0.0; n];
+        for i in 0..n {
+            let full_angle = if boundary[i] { PI } else { 2.0 * PI };
+            angle_defect[i] = full_angle - angle_sum[i];
+            if area[i] <= 0.0 {
+                continue;
+            }
+            let delta = scale(&laplace[i], 1.0 / (2.0 * area[i]));
+            mean[i] = -dot(&delta, &normals[i]) / 2.0;
+            gaussian[i] = angle_defect[i] / area[i];
+        }
+        CurvatureData {
+            mean,
+            gaussian,
+            angle_defect,
+        }
+    }
+    /// Principal curvatures `[kappa_min, kappa_max]` derived from mean and Gaussian curvature.
+    pub fn principal(&self) -> Vec<[f64; 2]> {
+        self.mean.iter().zip(self.gaussian.iter()).map(|(&h, &k)| {
+            let d = (h * h - k).max(0.0).sqrt();
+            [h - d, h + d]
+        }).collect()
+    }
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the signed mean curvature at each vertex.
+    ///
+    /// Polygons are split into triangle fans.
+    /// The sign follows the orientation of the faces:
+    /// a convex surface with counter-clockwise (outward facing) faces has positive mean curvature.
+    pub fn compute_mean_curvature(&self) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        Ok(CurvatureData::new(&mesh).mean)
+    }
+    /// Computes the Gaussian curvature at each vertex.
+    ///
+    /// The angle defect of a vertex is divided by its mixed Voronoi area.
+    pub fn compute_gaussian_curvature(&self) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        Ok(CurvatureData::new(&mesh).gaussian)
+    }
+    /// Computes the angle defect at each vertex.
+    ///
+    /// The angle defect is `2π` minus the sum of the interior angles of all adjacent triangles,
+    /// for boundary vertices `π` is used instead of `2π`.
+    /// By the Gauss-Bonnet theorem, it is the discrete Gaussian curvature integrated over the area around the vertex.
+    /// Vertices without adjacent faces have a defect of `2π`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ply_rs::ply::{ Ply, DefaultElement, PropertyDef, PropertyType, ScalarType, Property };
+    /// # let mut ply = Ply::<DefaultElement>::new();
+    /// let defects = ply.compute_angle_defects().unwrap();
+    /// let values = defects.iter().map(|&d| Property::Float(d as f32)).collect();
+    /// let def = PropertyDef::new("gaussian_curvature".to_string(), PropertyType::Scalar(ScalarType::Float));
+    /// ply.add_property_to_element("vertex", def, values).unwrap();
+    /// ```
+    pub fn compute_angle_defects(&self) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        Ok(CurvatureData::new(&mesh).angle_defect)
+    }
+    /// Computes the principal curvatures `[kappa_min, kappa_max]` at each vertex.
+    ///
+    /// The principal curvatures are the eigenvalues of the shape operator.
+    /// They are recovered from the discrete mean curvature `H` (cotangent Laplacian)
+    /// and Gaussian curvature `K` (Gauss-Bonnet) as `H ± sqrt(H² - K)`.
+    /// Where the discretization yields `H² < K`, both values collapse to `H`.
+    ///
+    /// # Examples
+    ///
+    /// The values can be stored back into the vertices with `add_property_to_element()`:
+    ///
+    /// ```rust,no_run
+    /// # use ply_rs::ply::{ Ply, DefaultElement, PropertyDef, PropertyType, ScalarType, Property };
+    /// # let mut ply = Ply::<DefaultElement>::new();
+    /// let kappa = ply.compute_principal_curvatures().unwrap();
+    /// let kappa_1 = kappa.iter().map(|k| Property::Float(k[0] as f32)).collect();
+    /// let def = PropertyDef::new("kappa_1".to_string(), PropertyType::Scalar(ScalarType::Float));
+    /// ply.add_property_to_element("vertex", def, kappa_1).unwrap();
+    /// ```
+    pub fn compute_principal_curvatures(&self) -> Result<Vec<[f64; 2]>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        Ok(CurvatureData::new(&mesh).principal())
+    }
+    /// Returns the indices of all umbilical vertices, using a threshold of `0.95`.
+    ///
+    /// See `compute_umbilics_with_threshold()`.
+    pub fn compute_umbilics(&self) -> Result<Vec<usize>> {
+        self.compute_umbilics_with_threshold(0.95)
+    }
+    /// Returns the indices of all vertices where both principal curvatures are (nearly) equal.
+    ///
+    /// A vertex is reported if both principal curvatures have the same sign
+    /// and the ratio of the smaller to the larger magnitude exceeds `threshold`.
+    /// Flat vertices are never reported.
+    ///
+    /// # Remarks
+    ///
+    /// The principal curvatures are derived from the discrete mean and Gaussian curvature,
+    /// the result is therefore only an approximation.
+    /// An exact computation would require fitting a higher-order surface.
+    pub fn compute_umbilics_with_threshold(&self, threshold: f64) -> Result<Vec<usize>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let principal = CurvatureData::new(&mesh).principal();
+        let mut umbilics = Vec::new();
+        for (i, k) in principal.iter().enumerate() {
+            if k[0] * k[1] <= 0.0 {
+                continue;
+            }
+            let (a, b) = (k[0].abs(), k[1].abs());
+            if a.min(b) / a.max(b) > threshold {
+                umbilics.push(i);
+            }
+        }
+        Ok(umbilics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+    use super::super::mesh::fixtures;
+    #[test]
+    fn mean_curvature_icosahedron_positive() {
+        let ply = fixtures::icosahedron();
+        let h = ply.compute_mean_curvature().unwrap();
+        assert_eq!(h.len(), 12);
+        assert!(h.iter().all(|&v| v > 0.0));
+    }
+    #[test]
+    fn gaussian_curvature_flat_grid_zero() {
+        let ply = fixtures::grid(4);
+        let k = ply.compute_gaussian_curvature().unwrap();
+        for i in 1..4 {
+            for j in 1..4 {
+                assert!(k[i * 5 + j].abs() < 1e-9);
+            }
+        }
+    }
+    #[test]
+    fn angle_defects_gauss_bonnet() {
+        // a closed surface of genus 0 has a total defect of 4π
+        let ply = fixtures::icosahedron();
+        let d = ply.compute_angle_defects().unwrap();
+        assert!((d.iter().sum::<f64>() - 4.0 * PI).abs() < 1e-9);
+        assert!(d.iter().all(|&v| (v - PI / 3.0).abs() < 1e-9));
+    }
+    #[test]
+    fn angle_defects_grid_boundary() {
+        let ply = fixtures::grid(2);
+        let d = ply.compute_angle_defects().unwrap();
+        // corner with a single right angle
+        assert!((d[2] - PI / 2.0).abs() < 1e-9);
+        // boundary vertex on a straight edge
+        assert!(d[1].abs() < 1e-9);
+        // interior vertex
+        assert!(d[4].abs() < 1e-9);
+    }
+    #[test]
+    fn principal_curvatures_ordered() {
+        let ply = fixtures::icosahedron();
+        let k = ply.compute_principal_curvatures().unwrap();
+        assert_eq!(k.len(), 12);
+        assert!(k.iter().all(|k| k[0] <= k[1] && k[0] > 0.0));
+    }
+    #[test]
+    fn umbilics_icosahedron_all() {
+        let ply = fixtures::icosahedron();
+        assert_eq!(ply.compute_umbilics().unwrap(), (0..12).collect::<Vec<_>>());
+    }
+    #[test]
+    fn umbilics_flat_grid_none() {
+        let ply = fixtures::grid(4);
+        assert!(ply.compute_umbilics().unwrap().is_empty());
+    }
+    #[test]
+    fn umbilics_no_faces_err() {
+        let mut ply = fixtures::icosahedron();
+        ply.payload.get_mut("face").unwrap().clear();
+        assert!(ply.compute_umbilics().is_err());
+    }
+}
@@ -0,0 +1,194 @@
+//! Curvature tensor estimation following Rusinkiewicz,
+//! "Estimating Curvatures and Their Derivatives on Triangle Meshes" (2004).
+
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::{ Property, PropertyDef, PropertyType, ScalarType };
+use super::mesh::{ Mesh, Vec3, add, sub, scale, dot, cross, normalize, invalid_input };
+
+/// Rotates the frame `(u, v)` around their cross product such that its normal becomes `new_normal`.
+fn rotate_frame(u: &Vec3, v: &Vec3, new_normal: &Vec3) -> (Vec3, Vec3) {
+    let old_normal = cross(u, v);
+    let ndot = dot(&old_normal, new_normal);
+    if ndot <= -1.0 {
+        return (scale(u, -1.0), scale(v, -1.0));
+    }
+    let perp_old = sub(new_normal, &scale(&old_normal, ndot));
+    let dperp = scale(&add(&old_normal, new_normal), 1.0 / (1.0 + ndot));
+    (sub(u, &scale(&dperp, dot(u, &perp_old))), sub(v, &scale(&dperp, dot(v, &perp_old))))
+}
+
+/// Expresses the second fundamental form `ii` given in frame `(old_u, old_v)` in the frame `(new_u, new_v)`.
+fn project_form(old_u: &Vec3, old_v: &Vec3, ii: &[f64; 3], new_u: &Vec3, new_v: &Vec3) -> [f64; 3] {
+    let (r_u, r_v) = rotate_frame(new_u, new_v, &cross(old_u, old_v));
+    let (u1, v1) = (dot(&r_u, old_u), dot(&r_u, old_v));
+    let (u2, v2) = (dot(&r_v, old_u), dot(&r_v, old_v));
+    [
+        ii[0] * u1 * u1 + ii[1] * 2.0 * u1 * v1 + ii[2] * v1 * v1,
+        ii[0] * u1 * u2 + ii[1] * (u1 * v2 + u2 * v1) + ii[2] * v1 * v2,
+        ii[0] * u2 * u2 + ii[1] * 2.0 * u2 * v2 + ii[2] * v2 * v2,
+    ]
+}
+
+/// Solves the symmetric 3x3 system `a x = b` with Cramer's rule.
+fn solve3(a: &[[f64; 3]; 3], b: &[f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: &[[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(a);
+    if d.abs() < 1e-300 {
+        return None;
+    }
+    let mut x = [0.0; 3];
+    for (c, xc) in x.iter_mut().enumerate() {
+        let mut m = *a;
+        for r in 0..3 {
+            m[r][c] = b[r];
+        }
+        *xc = det(&m) / d;
+    }
+    Some(x)
+}
+
+/// Per vertex curvature tensors `[xx, xy, xz, yy, yz, zz]`.
+pub(crate) fn curvature_tensors(mesh: &Mesh) -> Vec<[f64; 6]> {
+    let n = mesh.vertices.len();
+    let triangles = mesh.triangles();
+    let normals = mesh.vertex_normals(&triangles);
+    // arbitrary tangent frame per vertex
+    let frames: Vec<(Vec3, Vec3)> = normals.iter().map(|nv| {
+        let helper = if nv[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+        let u = normalize(&cross(&helper, nv));
+        (u, cross(nv, &u))
+    }).collect();
+    let mut forms = vec![[0.0; 3]; n];
+    let mut weights = vec![0.0; n];
+    for t in &triangles {
+        let p = [&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]];
+        let area = mesh.triangle_area(t);
+        if area <= 0.0 {
+            continue;
+        }
+        // edges e_j are opposite to vertex j
+        let e = [sub(p[2], p[1]), sub(p[0], p[2]), sub(p[1], p[0])];
+        let fu = normalize(&e[0]);
+        let fn_ = normalize(&cross(&e[0], &e[1]));
+        let fv = cross(&fn_, &fu);
+        let mut w = [[0.0; 3]; 3];
+        let mut m = [0.0; 3];
+        for (j, e_j) in e.iter().enumerate() {
+            let (prev, next) = ((j + 2) % 3, (j + 1) % 3);
+            let (u, v) = (dot(e_j, &fu), dot(e_j, &fv));
+            w[0][0] += u * u;
+            w[0][1] += u * v;
+            w[2][2] += v * v;
+            let dn = sub(&normals[t[prev]], &normals[t[next]]);
+            let (dnu, dnv) = (dot(&dn, &fu), dot(&dn, &fv));
+            m[0] += dnu * u;
+            m[1] += dnu * v + dnv * u;
+            m[2] += dnv * v;
+        }
+        w[1][1] = w[0][0] + w[2][2];
+        w[1][2] = w[0][1];
+        w[1][0] = w[0][1];
+        w[2][1] = w[1][2];
+        let ii = match solve3(&w, &m) {
+            Some(x) => x,
+            None => continue,
+        };
+        for &vi in t {
+            let (ref u, ref v) = frames[vi];
+            let projected = project_form(&fu, &fv, &ii, u, v);
+            let weight = area / 3.0;
+            for c in 0..3 {
+                forms[vi][c] += weight * projected[c];
+            }
+            weights[vi] += weight;
+        }
+    }
+    forms.iter().zip(weights.iter()).zip(frames.iter()).map(|((ii, &w), (u, v))| {
+        if w <= 0.0 {
+            return [0.0; 6];
+        }
+        let (ku, kuv, kv) = (ii[0] / w, ii[1] / w, ii[2] / w);
+        let entry = |a: usize, b: usize| ku * u[a] * u[b] + kuv * (u[a] * v[b] + v[a] * u[b]) + kv * v[a] * v[b];
+        [entry(0, 0), entry(0, 1), entry(0, 2), entry(1, 1), entry(1, 2), entry(2, 2)]
+    }).collect()
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the symmetric 3x3 curvature tensor of every vertex and stores it in the `vertex` element.
+    ///
+    /// For each face, the second fundamental form is fitted to the change of the vertex normals along its edges.
+    /// The forms are transferred to the tangent frame of each vertex and averaged, weighted by area.
+    /// Finally, the form is expressed in world coordinates: `T = ku u uᵀ + kuv (u vᵀ + v uᵀ) + kv v vᵀ`,
+    /// where `u` and `v` span the tangent plane of the vertex.
+    /// The eigenvalues of `T` are the principal curvatures and `0` (in normal direction).
+    ///
+    /// The six independent components are stored as float properties
+    /// `ct_xx`, `ct_xy`, `ct_xz`, `ct_yy`, `ct_yz`, and `ct_zz`,
+    /// the header is updated accordingly.
+    pub fn compute_curvature_tensor(&mut self) -> Result<()> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let tensors = curvature_tensors(&mesh);
+        // the properties are added one by one, check before the first so errors leave `self` unchanged
+        if !self.header.elements.contains_key("vertex") {
+            return invalid_input("No element `vertex` declared in header.");
+        }
+        let names = ["ct_xx", "ct_xy", "ct_xz", "ct_yy", "ct_yz", "ct_zz"];
+        for (c, name) in names.iter().enumerate() {
+            let values = tensors.iter().map(|t| Property::Float(t[c] as f32)).collect();
+            let def = PropertyDef::new(name.to_string(), PropertyType::Scalar(ScalarType::Float));
+            self.add_property_to_element("vertex", def, values)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    use super::super::Property;
+    use super::curvature_tensors;
+    #[test]
+    fn curvature_tensor_icosahedron() {
+        let ply = fixtures::icosahedron();
+        let mesh = ply.read_mesh().unwrap();
+        for (t, p) in curvature_tensors(&mesh).iter().zip(mesh.vertices.iter()) {
+            // the normal direction is an eigenvector with eigenvalue 0
+            let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            let n = [p[0] / r, p[1] / r, p[2] / r];
+            let tn = [
+                t[0] * n[0] + t[1] * n[1] + t[2] * n[2],
+                t[1] * n[0] + t[3] * n[1] + t[4] * n[2],
+                t[2] * n[0] + t[4] * n[1] + t[5] * n[2],
+            ];
+            assert!(tn.iter().all(|v| v.abs() < 1e-9));
+            // trace is twice the mean curvature, positive for a convex surface
+            assert!(t[0] + t[3] + t[5] > 0.0);
+        }
+    }
+    #[test]
+    fn curvature_tensor_flat_grid_zero() {
+        let mut ply = fixtures::grid(3);
+        ply.compute_curvature_tensor().unwrap();
+        assert!(ply.header.elements["vertex"].properties.contains_key("ct_yz"));
+        for v in &ply.payload["vertex"] {
+            for name in &["ct_xx", "ct_xy", "ct_xz", "ct_yy", "ct_yz", "ct_zz"] {
+                assert_eq!(v[*name], Property::Float(0.0));
+            }
+        }
+    }
+    #[test]
+    fn curvature_tensor_err_unchanged() {
+        let mut ply = fixtures::grid(3);
+        ply.header.elements.remove("vertex");
+        let before = ply.clone();
+        assert!(ply.compute_curvature_tensor().is_err());
+        assert_eq!(ply, before);
+    }
+}
@@ -22,100 +22,148 @@ impl PropertyAccess for DefaultElement {
     fn set_property(&mut self, key: String, property: Property) {
         self.insert(key, property);
     }
-    fn get_char(&self, key: &String) -> Option<i8> {
+    fn get_char(&self, key: &str) -> Option<i8> {
         match *get!(self.get(key)) {
             Property::Char(x) => Some(x),
             _ => None,
         }
     }
-    fn get_uchar(&self, key: &String) -> Option<u8> {
+    fn get_uchar(&self, key: &str) -> Option<u8> {
         match *get!(self.get(key)) {
             Property::UChar(x) => Some(x),
             _ => None,
         }
     }
-    fn get_short(&self, key: &String) -> Option<i16> {
+    fn get_short(&self, key: &str) -> Option<i16> {
         match *get!(self.get(key)) {
             Property::Short(x) => Some(x),
             _ => None,
         }
     }
-    fn get_ushort(&self, key: &String) -> Option<u16> {
+    fn get_ushort(&self, key: &str) -> Option<u16> {
         match *get!(self.get(key)) {
             Property::UShort(x) => Some(x),
             _ => None,
         }
     }
-    fn get_int(&self, key: &String) -> Option<i32> {
+    fn get_int(&self, key: &str) -> Option<i32> {
         match *get!(self.get(key)) {
             Property::Int(x) => Some(x),
             _ => None,
         }
     }
-    fn get_uint(&self, key: &String) -> Option<u32> {
+    fn get_uint(&self, key: &str) -> Option<u32> {
         match *get!(self.get(key)) {
             Property::UInt(x) => Some(x),
             _ => None,
         }
     }
-    fn get_float(&self, key: &String) -> Option<f32> {
+    fn get_long(&self, key: &str) -> Option<i64> {
+        match *get!(self.get(key)) {
+            Property::Long(x) => Some(x),
+            _ => None,
+        }
+    }
+    fn get_ulong(&self, key: &str) -> Option<u64> {
+        match *get!(self.get(key)) {
+            Property::ULong(x) => Some(x),
+            _ => None,
+        }
+    }
+    fn get_float(&self, key: &str) -> Option<f32> {
         match *get!(self.get(key)) {
             Property::Float(x) => Some(x),
             _ => None,
         }
     }
-    fn get_double(&self, key: &String) -> Option<f64> {
+    fn get_double(&self, key: &str) -> Option<f64> {
         match *get!(self.get(key)) {
             Property::Double(x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_char(&self, key: &String) -> Option<&[i8]> {
+    fn get_list_char(&self, key: &str) -> Option<&[i8]> {
         match *get!(self.get(key)) {
             Property::ListChar(ref x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_uchar(&self, key: &String) -> Option<&[u8]> {
+    fn get_list_uchar(&self, key: &str) -> Option<&[u8]> {
         match *get!(self.get(key)) {
             Property::ListUChar(ref x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_short(&self, key: &String) -> Option<&[i16]> {
+    fn get_list_short(&self, key: &str) -> Option<&[i16]> {
         match *get!(self.get(key)) {
             Property::ListShort(ref x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_ushort(&self, key: &String) -> Option<&[u16]> {
+    fn get_list_ushort(&self, key: &str) -> Option<&[u16]> {
         match *get!(self.get(key)) {
             Property::ListUShort(ref x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_int(&self, key: &String) -> Option<&[i32]> {
+    fn get_list_int(&self, key: &str) -> Option<&[i32]> {
         match *get!(self.get(key)) {
             Property::ListInt(ref x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_uint(&self, key: &String) -> Option<&[u32]> {
+    fn get_list_uint(&self, key: &str) -> Option<&[u32]> {
         match *get!(self.get(key)) {
             Property::ListUInt(ref x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_float(&self, key: &String) -> Option<&[f32]> {
+    fn get_list_long(&self, key: &str) -> Option<&[i64]> {
+        match *get!(self.get(key)) {
+            Property::ListLong(ref x) => Some(x),
+            _ => None,
+        }
+    }
+    fn get_list_ulong(&self, key: &str) -> Option<&[u64]> {
+        match *get!(self.get(key)) {
+            Property::ListULong(ref x) => Some(x),
+            _ => None,
+        }
+    }
+    fn get_list_float(&self, key: &str) -> Option<&[f32]> {
         match *get!(self.get(key)) {
             Property::ListFloat(ref x) => Some(x),
             _ => None,
         }
     }
-    fn get_list_double(&self, key: &String) -> Option<&[f64]> {
+    fn get_list_double(&self, key: &str) -> Option<&[f64]> {
         match *get!(self.get(key)) {
             Property::ListDouble(ref x) => Some(x),
             _ => None,
         }
     }
+    fn get_property(&self, key: &str) -> Option<&Property> {
+        self.get(key)
+    }
+    fn list_len(&self, key: &str) -> Option<usize> {
+        match *get!(self.get(key)) {
+            Property::ListChar(ref x) => Some(x.len()),
+            Property::ListUChar(ref x) => Some(x.len()),
+            Property::ListShort(ref x) => Some(x.len()),
+            Property::ListUShort(ref x) => Some(x.len()),
+            Property::ListInt(ref x) => Some(x.len()),
+            Property::ListUInt(ref x) => Some(x.len()),
+            Property::ListLong(ref x) => Some(x.len()),
+            Property::ListULong(ref x) => Some(x.len()),
+            Property::ListFloat(ref x) => Some(x.len()),
+            Property::ListDouble(ref x) => Some(x.len()),
+            _ => None,
+        }
+    }
+    fn property_count(&self) -> usize {
+        self.len()
+    }
+    fn property_keys(&self) -> Vec<String> {
+        self.keys().cloned().collect()
+    }
 }
@@ -0,0 +1,87 @@
+//! Point clouds from depth images.
+
+use super::{ Ply, DefaultElement, ElementDef, PropertyDef, PropertyType, ScalarType, Property, Addable };
+
+impl Ply<DefaultElement> {
+    /// Converts a depth image into a point cloud using the pinhole camera model.
+    ///
+    /// `depth` holds `width * height` values in row-major order.
+    /// The pixel `(u, v)` with depth `d` becomes the point
+    /// `z = d * depth_scale`, `x = (u - cx) * z / fx`, `y = (v - cy) * z / fy`,
+    /// e.g. use a `depth_scale` of `0.001` for depth images in millimetres to obtain metres.
+    /// Pixels with a depth of zero or a non-finite depth are skipped.
+    ///
+    /// The points are stored as `vertex` element with float properties `x`, `y` and `z`.
+    /// If `rgb` is given, it must hold three bytes per pixel in the same order,
+    /// which are added as uchar properties `r`, `g` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `depth` or `rgb` doesn't match the image size.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_depth_image(depth: &[f32], width: usize, height: usize, fx: f64, fy: f64, cx: f64, cy: f64, depth_scale: f64, rgb: Option<&[u8]>) -> Ply<DefaultElement> {
+        assert_eq!(depth.len(), width * height, "Depth image must have `width * height` values.");
+        if let Some(rgb) = rgb {
+            assert_eq!(rgb.len(), 3 * width * height, "Color image must have `3 * width * height` values.");
+        }
+        let mut vertex_def = ElementDef::new("vertex".to_string());
+        for name in &["x", "y", "z"] {
+            vertex_def.properties.add(PropertyDef::new(name.to_string(), PropertyType::Scalar(ScalarType::Float)));
+        }
+        if rgb.is_some() {
+            for name in &["r", "g", "b"] {
+                vertex_def.properties.add(PropertyDef::new(name.to_string(), PropertyType::Scalar(ScalarType::UChar)));
+            }
+        }
+        let mut vertices = Vec::new();
+        for (i, &d) in depth.iter().enumerate() {
+            if !d.is_finite() || d == 0.0 {
+                continue;
+            }
+            let (u, v) = ((i % width) as f64, (i / width) as f64);
+            let z = d as f64 * depth_scale;
+            let mut e = DefaultElement::new();
+            e.insert("x".to_string(), Property::Float(((u - cx) * z / fx) as f32));
+            e.insert("y".to_string(), Property::Float(((v - cy) * z / fy) as f32));
+            e.insert("z".to_string(), Property::Float(z as f32));
+            if let Some(rgb) = rgb {
+                e.insert("r".to_string(), Property::UChar(rgb[3 * i]));
+                e.insert("g".to_string(), Property::UChar(rgb[3 * i + 1]));
+                e.insert("b".to_string(), Property::UChar(rgb[3 * i + 2]));
+            }
+            vertices.push(e);
+        }
+        vertex_def.count = vertices.len();
+        let mut ply = Ply::<DefaultElement>::new();
+        ply.header.elements.add(vertex_def);
+        ply.payload.insert("vertex".to_string(), vertices);
+        ply
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    #[test]
+    fn depth_image_pinhole() {
+        let depth = [0.0, 1000.0, f32::NAN, 2000.0];
+        let ply = Ply::<DefaultElement>::from_depth_image(&depth, 2, 2, 500.0, 500.0, 0.5, 0.5, 0.001, None);
+        assert_eq!(ply.header.elements["vertex"].count, 2);
+        let points = ply.read_vertex_positions().unwrap();
+        let expected = [[0.001, -0.001, 1.0], [0.002, 0.002, 2.0]];
+        for (p, e) in points.iter().zip(expected.iter()) {
+            for k in 0..3 {
+                assert!((p[k] - e[k]).abs() < 1e-6);
+            }
+        }
+    }
+    #[test]
+    fn depth_image_colors() {
+        let rgb = [1, 2, 3, 4, 5, 6];
+        let ply = Ply::<DefaultElement>::from_depth_image(&[0.0, 1.0], 2, 1, 1.0, 1.0, 0.0, 0.0, 1.0, Some(&rgb));
+        let v = &ply.payload["vertex"];
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0]["r"], Property::UChar(4));
+        assert_eq!(v[0]["b"], Property::UChar(6));
+    }
+}
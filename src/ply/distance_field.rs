@@ -0,0 +1,104 @@
+//! Distance fields sampled on regular grids.
+
+use std::io::Result;
+use ndarray::Array3;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Vec3, dist, closest_point_on_triangle, invalid_input };
+
+/// Minimum padding of the grid on every side, relative to the bounding box diagonal.
+///
+/// Keeps the grid from collapsing along axes where the mesh is flat.
+const MIN_MARGIN: f64 = 1e-3;
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Samples the unsigned distance to the mesh surface on a regular grid with `grid_res` voxels per axis.
+    ///
+    /// The grid covers the bounding box of all vertices, expanded by 10%: every axis is padded on both sides
+    /// by 5% of the box extent along that axis, but at least by 0.1% of the box diagonal for flat meshes.
+    /// Element `[i, j, k]` of the result holds the distance from the centre of voxel `(i, j, k)`
+    /// (along x, y, z) to the closest point on any triangle, polygons are split into triangle fans.
+    ///
+    /// # Remarks
+    ///
+    /// The distance is evaluated by brute force against every triangle,
+    /// the complexity is `O(G * F)` for `G` voxels and `F` triangles.
+    ///
+    /// Requires the `ndarray` feature.
+    pub fn compute_unsigned_distance_field(&self, grid_res: [usize; 3]) -> Result<Array3<f64>> {
+        if grid_res.contains(&0) {
+            return invalid_input("Grid resolution must be at least 1 in every dimension.");
+        }
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        if triangles.is_empty() {
+            return invalid_input("Mesh has no triangles.");
+        }
+        let (mut min, mut max) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for t in &triangles {
+            for &v in t {
+                for k in 0..3 {
+                    min[k] = min[k].min(mesh.vertices[v][k]);
+                    max[k] = max[k].max(mesh.vertices[v][k]);
+                }
+            }
+        }
+        let min_margin = MIN_MARGIN * dist(&min, &max);
+        let mut voxel = [0.0; 3];
+        for k in 0..3 {
+            let margin = (0.05 * (max[k] - min[k])).max(min_margin);
+            min[k] -= margin;
+            max[k] += margin;
+            voxel[k] = (max[k] - min[k]) / grid_res[k] as f64;
+        }
+        let field = Array3::from_shape_fn((grid_res[0], grid_res[1], grid_res[2]), |(i, j, k)| {
+            let centre: Vec3 = [
+                min[0] + (i as f64 + 0.5) * voxel[0],
+                min[1] + (j as f64 + 0.5) * voxel[1],
+                min[2] + (k as f64 + 0.5) * voxel[2],
+            ];
+            triangles.iter().map(|t| {
+                let (a, b, c) = (&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]);
+                dist(&centre, &closest_point_on_triangle(&centre, a, b, c))
+            }).fold(f64::INFINITY, f64::min)
+        });
+        Ok(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn distance_field_flat_grid() {
+        // the unit square in the plane z = 0, the grid spans z in [-margin, margin]
+        let ply = fixtures::grid(1);
+        let field = ply.compute_unsigned_distance_field([4, 4, 2]).unwrap();
+        assert_eq!(field.shape(), &[4, 4, 2]);
+        let margin = super::MIN_MARGIN * 2f64.sqrt();
+        let expected = margin / 2.0;
+        for &d in field.iter() {
+            assert!(d >= expected - 1e-9);
+        }
+        // voxels above the square are exactly half a voxel away
+        assert!((field[[1, 1, 0]] - expected).abs() < 1e-9);
+        assert!((field[[2, 2, 1]] - expected).abs() < 1e-9);
+    }
+    #[test]
+    fn distance_field_padding_per_axis() {
+        // a 10 × 1 rectangle in the plane z = 0, the short axis is padded by 5% of its own extent
+        let vertices = [[0.0, 0.0, 0.0], [10.0, 0.0, 0.0], [10.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+        let ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![0, 2, 3]]);
+        let field = ply.compute_unsigned_distance_field([1, 22, 1]).unwrap();
+        // voxels are 0.05 wide along y, the first centre is at y = -0.025
+        assert!((field[[0, 0, 0]] - 0.025).abs() < 1e-9);
+        assert!((field[[0, 21, 0]] - 0.025).abs() < 1e-9);
+        assert!(field[[0, 1, 0]].abs() < 1e-9);
+    }
+    #[test]
+    fn distance_field_zero_resolution_err() {
+        let ply = fixtures::icosahedron();
+        assert!(ply.compute_unsigned_distance_field([0, 1, 1]).is_err());
+    }
+}
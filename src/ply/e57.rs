@@ -0,0 +1,193 @@
+//! Export to the ASTM E57 3D imaging data file format (ASTM E2807).
+//!
+//! Only the subset needed for a single unstructured point cloud is written:
+//! The file header, one compressed vector binary section holding the `x`, `y`, and `z` coordinates as double
+//! precision floats, and the XML section describing them.
+//!
+//! An E57 file consists of pages of 1024 bytes, each one containing 1020 bytes of data followed by
+//! a CRC-32C checksum. All offsets within the data are either logical (without checksums) or physical (with checksums).
+
+use std::io::Result;
+use byteorder::{ LittleEndian, BigEndian, WriteBytesExt };
+use super::{ Ply, PropertyAccess };
+
+const PAGE_SIZE: usize = 1024;
+const PAGE_DATA_SIZE: usize = PAGE_SIZE - 4;
+const FILE_HEADER_SIZE: usize = 48;
+const SECTION_HEADER_SIZE: usize = 32;
+const COMPRESSED_VECTOR_SECTION: u8 = 1;
+const DATA_PACKET: u8 = 1;
+/// Number of points per data packet, a packet must not exceed 64 KiB.
+const POINTS_PER_PACKET: usize = 2048;
+
+/// CRC-32C (Castagnoli) as used for the page checksums.
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0x82F6_3B78 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Maps an offset in the logical byte stream to the corresponding offset in the file.
+fn physical_offset(logical: usize) -> u64 {
+    ((logical / PAGE_DATA_SIZE) * PAGE_SIZE + logical % PAGE_DATA_SIZE) as u64
+}
+
+/// Derives a GUID from the content, such that equal point clouds yield equal files.
+fn content_guid(data: &[u8]) -> String {
+    let mut h = [0u32; 4];
+    for (k, v) in h.iter_mut().enumerate() {
+        let mut seeded = data.to_vec();
+        seeded.push(k as u8);
+        *v = crc32c(&seeded);
+    }
+    format!("{{{:08X}-{:04X}-{:04X}-{:04X}-{:04X}{:08X}}}",
+        h[0], h[1] >> 16, (h[1] & 0x0FFF) | 0x4000, (h[2] >> 16 & 0x3FFF) | 0x8000, h[2] & 0xFFFF, h[3])
+}
+
+/// Splits the logical byte stream into checksummed pages.
+fn paginate(logical: &[u8]) -> Vec<u8> {
+    let mut file = Vec::with_capacity(physical_offset(logical.len()) as usize + PAGE_SIZE);
+    for chunk in logical.chunks(PAGE_DATA_SIZE) {
+        let mut page = chunk.to_vec();
+        page.resize(PAGE_DATA_SIZE, 0);
+        let crc = crc32c(&page);
+        file.extend_from_slice(&page);
+        file.write_u32::<BigEndian>(crc).unwrap();
+    }
+    file
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Encodes the `x`, `y`, and `z` properties of all `vertex` elements as E57 file.
+    ///
+    /// The file contains a single `data3D` scan with `cartesianX`, `cartesianY`, and `cartesianZ`
+    /// stored as uncompressed double precision floats. All other properties and elements are ignored.
+    /// The GUIDs are derived from the coordinates, writing the same point cloud twice produces identical files.
+    ///
+    /// Requires the `e57` feature.
+    pub fn to_e57_bytes(&self) -> Result<Vec<u8>> {
+        let points = self.read_vertex_positions()?;
+
+        // binary section: header followed by data packets, each with one bytestream per coordinate
+        let mut packets = Vec::new();
+        for chunk in points.chunks(POINTS_PER_PACKET) {
+            let stream_len = chunk.len() * 8;
+            let packet_len = 6 + 3 * 2 + 3 * stream_len;
+            packets.write_u8(DATA_PACKET)?;
+            packets.write_u8(0)?;
+            packets.write_u16::<LittleEndian>((packet_len - 1) as u16)?;
+            packets.write_u16::<LittleEndian>(3)?;
+            for _ in 0..3 {
+                packets.write_u16::<LittleEndian>(stream_len as u16)?;
+            }
+            for k in 0..3 {
+                for p in chunk {
+                    packets.write_f64::<LittleEndian>(p[k])?;
+                }
+            }
+        }
+        let section_offset = FILE_HEADER_SIZE;
+        let data_offset = section_offset + SECTION_HEADER_SIZE;
+        let mut logical = vec![0; section_offset];
+        logical.write_u8(COMPRESSED_VECTOR_SECTION)?;
+        logical.extend_from_slice(&[0; 7]);
+        logical.write_u64::<LittleEndian>((SECTION_HEADER_SIZE + packets.len()) as u64)?;
+        logical.write_u64::<LittleEndian>(physical_offset(data_offset))?;
+        logical.write_u64::<LittleEndian>(0)?;
+        logical.extend_from_slice(&packets);
+
+        let file_guid = content_guid(&packets);
+        let mut scan_seed = packets;
+        scan_seed.push(0xFF);
+        let scan_guid = content_guid(&scan_seed);
+        let coordinates: String = ["cartesianX", "cartesianY", "cartesianZ"].iter()
+            .map(|name| format!("<{} type=\"Float\" precision=\"double\"/>", name))
+            .collect();
+        let xml = format!(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<e57Root type=\"Structure\" xmlns=\"http://www.astm.org/COMMIT/E57/2010-e57-v1.0\">",
+            "<formatName type=\"String\"><![CDATA[ASTM E57 3D Imaging Data File]]></formatName>",
+            "<guid type=\"String\"><![CDATA[{}]]></guid>",
+            "<versionMajor type=\"Integer\">1</versionMajor>",
+            "<versionMinor type=\"Integer\">0</versionMinor>",
+            "<data3D type=\"Vector\" allowHeterogeneousChildren=\"1\">",
+            "<vectorChild type=\"Structure\">",
+            "<guid type=\"String\"><![CDATA[{}]]></guid>",
+            "<points type=\"CompressedVector\" fileOffset=\"{}\" recordCount=\"{}\">",
+            "<prototype type=\"Structure\">{}</prototype>",
+            "<codecs type=\"Vector\" allowHeterogeneousChildren=\"1\"/>",
+            "</points>",
+            "</vectorChild>",
+            "</data3D>",
+            "<images2D type=\"Vector\" allowHeterogeneousChildren=\"1\"/>",
+            "</e57Root>\n"),
+            file_guid, scan_guid, physical_offset(section_offset), points.len(), coordinates);
+        let xml_offset = logical.len();
+        logical.extend_from_slice(xml.as_bytes());
+
+        let page_count = logical.len().div_ceil(PAGE_DATA_SIZE);
+        let mut header = Vec::with_capacity(FILE_HEADER_SIZE);
+        header.extend_from_slice(b"ASTM-E57");
+        header.write_u32::<LittleEndian>(1)?;
+        header.write_u32::<LittleEndian>(0)?;
+        header.write_u64::<LittleEndian>((page_count * PAGE_SIZE) as u64)?;
+        header.write_u64::<LittleEndian>(physical_offset(xml_offset))?;
+        header.write_u64::<LittleEndian>(xml.len() as u64)?;
+        header.write_u64::<LittleEndian>(PAGE_SIZE as u64)?;
+        logical[..FILE_HEADER_SIZE].copy_from_slice(&header);
+
+        Ok(paginate(&logical))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{ ByteOrder, LittleEndian, BigEndian };
+    use super::super::mesh::fixtures;
+    use super::{ crc32c, PAGE_SIZE, PAGE_DATA_SIZE };
+
+    fn logical_bytes(file: &[u8]) -> Vec<u8> {
+        let mut logical = Vec::new();
+        for page in file.chunks(PAGE_SIZE) {
+            assert_eq!(crc32c(&page[..PAGE_DATA_SIZE]), BigEndian::read_u32(&page[PAGE_DATA_SIZE..]));
+            logical.extend_from_slice(&page[..PAGE_DATA_SIZE]);
+        }
+        logical
+    }
+    #[test]
+    fn crc32c_check_value() {
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+    }
+    #[test]
+    fn e57_structure() {
+        // enough points to span several pages and packets
+        let vertices: Vec<_> = (0..3000).map(|i| [i as f64, -(i as f64), 0.5 * i as f64]).collect();
+        let ply = fixtures::from_mesh(&vertices, &[]);
+        let file = ply.to_e57_bytes().unwrap();
+        assert_eq!(&file[..8], b"ASTM-E57");
+        assert_eq!(file.len() % PAGE_SIZE, 0);
+        assert_eq!(LittleEndian::read_u64(&file[16..24]), file.len() as u64);
+        let logical = logical_bytes(&file);
+
+        let xml_physical = LittleEndian::read_u64(&file[24..32]) as usize;
+        let xml_logical = xml_physical / PAGE_SIZE * PAGE_DATA_SIZE + xml_physical % PAGE_SIZE;
+        let xml_len = LittleEndian::read_u64(&file[32..40]) as usize;
+        let xml = std::str::from_utf8(&logical[xml_logical..xml_logical + xml_len]).unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("fileOffset=\"48\" recordCount=\"3000\""));
+
+        // first packet holds 2048 points per coordinate
+        assert_eq!(logical[48], 1);
+        let packet = &logical[80..];
+        assert_eq!(LittleEndian::read_u16(&packet[4..6]), 3);
+        assert_eq!(LittleEndian::read_u16(&packet[6..8]), 2048 * 8);
+        let y = &packet[12 + 2048 * 8..];
+        assert_eq!(LittleEndian::read_f64(&y[7 * 8..]), -7.0);
+        assert_eq!(file, ply.to_e57_bytes().unwrap());
+    }
+}
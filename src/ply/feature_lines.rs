@@ -0,0 +1,68 @@
+//! Detection of sharp edges.
+
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ dot, normalize, invalid_input };
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Returns all edges where the dihedral angle between the two adjacent faces exceeds `dihedral_threshold_deg`.
+    ///
+    /// The dihedral angle is measured between the face normals, hence it is `0` for coplanar faces.
+    /// If the `face` element declares the properties `nx`, `ny`, and `nz`, those normals are used,
+    /// otherwise they are computed from the vertex positions.
+    /// Boundary edges and edges shared by more than two faces are never reported.
+    ///
+    /// Each edge is returned as a pair of vertex indices `(a, b)` with `a < b`, sorted ascending.
+    pub fn compute_feature_lines(&self, dihedral_threshold_deg: f64) -> Result<Vec<(usize, usize)>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let normals = match self.read_scalar_triples("face", ["nx", "ny", "nz"])? {
+            Some(n) => {
+                if n.len() != mesh.faces.len() {
+                    return invalid_input("Number of face normals doesn't match number of faces.");
+                }
+                n.iter().map(normalize).collect()
+            },
+            None => mesh.faces.iter().map(|f| mesh.face_normal(f)).collect::<Vec<_>>(),
+        };
+        let threshold = dihedral_threshold_deg.to_radians();
+        let mut edges = Vec::new();
+        for (edge, faces) in mesh.edge_faces() {
+            if faces.len() != 2 {
+                continue;
+            }
+            let cos = dot(&normals[faces[0]], &normals[faces[1]]).clamp(-1.0, 1.0);
+            if cos.acos() > threshold {
+                edges.push(edge);
+            }
+        }
+        edges.sort_unstable();
+        Ok(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn feature_lines_flat_none() {
+        let ply = fixtures::grid(3);
+        assert!(ply.compute_feature_lines(1.0).unwrap().is_empty());
+    }
+    #[test]
+    fn feature_lines_fold() {
+        // two triangles folded by 90 degrees along the edge (0, 1)
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![1, 0, 3]]);
+        assert_eq!(ply.compute_feature_lines(45.0).unwrap(), vec![(0, 1)]);
+        assert!(ply.compute_feature_lines(95.0).unwrap().is_empty());
+    }
+    #[test]
+    fn feature_lines_icosahedron_all() {
+        // dihedral angle of an icosahedron is ~138.2 deg, the normals differ by ~41.8 deg
+        let ply = fixtures::icosahedron();
+        assert_eq!(ply.compute_feature_lines(40.0).unwrap().len(), 30);
+        assert!(ply.compute_feature_lines(42.0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! Export to glTF 2.0.
+
+use std::io::Result;
+use byteorder::{ ByteOrder, LittleEndian };
+use serde_json::{ json, Value };
+use super::Ply;
+use super::PropertyAccess;
+use super::{ PropertyType, ScalarType };
+use super::mesh::{ Vec3, invalid_input };
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const MODE_POINTS: u32 = 0;
+const MODE_TRIANGLES: u32 = 4;
+
+/// Collects buffer views and accessors while filling the binary buffer.
+struct BufferBuilder {
+    buffer: Vec<u8>,
+    views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+impl BufferBuilder {
+    fn push_vec3(&mut self, values: &[Vec3], with_bounds: bool) -> usize {
+        let offset = self.buffer.len();
+        let mut bytes = [0u8; 4];
+        for v in values {
+            for &c in v {
+                LittleEndian::write_f32(&mut bytes, c as f32);
+                self.buffer.extend_from_slice(&bytes);
+            }
+        }
+        let mut accessor = json!({
+            "bufferView": self.views.len(),
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": "VEC3",
+        });
+        if with_bounds {
+            let (mut min, mut max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+            for v in values {
+                for k in 0..3 {
+                    min[k] = min[k].min(v[k] as f32);
+                    max[k] = max[k].max(v[k] as f32);
+                }
+            }
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+        self.add_view(offset, ARRAY_BUFFER, accessor)
+    }
+    fn push_indices(&mut self, indices: &[u32]) -> usize {
+        let offset = self.buffer.len();
+        let mut bytes = [0u8; 4];
+        for &i in indices {
+            LittleEndian::write_u32(&mut bytes, i);
+            self.buffer.extend_from_slice(&bytes);
+        }
+        let accessor = json!({
+            "bufferView": self.views.len(),
+            "componentType": UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        });
+        self.add_view(offset, ELEMENT_ARRAY_BUFFER, accessor)
+    }
+    fn add_view(&mut self, offset: usize, target: u32, accessor: Value) -> usize {
+        self.views.push(json!({
+            "buffer": 0,
+            "byteOffset": offset,
+            "byteLength": self.buffer.len() - offset,
+            "target": target,
+        }));
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Reads the vertex colors `r`/`g`/`b` (or `red`/`green`/`blue`) normalized to `[0, 1]`.
+    fn read_vertex_colors(&self) -> Result<Option<Vec<Vec3>>> {
+        for names in &[["r", "g", "b"], ["red", "green", "blue"]] {
+            let colors = match self.read_scalar_triples("vertex", *names)? {
+                Some(c) => c,
+                None => continue,
+            };
+            let max = match self.header.elements["vertex"].properties[names[0]].data_type {
+                PropertyType::Scalar(ScalarType::Float) | PropertyType::Scalar(ScalarType::Double) => 1.0,
+                PropertyType::Scalar(ScalarType::UChar) => 255.0,
+                PropertyType::Scalar(ScalarType::UShort) => 65535.0,
+                ref t => return invalid_input(&format!("Unsupported color type `{:?}`.", t)),
+            };
+            return Ok(Some(colors.iter().map(|c| [c[0] / max, c[1] / max, c[2] / max]).collect()));
+        }
+        Ok(None)
+    }
+    /// Converts the mesh to glTF 2.0, returning the JSON document and the binary buffer.
+    ///
+    /// The document contains a single mesh with one primitive:
+    ///
+    /// - `POSITION` from the `x`, `y`, `z` properties of `vertex`,
+    /// - `NORMAL` from `nx`, `ny`, `nz`, if declared,
+    /// - `COLOR_0` from `r`, `g`, `b` (or `red`, `green`, `blue`), if declared.
+    ///   Colors of type `uchar` and `ushort` are normalized to `[0, 1]`.
+    /// - `indices` from the triangulated `face` element.
+    ///   Without faces, the primitive renders the vertices as points.
+    ///
+    /// All attributes are stored as 32 bit floats, the indices as 32 bit unsigned integers.
+    /// The JSON references the binary buffer by the relative URI `buffer.bin`,
+    /// either save it under that name next to the `.gltf` file or replace the URI,
+    /// e.g. with a data URI or by removing it when packing both into a `.glb`.
+    ///
+    /// Requires the `gltf` feature.
+    pub fn to_gltf_json_bytes(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mesh = self.read_mesh()?;
+        if mesh.vertices.len() > u32::MAX as usize {
+            return invalid_input("Too many vertices for glTF.");
+        }
+        let mut builder = BufferBuilder {
+            buffer: Vec::new(),
+            views: Vec::new(),
+            accessors: Vec::new(),
+        };
+        let mut attributes = json!({ "POSITION": builder.push_vec3(&mesh.vertices, true) });
+        if let Some(normals) = self.read_scalar_triples("vertex", ["nx", "ny", "nz"])? {
+            attributes["NORMAL"] = json!(builder.push_vec3(&normals, false));
+        }
+        if let Some(colors) = self.read_vertex_colors()? {
+            attributes["COLOR_0"] = json!(builder.push_vec3(&colors, false));
+        }
+        let mut primitive = json!({ "attributes": attributes, "mode": MODE_POINTS });
+        let triangles = mesh.triangles();
+        if !triangles.is_empty() {
+            let indices: Vec<u32> = triangles.iter().flat_map(|t| t.iter().map(|&i| i as u32)).collect();
+            primitive["indices"] = json!(builder.push_indices(&indices));
+            primitive["mode"] = json!(MODE_TRIANGLES);
+        }
+        let document = json!({
+            "asset": { "version": "2.0", "generator": "ply-rs" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0 }],
+            "meshes": [{ "primitives": [primitive] }],
+            "buffers": [{ "uri": "buffer.bin", "byteLength": builder.buffer.len() }],
+            "bufferViews": builder.views,
+            "accessors": builder.accessors,
+        });
+        Ok((document.to_string().into_bytes(), builder.buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use super::super::mesh::fixtures;
+    #[test]
+    fn gltf_icosahedron() {
+        let ply = fixtures::icosahedron();
+        let (json, bin) = ply.to_gltf_json_bytes().unwrap();
+        let doc: Value = serde_json::from_slice(&json).unwrap();
+        // 12 positions and 60 indices, 4 bytes each
+        assert_eq!(bin.len(), 12 * 3 * 4 + 60 * 4);
+        assert_eq!(doc["buffers"][0]["byteLength"], bin.len());
+        let primitive = &doc["meshes"][0]["primitives"][0];
+        assert_eq!(primitive["mode"], 4);
+        assert!(primitive["attributes"].get("NORMAL").is_none());
+        let indices = &doc["accessors"][primitive["indices"].as_u64().unwrap() as usize];
+        assert_eq!(indices["count"], 60);
+        let position = &doc["accessors"][primitive["attributes"]["POSITION"].as_u64().unwrap() as usize];
+        assert_eq!(position["count"], 12);
+        assert_eq!(position["type"], "VEC3");
+    }
+    #[test]
+    fn gltf_point_cloud() {
+        let ply = fixtures::from_mesh(&[[0.0, 1.0, 2.0], [3.0, 4.0, 5.0]], &[]);
+        let (json, bin) = ply.to_gltf_json_bytes().unwrap();
+        let doc: Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(bin.len(), 24);
+        assert_eq!(doc["meshes"][0]["primitives"][0]["mode"], 0);
+        assert_eq!(doc["accessors"][0]["min"], serde_json::json!([0.0, 1.0, 2.0]));
+        assert_eq!(doc["accessors"][0]["max"], serde_json::json!([3.0, 4.0, 5.0]));
+    }
+}
@@ -0,0 +1,197 @@
+//! Harmonic functions on triangle meshes.
+
+use std::collections::VecDeque;
+use std::io::Result;
+use nalgebra::DMatrix;
+use nalgebra_sparse::{ CooMatrix, CscMatrix };
+use nalgebra_sparse::factorization::CscCholesky;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Mesh, sub, cot, invalid_input };
+
+/// Reverse Cuthill-McKee ordering of the symmetric sparsity pattern of `a`, `order[new] = old`.
+///
+/// Reduces the bandwidth and hence the fill-in of the Cholesky factor,
+/// which would otherwise depend on the arbitrary vertex order of the file.
+fn reverse_cuthill_mckee(a: &CscMatrix<f64>) -> Vec<usize> {
+    let n = a.ncols();
+    let neighbours: Vec<&[usize]> = (0..n).map(|j| a.pattern().lane(j)).collect();
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut by_degree: Vec<usize> = (0..n).collect();
+    by_degree.sort_by_key(|&v| neighbours[v].len());
+    for &start in &by_degree {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            let mut next: Vec<usize> = neighbours[v].iter().cloned().filter(|&u| !visited[u]).collect();
+            next.sort_by_key(|&u| neighbours[u].len());
+            for u in next {
+                visited[u] = true;
+                queue.push_back(u);
+            }
+        }
+    }
+    order.reverse();
+    order
+}
+
+/// Solves `A X = B` for a sparse symmetric positive definite `A` with a sparse Cholesky factorization.
+///
+/// The unknowns are reordered with `reverse_cuthill_mckee()` first to keep the factor sparse.
+/// Returns `None` if the factorization fails or results in non-finite values, e.g. if `A` is singular.
+pub(crate) fn solve_sparse_spd(a: &CscMatrix<f64>, b: &DMatrix<f64>) -> Option<DMatrix<f64>> {
+    let n = a.nrows();
+    if n == 0 {
+        return Some(DMatrix::zeros(0, b.ncols()));
+    }
+    let order = reverse_cuthill_mckee(a);
+    let mut position = vec![0; n];
+    for (new, &old) in order.iter().enumerate() {
+        position[old] = new;
+    }
+    let mut permuted = CooMatrix::new(n, n);
+    for (i, j, &v) in a.triplet_iter() {
+        permuted.push(position[i], position[j], v);
+    }
+    let rhs = DMatrix::from_fn(n, b.ncols(), |r, c| b[(order[r], c)]);
+    let x = CscCholesky::factor(&CscMatrix::from(&permuted)).ok()?.solve(&rhs);
+    if !x.iter().all(|v| v.is_finite()) {
+        return None;
+    }
+    Some(DMatrix::from_fn(n, b.ncols(), |r, c| x[(position[r], c)]))
+}
+
+/// Solves the Laplace equation `L x = 0` with the cotangent Laplacian `L` and Dirichlet conditions.
+///
+/// `fixed[v]` holds the prescribed values of vertex `v`, all of them must have length `dims`.
+/// Returns a `n × dims` matrix with one row per vertex.
+/// The system is sparse and symmetric positive definite if every connected component has a fixed vertex,
+/// it is solved with a sparse Cholesky factorization.
+pub(crate) fn solve_harmonic(mesh: &Mesh, triangles: &[[usize; 3]], fixed: &[Option<Vec<f64>>], dims: usize) -> Result<DMatrix<f64>> {
+    let n = mesh.vertices.len();
+    let mut column = vec![usize::MAX; n];
+    let mut free = 0;
+    for (v, c) in column.iter_mut().enumerate() {
+        if fixed[v].is_none() {
+            *c = free;
+            free += 1;
+        }
+    }
+    let mut lhs = CooMatrix::<f64>::new(free, free);
+    let mut rhs = DMatrix::<f64>::zeros(free, dims);
+    for t in triangles {
+        let p = [&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]];
+        for k in 0..3 {
+            // edge (i, j) is opposite to corner k
+            let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+            let w = cot(&sub(p[i], p[k]), &sub(p[j], p[k])) / 2.0;
+            for &(a, b) in &[(t[i], t[j]), (t[j], t[i])] {
+                if fixed[a].is_some() {
+                    continue;
+                }
+                let row = column[a];
+                lhs.push(row, row, w);
+                match fixed[b] {
+                    Some(ref values) => {
+                        for (d, value) in values.iter().enumerate() {
+                            rhs[(row, d)] += w * value;
+                        }
+                    },
+                    None => lhs.push(row, column[b], -w),
+                }
+            }
+        }
+    }
+    // duplicate entries are summed up by the conversion
+    let solution = match solve_sparse_spd(&CscMatrix::from(&lhs), &rhs) {
+        Some(s) => s,
+        None => return invalid_input("Linear system is singular, every connected component needs at least one constrained vertex."),
+    };
+    Ok(DMatrix::from_fn(n, dims, |v, d| match fixed[v] {
+        Some(ref values) => values[d],
+        None => solution[(column[v], d)],
+    }))
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes a harmonic scalar field that interpolates the values given in `constraints`.
+    ///
+    /// Each constraint `(vertex, value)` fixes the value of a vertex (Dirichlet boundary condition),
+    /// the values of all other vertices solve `L x = 0` with the cotangent Laplacian `L`.
+    /// If a vertex is constrained several times, the last value is used.
+    ///
+    /// Returns one value per vertex.
+    /// Every connected component of the mesh needs at least one constraint and all vertices must be part of a face,
+    /// otherwise the system is singular and an error is returned.
+    ///
+    /// The sparse system is solved with a sparse Cholesky factorization.
+    ///
+    /// Requires the `nalgebra` feature.
+    pub fn compute_harmonic_field(&self, constraints: &[(usize, f64)]) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        if constraints.is_empty() {
+            return invalid_input("At least one constraint is required.");
+        }
+        let mut fixed = vec![None; mesh.vertices.len()];
+        for &(v, value) in constraints {
+            match fixed.get_mut(v) {
+                Some(f) => *f = Some(vec![value]),
+                None => return invalid_input(&format!("Constrained vertex {} doesn't exist.", v)),
+            }
+        }
+        let field = solve_harmonic(&mesh, &mesh.triangles(), &fixed, 1)?;
+        Ok(field.column(0).iter().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn harmonic_field_linear_on_grid() {
+        // a linear function is harmonic, fixing both ends of the grid reproduces it
+        let n = 4;
+        let ply = fixtures::grid(n);
+        let mut constraints = Vec::new();
+        for j in 0..=n {
+            constraints.push((j, 0.0));
+            constraints.push((n * (n + 1) + j, 1.0));
+        }
+        let field = ply.compute_harmonic_field(&constraints).unwrap();
+        assert_eq!(field.len(), 25);
+        for i in 0..=n {
+            for j in 0..=n {
+                assert!((field[i * (n + 1) + j] - i as f64 / n as f64).abs() < 1e-9);
+            }
+        }
+    }
+    #[test]
+    fn harmonic_field_large_grid() {
+        // 10201 vertices, too many for a dense solver
+        let n = 100;
+        let ply = fixtures::grid(n);
+        let constraints = [(0, 0.0), (n * (n + 1), 1.0)];
+        let field = ply.compute_harmonic_field(&constraints).unwrap();
+        assert_eq!(field.len(), (n + 1) * (n + 1));
+        assert!(field.iter().all(|&v| (-1e-9..=1.0 + 1e-9).contains(&v)));
+    }
+    #[test]
+    fn harmonic_field_constant() {
+        let ply = fixtures::icosahedron();
+        let field = ply.compute_harmonic_field(&[(0, 2.0)]).unwrap();
+        assert!(field.iter().all(|&v| (v - 2.0).abs() < 1e-9));
+    }
+    #[test]
+    fn harmonic_field_invalid_constraints_err() {
+        let ply = fixtures::icosahedron();
+        assert!(ply.compute_harmonic_field(&[]).is_err());
+        assert!(ply.compute_harmonic_field(&[(12, 1.0)]).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Detection of self-intersections.
+//!
+//! The triangle-triangle test follows Guigue and Devillers,
+//! "Fast and Robust Triangle-Triangle Overlap Test Using Orientation Predicates" (2003).
+
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Vec3, sub, dot, cross, norm, intersect_ray_triangle };
+
+type Triangle<'a> = [&'a Vec3; 3];
+
+/// Tests the intervals on the intersection line of both planes for overlap.
+fn check_min_max(p1: &Vec3, q1: &Vec3, r1: &Vec3, p2: &Vec3, q2: &Vec3, r2: &Vec3) -> bool {
+    let n = cross(&sub(p2, q1), &sub(p1, q1));
+    if dot(&sub(q2, q1), &n) > 0.0 {
+        return false;
+    }
+    let n = cross(&sub(p2, p1), &sub(r1, p1));
+    dot(&sub(r2, p1), &n) <= 0.0
+}
+
+/// Separating axis test for two triangles in the same plane with normal `n`.
+fn coplanar_overlap(t1: &Triangle, t2: &Triangle, n: &Vec3) -> bool {
+    for t in &[t1, t2] {
+        for k in 0..3 {
+            let axis = cross(n, &sub(t[(k + 1) % 3], t[k]));
+            let project = |t: &Triangle| {
+                let d: Vec<f64> = t.iter().map(|p| dot(p, &axis)).collect();
+                (d.iter().cloned().fold(f64::INFINITY, f64::min), d.iter().cloned().fold(f64::NEG_INFINITY, f64::max))
+            };
+            let (min1, max1) = project(t1);
+            let (min2, max2) = project(t2);
+            if max1 < min2 || max2 < min1 {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Orders the vertices of the second triangle such that `p2` is alone on its side of the plane of the first one.
+fn tri_tri_3d(t1: [&Vec3; 3], t2: [&Vec3; 3], dp2: f64, dq2: f64, dr2: f64, coplanar: &dyn Fn() -> bool) -> bool {
+    let [p1, q1, r1] = t1;
+    let [p2, q2, r2] = t2;
+    if dp2 > 0.0 {
+        if dq2 > 0.0 {
+            check_min_max(p1, r1, q1, r2, p2, q2)
+        } else if dr2 > 0.0 {
+            check_min_max(p1, r1, q1, q2, r2, p2)
+        } else {
+            check_min_max(p1, q1, r1, p2, q2, r2)
+        }
+    } else if dp2 < 0.0 {
+        if dq2 < 0.0 {
+            check_min_max(p1, q1, r1, r2, p2, q2)
+        } else if dr2 < 0.0 {
+            check_min_max(p1, q1, r1, q2, r2, p2)
+        } else {
+            check_min_max(p1, r1, q1, p2, q2, r2)
+        }
+    } else if dq2 < 0.0 {
+        if dr2 >= 0.0 {
+            check_min_max(p1, r1, q1, q2, r2, p2)
+        } else {
+            check_min_max(p1, q1, r1, p2, q2, r2)
+        }
+    } else if dq2 > 0.0 {
+        if dr2 > 0.0 {
+            check_min_max(p1, r1, q1, p2, q2, r2)
+        } else {
+            check_min_max(p1, q1, r1, q2, r2, p2)
+        }
+    } else if dr2 > 0.0 {
+        check_min_max(p1, q1, r1, r2, p2, q2)
+    } else if dr2 < 0.0 {
+        check_min_max(p1, r1, q1, r2, p2, q2)
+    } else {
+        coplanar()
+    }
+}
+
+/// Returns `true` if the triangles `t1` and `t2` share at least one point.
+fn triangles_overlap(t1: &Triangle, t2: &Triangle) -> bool {
+    let [p1, q1, r1] = *t1;
+    let [p2, q2, r2] = *t2;
+    let n2 = cross(&sub(p2, r2), &sub(q2, r2));
+    let dp1 = dot(&sub(p1, r2), &n2);
+    let dq1 = dot(&sub(q1, r2), &n2);
+    let dr1 = dot(&sub(r1, r2), &n2);
+    if dp1 * dq1 > 0.0 && dp1 * dr1 > 0.0 {
+        return false;
+    }
+    let n1 = cross(&sub(q1, p1), &sub(r1, p1));
+    let dp2 = dot(&sub(p2, r1), &n1);
+    let dq2 = dot(&sub(q2, r1), &n1);
+    let dr2 = dot(&sub(r2, r1), &n1);
+    if dp2 * dq2 > 0.0 && dp2 * dr2 > 0.0 {
+        return false;
+    }
+    let coplanar = || coplanar_overlap(t1, t2, &n1);
+    let test = |a: [&Vec3; 3], b: [&Vec3; 3], d: [f64; 3]| tri_tri_3d(a, b, d[0], d[1], d[2], &coplanar);
+    // rotate the first triangle such that p1 is alone on its side of the plane of the second one
+    if dp1 > 0.0 {
+        if dq1 > 0.0 {
+            test([r1, p1, q1], [p2, r2, q2], [dp2, dr2, dq2])
+        } else if dr1 > 0.0 {
+            test([q1, r1, p1], [p2, r2, q2], [dp2, dr2, dq2])
+        } else {
+            test([p1, q1, r1], [p2, q2, r2], [dp2, dq2, dr2])
+        }
+    } else if dp1 < 0.0 {
+        if dq1 < 0.0 {
+            test([r1, p1, q1], [p2, q2, r2], [dp2, dq2, dr2])
+        } else if dr1 < 0.0 {
+            test([q1, r1, p1], [p2, q2, r2], [dp2, dq2, dr2])
+        } else {
+            test([p1, q1, r1], [p2, r2, q2], [dp2, dr2, dq2])
+        }
+    } else if dq1 < 0.0 {
+        if dr1 >= 0.0 {
+            test([q1, r1, p1], [p2, r2, q2], [dp2, dr2, dq2])
+        } else {
+            test([p1, q1, r1], [p2, q2, r2], [dp2, dq2, dr2])
+        }
+    } else if dq1 > 0.0 {
+        if dr1 > 0.0 {
+            test([p1, q1, r1], [p2, r2, q2], [dp2, dr2, dq2])
+        } else {
+            test([q1, r1, p1], [p2, q2, r2], [dp2, dq2, dr2])
+        }
+    } else if dr1 > 0.0 {
+        test([r1, p1, q1], [p2, q2, r2], [dp2, dq2, dr2])
+    } else if dr1 < 0.0 {
+        test([r1, p1, q1], [p2, r2, q2], [dp2, dr2, dq2])
+    } else {
+        coplanar()
+    }
+}
+
+/// Returns `true` if the segment `(a, b)` crosses the triangle `t` strictly between its end points.
+fn segment_crosses(a: &Vec3, b: &Vec3, t: &Triangle) -> bool {
+    matches!(intersect_ray_triangle(a, &sub(b, a), t[0], t[1], t[2]), Some(s) if s < 1.0 - 1e-12)
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Returns all pairs of triangles that intersect each other.
+    ///
+    /// Polygons are split into triangle fans, the indices refer to the resulting triangles,
+    /// which match the face indices for pure triangle meshes.
+    /// Each pair `(i, j)` satisfies `i < j`, the pairs are sorted ascending.
+    ///
+    /// Neighbouring triangles touch by construction:
+    /// Triangles sharing an edge are never reported,
+    /// triangles sharing a single vertex only if an edge of one passes through the other.
+    /// Degenerated triangles without area are ignored.
+    ///
+    /// # Remarks
+    ///
+    /// Every pair of triangles is tested, the complexity is `O(F²)`.
+    /// An accelerated version based on a bounding volume hierarchy is left for future work.
+    pub fn find_self_intersections(&self) -> Result<Vec<(usize, usize)>> {
+        let mesh = self.read_mesh()?;
+        let triangles = mesh.triangles();
+        let corners: Vec<Triangle> = triangles.iter().map(|t| [&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]]).collect();
+        let valid: Vec<bool> = corners.iter().map(|c| norm(&cross(&sub(c[1], c[0]), &sub(c[2], c[0]))) > 0.0).collect();
+        let mut intersections = Vec::new();
+        for i in 0..triangles.len() {
+            if !valid[i] {
+                continue;
+            }
+            for j in (i + 1)..triangles.len() {
+                if !valid[j] {
+                    continue;
+                }
+                let shared: Vec<usize> = triangles[i].iter().cloned().filter(|v| triangles[j].contains(v)).collect();
+                let intersecting = match shared.len() {
+                    0 => triangles_overlap(&corners[i], &corners[j]),
+                    1 => {
+                        let opposite = |t: &[usize; 3]| -> Vec<usize> { t.iter().cloned().filter(|&v| v != shared[0]).collect() };
+                        let (a, b) = (opposite(&triangles[i]), opposite(&triangles[j]));
+                        segment_crosses(&mesh.vertices[a[0]], &mesh.vertices[a[1]], &corners[j])
+                            || segment_crosses(&mesh.vertices[b[0]], &mesh.vertices[b[1]], &corners[i])
+                    },
+                    _ => false,
+                };
+                if intersecting {
+                    intersections.push((i, j));
+                }
+            }
+        }
+        Ok(intersections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn self_intersections_clean() {
+        assert!(fixtures::icosahedron().find_self_intersections().unwrap().is_empty());
+        assert!(fixtures::grid(3).find_self_intersections().unwrap().is_empty());
+    }
+    #[test]
+    fn self_intersections_crossing() {
+        let vertices = [
+            [0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0],
+            [0.5, 0.5, -1.0], [0.5, 0.5, 1.0], [3.0, 3.0, 0.0],
+            [5.0, 5.0, 5.0], [6.0, 5.0, 5.0], [5.0, 6.0, 5.0],
+        ];
+        let ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]);
+        assert_eq!(ply.find_self_intersections().unwrap(), vec![(0, 1)]);
+    }
+    #[test]
+    fn self_intersections_coplanar() {
+        let vertices = [
+            [0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0],
+            [0.5, 0.5, 0.0], [3.0, 0.5, 0.0], [0.5, 3.0, 0.0],
+            [3.0, 3.0, 0.0], [4.0, 3.0, 0.0], [3.0, 4.0, 0.0],
+        ];
+        let ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]);
+        assert_eq!(ply.find_self_intersections().unwrap(), vec![(0, 1)]);
+    }
+    #[test]
+    fn self_intersections_shared_vertex() {
+        // the second triangle shares vertex 0 and pierces the first one
+        let vertices = [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [1.0, 0.2, -1.0], [0.2, 1.0, 1.0]];
+        let ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![0, 3, 4]]);
+        assert_eq!(ply.find_self_intersections().unwrap(), vec![(0, 1)]);
+    }
+}
@@ -3,6 +3,17 @@ use super::ElementDef;
 use super::PropertyDef;
 
 /// Alias to reduce coupling with `LinkedHashMap`
+///
+/// Lookups accept any type the `String` keys borrow as, hence string literals work without allocation:
+///
+/// ```rust
+/// # use ply_rs::ply::{ KeyMap, Property };
+/// let mut element = KeyMap::<Property>::new();
+/// element.insert("x".to_string(), Property::Float(1.0));
+/// assert!(element.contains_key("x"));
+/// assert_eq!(element.get("x"), Some(&Property::Float(1.0)));
+/// assert!(element.remove("x").is_some());
+/// ```
 pub type KeyMap<V> = LinkedHashMap<String, V>;
 
 /// Convenience trait to assure consistency between map key and name attribute of stored element.
@@ -0,0 +1,108 @@
+//! LZ4 compressed PLY files.
+//!
+//! The envelope consists of the magic bytes `PLYZ`, the uncompressed size as little endian `u32`,
+//! followed by the LZ4 block compressed PLY file.
+
+use std::convert::TryFrom;
+use std::io;
+use std::io::{ Result, ErrorKind };
+use byteorder::{ ByteOrder, LittleEndian };
+use super::Ply;
+use super::PropertyAccess;
+use super::DefaultElement;
+use crate::parser::Parser;
+use crate::writer::Writer;
+
+const MAGIC: &[u8; 4] = b"PLYZ";
+const ENVELOPE_SIZE: usize = 8;
+/// Each byte of an LZ4 block expands to at most this many bytes.
+const MAX_EXPANSION: usize = 255;
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Writes the PLY file with `write_ply()` and compresses it with LZ4.
+    ///
+    /// The payload is encoded as declared in `header.encoding`,
+    /// binary encodings usually give the best results.
+    ///
+    /// Requires the `lz4` feature.
+    pub fn to_lz4_bytes(&mut self) -> Result<Vec<u8>> {
+        let mut raw = Vec::new();
+        Writer::new().write_ply(&mut raw, self)?;
+        let size = match u32::try_from(raw.len()) {
+            Ok(s) => s,
+            Err(_) => return Err(io::Error::new(ErrorKind::InvalidInput, "PLY file too large for LZ4 envelope.")),
+        };
+        let compressed = lz4_flex::block::compress(&raw);
+        let mut bytes = Vec::with_capacity(ENVELOPE_SIZE + compressed.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&[0; 4]);
+        LittleEndian::write_u32(&mut bytes[4..ENVELOPE_SIZE], size);
+        bytes.extend_from_slice(&compressed);
+        Ok(bytes)
+    }
+}
+
+impl Ply<DefaultElement> {
+    /// Reads a PLY file created by `to_lz4_bytes()`.
+    ///
+    /// Magic bytes and uncompressed size are validated before the data is inflated.
+    ///
+    /// Requires the `lz4` feature.
+    pub fn from_lz4_bytes(bytes: &[u8]) -> Result<Ply<DefaultElement>> {
+        if bytes.len() < ENVELOPE_SIZE || &bytes[0..4] != MAGIC {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Missing magic bytes `PLYZ`."));
+        }
+        let size = LittleEndian::read_u32(&bytes[4..ENVELOPE_SIZE]) as usize;
+        let max_size = (bytes.len() - ENVELOPE_SIZE).saturating_mul(MAX_EXPANSION);
+        if size > max_size {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Uncompressed size {} exceeds the maximum of {} for {} compressed bytes.", size, max_size, bytes.len() - ENVELOPE_SIZE)
+            ));
+        }
+        let raw = match lz4_flex::block::decompress(&bytes[ENVELOPE_SIZE..], size) {
+            Ok(r) => r,
+            Err(e) => return Err(io::Error::new(ErrorKind::InvalidData, format!("Couldn't decompress LZ4 data: {}", e))),
+        };
+        if raw.len() != size {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Expected {} uncompressed bytes, found {}.", size, raw.len())
+            ));
+        }
+        Ok(Parser::<DefaultElement>::new().read_ply(&mut raw.as_slice())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::super::mesh::fixtures;
+    #[test]
+    fn lz4_round_trip() {
+        let mut ply = fixtures::icosahedron();
+        ply.header.encoding = Encoding::BinaryLittleEndian;
+        let bytes = ply.to_lz4_bytes().unwrap();
+        assert_eq!(&bytes[0..4], b"PLYZ");
+        let read = Ply::<DefaultElement>::from_lz4_bytes(&bytes).unwrap();
+        assert_eq!(read, ply);
+    }
+    #[test]
+    fn lz4_invalid_err() {
+        let mut ply = fixtures::icosahedron();
+        let mut bytes = ply.to_lz4_bytes().unwrap();
+        assert!(Ply::<DefaultElement>::from_lz4_bytes(&bytes[0..6]).is_err());
+        bytes[4] = bytes[4].wrapping_add(1);
+        assert!(Ply::<DefaultElement>::from_lz4_bytes(&bytes).is_err());
+        bytes[0] = b'X';
+        assert!(Ply::<DefaultElement>::from_lz4_bytes(&bytes).is_err());
+    }
+    #[test]
+    fn lz4_forged_size_err() {
+        let err = Ply::<DefaultElement>::from_lz4_bytes(b"PLYZ\xff\xff\xff\xff").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("exceeds"));
+        let err = Ply::<DefaultElement>::from_lz4_bytes(b"PLYZ\xff\xff\xff\xff\x00\x00").unwrap_err();
+        assert!(err.to_string().contains("exceeds"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Detection and repair of non-manifold topology.
+
+use std::collections::HashMap;
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ find, invalid_input };
+
+/// Describes a non-manifold configuration found and repaired by `Ply::repair_manifold()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifoldError {
+    /// The edge `edge` was shared by more than two faces.
+    ///
+    /// All faces except the first two in payload order have been detached from the edge.
+    /// Each of them received a copy of both edge vertices,
+    /// `new_vertices` lists the copies as pairs `(copy of edge.0, copy of edge.1)`.
+    NonManifoldEdge {
+        edge: (usize, usize),
+        detached_faces: Vec<usize>,
+        new_vertices: Vec<(usize, usize)>,
+    },
+    /// The faces around `vertex` formed several fans connected only by the vertex itself.
+    ///
+    /// The first fan keeps `vertex`, each other fan received its own copy, listed in `new_vertices`.
+    NonManifoldVertex {
+        vertex: usize,
+        new_vertices: Vec<usize>,
+    },
+}
+
+fn replace_vertex(face: &mut [usize], old: usize, new: usize) {
+    for v in face.iter_mut() {
+        if *v == old {
+            *v = new;
+        }
+    }
+}
+
+impl<E: PropertyAccess + Clone> Ply<E> {
+    /// Detects non-manifold edges and vertices and splits them by duplicating vertices.
+    ///
+    /// - An edge shared by more than two faces is non-manifold.
+    ///   All but the first two faces get their own copy of the edge vertices.
+    /// - A vertex is non-manifold if its adjacent faces don't form a single fan (i.e. the one-ring is not a disk).
+    ///   Every additional fan gets its own copy of the vertex.
+    ///
+    /// Duplicated vertices are appended to the `vertex` element and carry all properties of the original,
+    /// the `face` element is updated in place and the element counts in the header are adjusted.
+    ///
+    /// Returns the list of repairs that have been applied, an empty list means the mesh was already manifold.
+    pub fn repair_manifold(&mut self) -> Result<Vec<ManifoldError>> {
+        let mut mesh = self.read_mesh()?;
+        let mut repairs = Vec::new();
+        let mut duplicates = Vec::<usize>::new();
+        let mut vertex_count = mesh.vertices.len();
+        let mut duplicate = |v: usize| -> usize {
+            duplicates.push(v);
+            vertex_count += 1;
+            vertex_count - 1
+        };
+
+        // split one edge at a time, detached faces no longer contain the endpoints of the other edges
+        while let Some(((a, b), faces)) = mesh.edge_faces().into_iter().filter(|(_, f)| f.len() > 2).min() {
+            let detached_faces = faces[2..].to_vec();
+            let mut new_vertices = Vec::new();
+            for &fi in &detached_faces {
+                let (new_a, new_b) = (duplicate(a), duplicate(b));
+                replace_vertex(&mut mesh.faces[fi], a, new_a);
+                replace_vertex(&mut mesh.faces[fi], b, new_b);
+                new_vertices.push((new_a, new_b));
+            }
+            repairs.push(ManifoldError::NonManifoldEdge {
+                edge: (a, b),
+                detached_faces,
+                new_vertices,
+            });
+        }
+
+        // faces around each vertex, faces sharing an edge at the vertex are joined into one fan
+        let mut vertex_faces = HashMap::<usize, Vec<usize>>::new();
+        for (fi, f) in mesh.faces.iter().enumerate() {
+            for &v in f {
+                vertex_faces.entry(v).or_default().push(fi);
+            }
+        }
+        let mut vertices: Vec<_> = vertex_faces.into_iter().filter(|(_, f)| f.len() > 1).collect();
+        vertices.sort();
+        for (v, faces) in vertices {
+            let mut parent: Vec<usize> = (0..faces.len()).collect();
+            let mut spoke_owner = HashMap::<usize, usize>::new();
+            for (local, &fi) in faces.iter().enumerate() {
+                let f = &mesh.faces[fi];
+                for k in 0..f.len() {
+                    if f[k] != v {
+                        continue;
+                    }
+                    for &u in &[f[(k + 1) % f.len()], f[(k + f.len() - 1) % f.len()]] {
+                        match spoke_owner.get(&u) {
+                            Some(&other) => {
+                                let (ra, rb) = (find(&mut parent, local), find(&mut parent, other));
+                                parent[ra] = rb;
+                            },
+                            None => { spoke_owner.insert(u, local); },
+                        }
+                    }
+                }
+            }
+            let mut fans = Vec::<usize>::new();
+            let mut fan_vertex = HashMap::<usize, usize>::new();
+            for (local, &fi) in faces.iter().enumerate() {
+                let root = find(&mut parent, local);
+                if !fan_vertex.contains_key(&root) {
+                    let target = if fan_vertex.is_empty() { v } else { duplicate(v) };
+                    fan_vertex.insert(root, target);
+                    if target != v {
+                        fans.push(target);
+                    }
+                }
+                replace_vertex(&mut mesh.faces[fi], v, fan_vertex[&root]);
+            }
+            if !fans.is_empty() {
+                repairs.push(ManifoldError::NonManifoldVertex {
+                    vertex: v,
+                    new_vertices: fans,
+                });
+            }
+        }
+
+        if duplicates.is_empty() {
+            return Ok(repairs);
+        }
+        // Check everything before modifying the payload, so errors leave `self` unchanged.
+        let vertex_count = self.payload.get("vertex").map_or(0, |e| e.len());
+        if let Some(&v) = duplicates.iter().find(|&&v| v >= vertex_count) {
+            return invalid_input(&format!("Vertex {} doesn't exist.", v));
+        }
+        self.write_face_indices(&mesh.faces)?;
+        let vertex_elements = self.payload.entry("vertex".to_string()).or_default();
+        for v in duplicates {
+            let copy = vertex_elements[v].clone();
+            vertex_elements.push(copy);
+        }
+        let new_count = vertex_elements.len();
+        if let Some(def) = self.header.elements.get_mut("vertex") {
+            def.count = new_count;
+        }
+        Ok(repairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    use super::super::{ Property, PropertyAccess, PropertyType, ScalarType };
+    use super::ManifoldError;
+    #[test]
+    fn repair_manifold_clean() {
+        let mut ply = fixtures::icosahedron();
+        assert!(ply.repair_manifold().unwrap().is_empty());
+        assert_eq!(ply, fixtures::icosahedron());
+    }
+    #[test]
+    fn repair_manifold_edge() {
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, -1.0, 0.0]];
+        let mut ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![1, 0, 3], vec![0, 1, 4]]);
+        let repairs = ply.repair_manifold().unwrap();
+        assert_eq!(repairs, vec![ManifoldError::NonManifoldEdge {
+            edge: (0, 1),
+            detached_faces: vec![2],
+            new_vertices: vec![(5, 6)],
+        }]);
+        assert_eq!(ply.header.elements["vertex"].count, 7);
+        assert_eq!(ply.payload["vertex"][5], ply.payload["vertex"][0]);
+        assert!(ply.repair_manifold().unwrap().is_empty());
+    }
+    #[test]
+    fn repair_manifold_edges_sharing_vertex() {
+        // face 4 is the third face on both edges (0, 1) and (0, 2)
+        let vertices = [
+            [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, -1.0, 0.0],
+            [1.0, 0.0, 1.0], [-1.0, 1.0, 0.0], [0.0, 1.0, 1.0],
+        ];
+        let faces = [vec![0, 1, 3], vec![1, 0, 4], vec![2, 0, 5], vec![0, 2, 6], vec![0, 1, 2]];
+        let mut ply = fixtures::from_mesh(&vertices, &faces);
+        let repairs = ply.repair_manifold().unwrap();
+        assert_eq!(repairs, vec![
+            ManifoldError::NonManifoldEdge {
+                edge: (0, 1),
+                detached_faces: vec![4],
+                new_vertices: vec![(7, 8)],
+            },
+            ManifoldError::NonManifoldVertex {
+                vertex: 0,
+                new_vertices: vec![9],
+            },
+            // face 4 still touches vertex 2, but no longer shares an edge with the other faces there
+            ManifoldError::NonManifoldVertex {
+                vertex: 2,
+                new_vertices: vec![10],
+            },
+        ]);
+        // every copy is referenced by a face
+        let mesh = ply.read_mesh().unwrap();
+        assert_eq!(mesh.vertices.len(), 11);
+        for v in 0..mesh.vertices.len() {
+            assert!(mesh.faces.iter().any(|f| f.contains(&v)));
+        }
+        assert!(ply.repair_manifold().unwrap().is_empty());
+    }
+    #[test]
+    fn repair_manifold_vertex() {
+        // two triangles touching in vertex 0 only
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 0.0, 0.0], [-1.0, -1.0, 0.0]];
+        let mut ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![0, 3, 4]]);
+        let repairs = ply.repair_manifold().unwrap();
+        assert_eq!(repairs, vec![ManifoldError::NonManifoldVertex {
+            vertex: 0,
+            new_vertices: vec![5],
+        }]);
+        assert_eq!(ply.payload["vertex"].len(), 6);
+        assert!(ply.repair_manifold().unwrap().is_empty());
+    }
+    #[test]
+    fn repair_manifold_err_unchanged() {
+        // the new vertex 256 doesn't fit into the declared `uchar` indices
+        let mut vertices = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 0.0, 0.0], [-1.0, -1.0, 0.0]];
+        vertices.resize(256, [2.0, 2.0, 2.0]);
+        let mut ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![0, 3, 4]]);
+        ply.header.elements["face"].properties["vertex_indices"].data_type = PropertyType::List(ScalarType::UChar, ScalarType::UChar);
+        for f in ply.payload.get_mut("face").unwrap() {
+            let indices = f.get_list_uint("vertex_indices").unwrap().iter().map(|&i| i as u8).collect();
+            f.insert("vertex_indices".to_string(), Property::ListUChar(indices));
+        }
+        let before = ply.clone();
+        assert!(ply.repair_manifold().is_err());
+        assert_eq!(ply, before);
+    }
+}
@@ -0,0 +1,91 @@
+//! Approximation of the medial axis with the shrinking ball algorithm.
+
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::spatial::PointTree;
+use super::mesh::{ Vec3, add, sub, scale, dot, normalize, invalid_input };
+
+/// Maximal number of shrinking steps per vertex.
+const MAX_STEPS: usize = 64;
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Approximates the medial axis by the centres of maximal balls touching the surface at each vertex.
+    ///
+    /// Implements the shrinking ball algorithm of Ma et al., "3D Medial Axis Point Approximation Using Nearest Neighbors
+    /// and the Normal Field" (2012): A ball with radius `initial_radius` is placed inside the surface,
+    /// touching the vertex `p` with its centre on the inward normal.
+    /// While another vertex `q` lies inside the ball, the ball is shrunk to the one touching both `p` and `q`.
+    /// The centre of the final ball is a point of the medial axis.
+    ///
+    /// `initial_radius` must be larger than the expected local feature size, e.g. the diagonal of the bounding box.
+    /// Vertex normals are read from `nx`, `ny`, and `nz` if declared,
+    /// otherwise they are computed from the counter-clockwise oriented faces.
+    /// Vertices without normal are skipped, hence the result contains at most one point per vertex.
+    ///
+    /// Requires the `kdtree` feature.
+    pub fn approximate_medial_axis(&self, initial_radius: f64) -> Result<Vec<[f64; 3]>> {
+        if initial_radius.is_nan() || initial_radius <= 0.0 {
+            return invalid_input("Initial radius must be positive.");
+        }
+        let mesh = self.read_mesh()?;
+        let normals: Vec<Vec3> = match self.read_scalar_triples("vertex", ["nx", "ny", "nz"])? {
+            Some(n) => n.iter().map(normalize).collect(),
+            None => {
+                mesh.require_faces()?;
+                mesh.vertex_normals(&mesh.triangles())
+            },
+        };
+        let tree = PointTree::new(&mesh.vertices)?;
+        let mut medial_points = Vec::with_capacity(mesh.vertices.len());
+        for (i, (p, n)) in mesh.vertices.iter().zip(normals.iter()).enumerate() {
+            if *n == [0.0; 3] {
+                continue;
+            }
+            let inward = scale(n, -1.0);
+            let mut radius = initial_radius;
+            let mut centre = add(p, &scale(&inward, radius));
+            let mut previous = None;
+            for _ in 0..MAX_STEPS {
+                let (q, d) = match tree.nearest_n(&centre, 2).into_iter().find(|&(q, _)| q != i) {
+                    Some(found) => found,
+                    None => break,
+                };
+                if d >= radius * (1.0 - 1e-9) || previous == Some(q) {
+                    break;
+                }
+                // ball through p and q with its centre on the inward normal of p
+                let pq = sub(&mesh.vertices[q], p);
+                let projection = dot(&inward, &pq);
+                if projection <= 0.0 {
+                    break;
+                }
+                let shrunk = dot(&pq, &pq) / (2.0 * projection);
+                if shrunk >= radius {
+                    break;
+                }
+                radius = shrunk;
+                centre = add(p, &scale(&inward, radius));
+                previous = Some(q);
+            }
+            medial_points.push(centre);
+        }
+        Ok(medial_points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::{ fixtures, norm };
+    #[test]
+    fn medial_axis_sphere_centre() {
+        let ply = fixtures::icosahedron();
+        let points = ply.approximate_medial_axis(10.0).unwrap();
+        assert_eq!(points.len(), 12);
+        assert!(points.iter().all(|p| norm(p) < 1e-9));
+    }
+    #[test]
+    fn medial_axis_invalid_radius_err() {
+        assert!(fixtures::icosahedron().approximate_medial_axis(0.0).is_err());
+    }
+}
@@ -0,0 +1,636 @@
+//! Interprets the payload of a `Ply` as a polygon mesh.
+//!
+//! The geometric algorithms operate on the common conventions:
+//! an element `vertex` with the properties `x`, `y`, and `z`
+//! and an element `face` with a list property `vertex_indices` (or `vertex_index`).
+
+use std::io;
+use std::io::{ Result, ErrorKind };
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use super::Ply;
+use super::PropertyAccess;
+use super::{ Property, PropertyDef, PropertyType, ScalarType };
+
+pub type Vec3 = [f64; 3];
+
+pub fn add(a: &Vec3, b: &Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+pub fn sub(a: &Vec3, b: &Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+pub fn scale(a: &Vec3, s: f64) -> Vec3 {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+pub fn dot(a: &Vec3, b: &Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+pub fn cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+pub fn norm(a: &Vec3) -> f64 {
+    dot(a, a).sqrt()
+}
+pub fn dist(a: &Vec3, b: &Vec3) -> f64 {
+    norm(&sub(a, b))
+}
+/// Returns `a` scaled to unit length, or the zero vector if `a` has no length.
+pub fn normalize(a: &Vec3) -> Vec3 {
+    let n = norm(a);
+    if n > 0.0 {
+        scale(a, 1.0 / n)
+    } else {
+        [0.0; 3]
+    }
+}
+/// Cotangent of the angle between `a` and `b`.
+pub fn cot(a: &Vec3, b: &Vec3) -> f64 {
+    let s = norm(&cross(a, b));
+    if s > 0.0 {
+        dot(a, b) / s
+    } else {
+        0.0
+    }
+}
+/// Angle between `a` and `b` in radians.
+pub fn angle(a: &Vec3, b: &Vec3) -> f64 {
+    norm(&cross(a, b)).atan2(dot(a, b))
+}
+/// Intersects the ray `origin + t * direction` with the triangle `(a, b, c)` (Möller-Trumbore).
+///
+/// Returns the ray parameter `t` of the hit, only hits with `t > 0` are reported.
+pub fn intersect_ray_triangle(origin: &Vec3, direction: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3) -> Option<f64> {
+    const EPSILON: f64 = 1e-12;
+    let e1 = sub(b, a);
+    let e2 = sub(c, a);
+    let p = cross(direction, &e2);
+    let det = dot(&e1, &p);
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let s = sub(origin, a);
+    let u = dot(&s, &p) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = cross(&s, &e1);
+    let v = dot(direction, &q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = dot(&e2, &q) / det;
+    if t > EPSILON {
+        Some(t)
+    } else {
+        None
+    }
+}
+/// Returns the point of the triangle `(a, b, c)` closest to `p`.
+///
+/// See Ericson, "Real-Time Collision Detection" (2004), section 5.1.5.
+#[cfg_attr(not(any(feature = "ndarray")), allow(dead_code))]
+pub fn closest_point_on_triangle(p: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3) -> Vec3 {
+    let ab = sub(b, a);
+    let ac = sub(c, a);
+    let ap = sub(p, a);
+    let (d1, d2) = (dot(&ab, &ap), dot(&ac, &ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return *a;
+    }
+    let bp = sub(p, b);
+    let (d3, d4) = (dot(&ab, &bp), dot(&ac, &bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return *b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return add(a, &scale(&ab, d1 / (d1 - d3)));
+    }
+    let cp = sub(p, c);
+    let (d5, d6) = (dot(&ab, &cp), dot(&ac, &cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return *c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return add(a, &scale(&ac, d2 / (d2 - d6)));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return add(b, &scale(&sub(c, b), (d4 - d3) / ((d4 - d3) + (d5 - d6))));
+    }
+    let denominator = va + vb + vc;
+    if denominator == 0.0 {
+        // degenerated triangle, all points are collinear
+        return *a;
+    }
+    let (v, w) = (vb / denominator, vc / denominator);
+    add(a, &add(&scale(&ab, v), &scale(&ac, w)))
+}
+
+/// Reads a scalar property of any type and converts it to `f64`.
+pub fn get_scalar_as_f64<E: PropertyAccess>(element: &E, property_def: &PropertyDef) -> Option<f64> {
+    let key = &property_def.name;
+    let scalar_type = match property_def.data_type {
+        PropertyType::Scalar(ref t) => t,
+        PropertyType::List(_, _) => return None,
+    };
+    match *scalar_type {
+        ScalarType::Char => element.get_char(key).map(|v| v as f64),
+        ScalarType::UChar => element.get_uchar(key).map(|v| v as f64),
+        ScalarType::Short => element.get_short(key).map(|v| v as f64),
+        ScalarType::UShort => element.get_ushort(key).map(|v| v as f64),
+        ScalarType::Int => element.get_int(key).map(|v| v as f64),
+        ScalarType::UInt => element.get_uint(key).map(|v| v as f64),
+        ScalarType::Long => element.get_long(key).map(|v| v as f64),
+        ScalarType::ULong => element.get_ulong(key).map(|v| v as f64),
+        ScalarType::Float => element.get_float(key).map(|v| v as f64),
+        ScalarType::Double => element.get_double(key),
+    }
+}
+
+/// Reads a list property of an integer type as indices. Negative entries are rejected.
+pub fn get_list_as_indices<E: PropertyAccess>(element: &E, property_def: &PropertyDef) -> Option<Vec<usize>> {
+    fn convert<T: Copy + Into<i64>>(list: &[T]) -> Option<Vec<usize>> {
+        list.iter().map(|&v| {
+            let v: i64 = v.into();
+            if v < 0 { None } else { Some(v as usize) }
+        }).collect()
+    }
+    let key = &property_def.name;
+    let scalar_type = match property_def.data_type {
+        PropertyType::List(_, ref t) => t,
+        PropertyType::Scalar(_) => return None,
+    };
+    match *scalar_type {
+        ScalarType::Char => convert(element.get_list_char(key)?),
+        ScalarType::UChar => convert(element.get_list_uchar(key)?),
+        ScalarType::Short => convert(element.get_list_short(key)?),
+        ScalarType::UShort => convert(element.get_list_ushort(key)?),
+        ScalarType::Int => convert(element.get_list_int(key)?),
+        ScalarType::UInt => convert(element.get_list_uint(key)?),
+        ScalarType::Long => convert(element.get_list_long(key)?),
+        ScalarType::ULong => element.get_list_ulong(key)?.iter().map(|&v| usize::try_from(v).ok()).collect(),
+        ScalarType::Float | ScalarType::Double => None,
+    }
+}
+
+/// Converts `value` to a scalar property of type `scalar_type`, integers are rounded and saturated.
+pub fn scalar_from_f64(scalar_type: &ScalarType, value: f64) -> Property {
+    match *scalar_type {
+        ScalarType::Char => Property::Char(value.round() as i8),
+        ScalarType::UChar => Property::UChar(value.round() as u8),
+        ScalarType::Short => Property::Short(value.round() as i16),
+        ScalarType::UShort => Property::UShort(value.round() as u16),
+        ScalarType::Int => Property::Int(value.round() as i32),
+        ScalarType::UInt => Property::UInt(value.round() as u32),
+        ScalarType::Long => Property::Long(value.round() as i64),
+        ScalarType::ULong => Property::ULong(value.round() as u64),
+        ScalarType::Float => Property::Float(value as f32),
+        ScalarType::Double => Property::Double(value),
+    }
+}
+
+/// Converts indices to a list property of the type declared in `property_def`.
+///
+/// Returns `None` if an index doesn't fit into the declared type.
+pub fn indices_to_property(property_def: &PropertyDef, indices: &[usize]) -> Option<Property> {
+    fn convert<T: TryFrom<usize>>(indices: &[usize]) -> Option<Vec<T>> {
+        indices.iter().map(|&i| T::try_from(i).ok()).collect()
+    }
+    let scalar_type = match property_def.data_type {
+        PropertyType::List(_, ref t) => t,
+        PropertyType::Scalar(_) => return None,
+    };
+    Some(match *scalar_type {
+        ScalarType::Char => Property::ListChar(convert(indices)?),
+        ScalarType::UChar => Property::ListUChar(convert(indices)?),
+        ScalarType::Short => Property::ListShort(convert(indices)?),
+        ScalarType::UShort => Property::ListUShort(convert(indices)?),
+        ScalarType::Int => Property::ListInt(convert(indices)?),
+        ScalarType::UInt => Property::ListUInt(convert(indices)?),
+        ScalarType::Long => Property::ListLong(convert(indices)?),
+        ScalarType::ULong => Property::ListULong(convert(indices)?),
+        ScalarType::Float | ScalarType::Double => return None,
+    })
+}
+
+/// Returns the representative of `i` in the union-find forest `parent`, compressing the path.
+pub fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut i = i;
+    while parent[i] != root {
+        let next = parent[i];
+        parent[i] = root;
+        i = next;
+    }
+    root
+}
+
+pub fn invalid_input<T>(message: &str) -> Result<T> {
+    Err(io::Error::new(ErrorKind::InvalidInput, message.to_string()))
+}
+
+/// Vertex positions and polygons extracted from a `Ply`.
+#[derive(Debug, Clone)]
+pub struct Mesh {
+    pub vertices: Vec<Vec3>,
+    pub faces: Vec<Vec<usize>>,
+}
+
+impl Mesh {
+    /// Splits every polygon into a fan of triangles.
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut triangles = Vec::new();
+        for f in &self.faces {
+            for i in 1..f.len().saturating_sub(1) {
+                triangles.push([f[0], f[i], f[i + 1]]);
+            }
+        }
+        triangles
+    }
+    /// Returns every undirected polygon edge once, as `(min, max)` in ascending order.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = Vec::new();
+        for f in &self.faces {
+            for k in 0..f.len() {
+                let (a, b) = (f[k], f[(k + 1) % f.len()]);
+                if a != b {
+                    edges.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+    /// Lists the vertices connected to each vertex by a polygon edge.
+    #[cfg_attr(not(any(feature = "nalgebra")), allow(dead_code))]
+    pub fn vertex_neighbours(&self) -> Vec<Vec<usize>> {
+        let mut neighbours = vec![Vec::new(); self.vertices.len()];
+        for (a, b) in self.edges() {
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        }
+        neighbours
+    }
+    /// Returns an error if the mesh has no faces.
+    pub fn require_faces(&self) -> Result<()> {
+        if self.faces.is_empty() {
+            return invalid_input("Mesh has no faces.");
+        }
+        Ok(())
+    }
+    pub fn triangle_area(&self, t: &[usize; 3]) -> f64 {
+        let (a, b, c) = (&self.vertices[t[0]], &self.vertices[t[1]], &self.vertices[t[2]]);
+        0.5 * norm(&cross(&sub(b, a), &sub(c, a)))
+    }
+    /// Maps each undirected edge `(min, max)` to the triangles it belongs to.
+    pub fn edge_triangles(triangles: &[[usize; 3]]) -> HashMap<(usize, usize), Vec<usize>> {
+        let mut map = HashMap::<(usize, usize), Vec<usize>>::new();
+        for (ti, t) in triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                map.entry((a.min(b), a.max(b))).or_default().push(ti);
+            }
+        }
+        map
+    }
+    /// Flags vertices that lie on an edge with only one adjacent triangle.
+    pub fn boundary_vertices(&self, triangles: &[[usize; 3]]) -> Vec<bool> {
+        let mut boundary = vec![false; self.vertices.len()];
+        for ((a, b), ts) in Mesh::edge_triangles(triangles) {
+            if ts.len() == 1 {
+                boundary[a] = true;
+                boundary[b] = true;
+            }
+        }
+        boundary
+    }
+    /// Maps each undirected polygon edge `(min, max)` to the faces it belongs to.
+    pub fn edge_faces(&self) -> HashMap<(usize, usize), Vec<usize>> {
+        let mut map = HashMap::<(usize, usize), Vec<usize>>::new();
+        for (fi, f) in self.faces.iter().enumerate() {
+            for k in 0..f.len() {
+                let (a, b) = (f[k], f[(k + 1) % f.len()]);
+                if a != b {
+                    map.entry((a.min(b), a.max(b))).or_default().push(fi);
+                }
+            }
+        }
+        map
+    }
+    /// Unit normal of a polygon, computed with Newell's method.
+    pub fn face_normal(&self, face: &[usize]) -> Vec3 {
+        let mut n = [0.0; 3];
+        for k in 0..face.len() {
+            let (a, b) = (&self.vertices[face[k]], &self.vertices[face[(k + 1) % face.len()]]);
+            n = add(&n, &cross(a, b));
+        }
+        normalize(&n)
+    }
+    /// Collects the closed loops of boundary edges, following the orientation of the triangles.
+    ///
+    /// A boundary edge is a directed triangle edge without its opposite counterpart.
+    #[cfg_attr(not(any(feature = "nalgebra")), allow(dead_code))]
+    pub fn boundary_loops(triangles: &[[usize; 3]]) -> Vec<Vec<usize>> {
+        let mut half_edges = HashMap::<(usize, usize), usize>::new();
+        for t in triangles {
+            for k in 0..3 {
+                *half_edges.entry((t[k], t[(k + 1) % 3])).or_default() += 1;
+            }
+        }
+        let mut next = HashMap::<usize, usize>::new();
+        for &(a, b) in half_edges.keys() {
+            if !half_edges.contains_key(&(b, a)) {
+                next.insert(a, b);
+            }
+        }
+        let mut starts: Vec<usize> = next.keys().cloned().collect();
+        starts.sort_unstable();
+        let mut loops = Vec::new();
+        for start in starts {
+            if !next.contains_key(&start) {
+                continue;
+            }
+            let mut boundary_loop = vec![start];
+            let mut current = next.remove(&start).unwrap();
+            while current != start {
+                boundary_loop.push(current);
+                current = match next.remove(&current) {
+                    Some(n) => n,
+                    None => break,
+                };
+            }
+            loops.push(boundary_loop);
+        }
+        loops
+    }
+    /// Area weighted vertex normals.
+    pub fn vertex_normals(&self, triangles: &[[usize; 3]]) -> Vec<Vec3> {
+        let mut normals = vec![[0.0; 3]; self.vertices.len()];
+        for t in triangles {
+            let (a, b, c) = (&self.vertices[t[0]], &self.vertices[t[1]], &self.vertices[t[2]]);
+            let n = cross(&sub(b, a), &sub(c, a));
+            for &i in t {
+                normals[i] = add(&normals[i], &n);
+            }
+        }
+        normals.iter().map(normalize).collect()
+    }
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Reads three scalar properties of every element called `element_name`.
+    ///
+    /// Returns `None` if the element or one of the properties isn't declared in the header.
+    pub(crate) fn read_scalar_triples(&self, element_name: &str, names: [&str; 3]) -> Result<Option<Vec<Vec3>>> {
+        let def = match self.header.elements.get(element_name) {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let mut coordinates = Vec::new();
+        for name in &names {
+            match def.properties.get(*name) {
+                Some(p) => match p.data_type {
+                    PropertyType::Scalar(_) => coordinates.push(p),
+                    PropertyType::List(_, _) => return invalid_input(&format!("Property `{}` of `{}` must be a scalar.", name, element_name)),
+                },
+                None => return Ok(None),
+            }
+        }
+        let elements = match self.payload.get(element_name) {
+            Some(e) => e,
+            None => return Ok(Some(Vec::new())),
+        };
+        let mut triples = Vec::with_capacity(elements.len());
+        for (i, e) in elements.iter().enumerate() {
+            let mut p = [0.0; 3];
+            for (c, d) in coordinates.iter().enumerate() {
+                p[c] = match get_scalar_as_f64(e, d) {
+                    Some(v) => v,
+                    None => return invalid_input(&format!("Element {} of `{}` has no value for property `{}`.", i, element_name, d.name)),
+                };
+            }
+            triples.push(p);
+        }
+        Ok(Some(triples))
+    }
+    /// Reads `x`, `y`, and `z` of every `vertex` element.
+    pub(crate) fn read_vertex_positions(&self) -> Result<Vec<Vec3>> {
+        match self.read_scalar_triples("vertex", ["x", "y", "z"])? {
+            Some(p) => Ok(p),
+            None => invalid_input("Element `vertex` with properties `x`, `y`, and `z` required."),
+        }
+    }
+    /// Overwrites `x`, `y`, and `z` of every `vertex` element, converted to the declared types.
+    pub(crate) fn write_vertex_positions(&mut self, positions: &[Vec3]) -> Result<()> {
+        let def = match self.header.elements.get("vertex") {
+            Some(d) => d,
+            None => return invalid_input("No element `vertex` declared in header."),
+        };
+        let mut types = Vec::with_capacity(3);
+        for name in &["x", "y", "z"] {
+            match def.properties.get(*name).map(|p| &p.data_type) {
+                Some(PropertyType::Scalar(t)) => types.push((name.to_string(), t.clone())),
+                _ => return invalid_input(&format!("Property `{}` of `vertex` must be a scalar.", name)),
+            }
+        }
+        let elements = self.payload.entry("vertex".to_string()).or_default();
+        if elements.len() != positions.len() {
+            return invalid_input("Number of vertices changed unexpectedly.");
+        }
+        for (e, p) in elements.iter_mut().zip(positions) {
+            for ((name, t), &v) in types.iter().zip(p.iter()) {
+                e.set_property(name.clone(), scalar_from_f64(t, v));
+            }
+        }
+        Ok(())
+    }
+    /// Reads the scalar properties `defs` of every element called `element_name` as rows of `f64`.
+    pub(crate) fn read_scalar_rows(&self, element_name: &str, defs: &[PropertyDef]) -> Result<Vec<Vec<f64>>> {
+        let elements = self.payload.get(element_name).map(|e| e.as_slice()).unwrap_or(&[]);
+        let mut rows = Vec::with_capacity(elements.len());
+        for (i, e) in elements.iter().enumerate() {
+            let mut row = Vec::with_capacity(defs.len());
+            for d in defs {
+                match get_scalar_as_f64(e, d) {
+                    Some(v) => row.push(v),
+                    None => return invalid_input(&format!("Element {} of `{}` has no scalar value for property `{}`.", i, element_name, d.name)),
+                }
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+    /// Returns the declaration of the index list of the `face` element, `None` if there is no `face` element.
+    fn face_index_property(&self) -> Result<Option<&PropertyDef>> {
+        let def = match self.header.elements.get("face") {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let prop = match def.properties.get("vertex_indices").or_else(|| def.properties.get("vertex_index")) {
+            Some(p) => p,
+            None => return invalid_input("Element `face` has neither `vertex_indices` nor `vertex_index`."),
+        };
+        match prop.data_type {
+            PropertyType::List(_, ScalarType::Float) | PropertyType::List(_, ScalarType::Double) =>
+                invalid_input(&format!("Property `{}` of `face` must be a list of integers.", prop.name)),
+            PropertyType::List(_, _) => Ok(Some(prop)),
+            PropertyType::Scalar(_) => invalid_input(&format!("Property `{}` of `face` must be a list.", prop.name)),
+        }
+    }
+    /// Converts index lists to the declared list type of the `face` element, without modifying any face.
+    ///
+    /// Returns the name of the index property and one property per entry of `faces`.
+    pub(crate) fn face_index_properties(&self, faces: &[Vec<usize>]) -> Result<(String, Vec<Property>)> {
+        let prop = match self.face_index_property()? {
+            Some(p) => p,
+            None => return invalid_input("No element `face` declared in header."),
+        };
+        let mut properties = Vec::with_capacity(faces.len());
+        for f in faces {
+            match indices_to_property(prop, f) {
+                Some(p) => properties.push(p),
+                None => return invalid_input(&format!("Indices of face {:?} don't fit into `{:?}`.", f, prop.data_type)),
+            }
+        }
+        Ok((prop.name.clone(), properties))
+    }
+    /// Replaces the index lists of the existing `face` elements, `faces` must contain one entry per element.
+    ///
+    /// The indices are stored with the declared list type.
+    /// On error, no face is modified.
+    pub(crate) fn write_face_indices(&mut self, faces: &[Vec<usize>]) -> Result<()> {
+        if self.payload.get("face").map_or(0, |e| e.len()) != faces.len() {
+            return invalid_input("Number of faces changed unexpectedly.");
+        }
+        let (name, properties) = self.face_index_properties(faces)?;
+        let elements = self.payload.entry("face".to_string()).or_default();
+        for (e, p) in elements.iter_mut().zip(properties) {
+            e.set_property(name.clone(), p);
+        }
+        Ok(())
+    }
+    /// Reads the vertex indices of every `face` element.
+    ///
+    /// Returns an empty list if there is no `face` element.
+    pub(crate) fn read_face_indices(&self, vertex_count: usize) -> Result<Vec<Vec<usize>>> {
+        let prop = match self.face_index_property()? {
+            Some(p) => p,
+            None => return Ok(Vec::new()),
+        };
+        let elements = match self.payload.get("face") {
+            Some(e) => e,
+            None => return Ok(Vec::new()),
+        };
+        let mut faces = Vec::with_capacity(elements.len());
+        for (i, e) in elements.iter().enumerate() {
+            let face = match get_list_as_indices(e, prop) {
+                Some(f) => f,
+                None => return invalid_input(&format!("Face {} has no valid index list `{}`.", i, prop.name)),
+            };
+            if let Some(&v) = face.iter().find(|&&v| v >= vertex_count) {
+                return invalid_input(&format!("Face {} references vertex {}, but there are only {} vertices.", i, v, vertex_count));
+            }
+            faces.push(face);
+        }
+        Ok(faces)
+    }
+    /// Reads vertex positions and faces.
+    pub(crate) fn read_mesh(&self) -> Result<Mesh> {
+        let vertices = self.read_vertex_positions()?;
+        let faces = self.read_face_indices(vertices.len())?;
+        Ok(Mesh { vertices, faces })
+    }
+}
+
+#[cfg(test)]
+pub mod fixtures {
+    use super::super::*;
+    use super::Vec3;
+
+    /// Builds a `Ply` with double precision vertices and a `vertex_indices` face list.
+    pub fn from_mesh(vertices: &[Vec3], faces: &[Vec<usize>]) -> Ply<DefaultElement> {
+        let mut ply = Ply::<DefaultElement>::new();
+        let mut vertex_def = ElementDef::new("vertex".to_string());
+        for name in &["x", "y", "z"] {
+            vertex_def.properties.add(PropertyDef::new(name.to_string(), PropertyType::Scalar(ScalarType::Double)));
+        }
+        let mut face_def = ElementDef::new("face".to_string());
+        face_def.properties.add(PropertyDef::new("vertex_indices".to_string(), PropertyType::List(ScalarType::UChar, ScalarType::UInt)));
+        ply.header.elements.add(vertex_def);
+        ply.header.elements.add(face_def);
+        ply.payload.insert("vertex".to_string(), vertices.iter().map(|v| {
+            let mut e = DefaultElement::new();
+            e.insert("x".to_string(), Property::Double(v[0]));
+            e.insert("y".to_string(), Property::Double(v[1]));
+            e.insert("z".to_string(), Property::Double(v[2]));
+            e
+        }).collect());
+        ply.payload.insert("face".to_string(), faces.iter().map(|f| {
+            let mut e = DefaultElement::new();
+            e.insert("vertex_indices".to_string(), Property::ListUInt(f.iter().map(|&i| i as u32).collect()));
+            e
+        }).collect());
+        ply.make_consistent().unwrap();
+        ply
+    }
+
+    /// Regular icosahedron with outward facing triangles.
+    pub fn icosahedron() -> Ply<DefaultElement> {
+        let t = (1.0 + 5f64.sqrt()) / 2.0;
+        let vertices = [
+            [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+            [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+            [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+        ];
+        let faces: Vec<Vec<usize>> = vec![
+            vec![0, 11, 5], vec![0, 5, 1], vec![0, 1, 7], vec![0, 7, 10], vec![0, 10, 11],
+            vec![1, 5, 9], vec![5, 11, 4], vec![11, 10, 2], vec![10, 7, 6], vec![7, 1, 8],
+            vec![3, 9, 4], vec![3, 4, 2], vec![3, 2, 6], vec![3, 6, 8], vec![3, 8, 9],
+            vec![4, 9, 5], vec![2, 4, 11], vec![6, 2, 10], vec![8, 6, 7], vec![9, 8, 1],
+        ];
+        from_mesh(&vertices, &faces)
+    }
+
+    /// Flat `n` x `n` grid of unit quads in the xy-plane, each split into two triangles.
+    ///
+    /// Vertex `(i, j)` has index `i * (n + 1) + j`.
+    pub fn grid(n: usize) -> Ply<DefaultElement> {
+        let mut vertices = Vec::new();
+        for i in 0..=n {
+            for j in 0..=n {
+                vertices.push([j as f64, i as f64, 0.0]);
+            }
+        }
+        let mut faces = Vec::new();
+        for i in 0..n {
+            for j in 0..n {
+                let a = i * (n + 1) + j;
+                let b = a + n + 1;
+                faces.push(vec![a, a + 1, b + 1]);
+                faces.push(vec![a, b + 1, b]);
+            }
+        }
+        from_mesh(&vertices, &faces)
+    }
+
+    /// Declares and stores the face indices of `ply` as `uchar`, limiting it to 256 vertices.
+    pub fn with_uchar_indices(mut ply: Ply<DefaultElement>) -> Ply<DefaultElement> {
+        ply.header.elements["face"].properties["vertex_indices"].data_type = PropertyType::List(ScalarType::UChar, ScalarType::UChar);
+        for f in ply.payload.get_mut("face").unwrap() {
+            let indices = f.get_list_uint("vertex_indices").unwrap().iter().map(|&i| i as u8).collect();
+            f.insert("vertex_indices".to_string(), Property::ListUChar(indices));
+        }
+        ply
+    }
+}
@@ -1,17 +1,117 @@
 //! Definitions used to model PLY files.
 
 
+mod barycentre;
+
+mod bounding_box;
+
+#[cfg(feature = "rand")]
+mod clustering;
+
+mod compressibility;
+pub use self::compressibility::*;
+
 mod consistency;
 pub use self::consistency::*;
 
+mod coplanar;
+
+mod curvature;
+
+mod curvature_tensor;
+
 mod default_element;
 pub use self::default_element::*;
 
+mod depth;
+
+#[cfg(feature = "e57")]
+mod e57;
+
+#[cfg(feature = "ndarray")]
+mod distance_field;
+
+mod feature_lines;
+
+#[cfg(feature = "gltf")]
+mod gltf;
+
+#[cfg(feature = "nalgebra")]
+mod harmonic;
+
+mod intersection;
+
 mod key_map;
 pub use self::key_map::*;
 
+#[cfg(feature = "lz4")]
+mod lz4;
+
+mod manifold;
+pub use self::manifold::*;
+
+#[cfg(feature = "kdtree")]
+mod medial_axis;
+
+mod mesh;
+
+#[cfg(feature = "rand")]
+mod noise;
+
+mod normals;
+
+#[cfg(feature = "rand")]
+mod occlusion;
+
+#[cfg(feature = "nalgebra")]
+mod parameterisation;
+
+mod persistence;
+
 mod ply_data_structure;
 pub use self::ply_data_structure::*;
 
+mod primitives;
+
 mod property;
 pub use self::property::*;
+
+/// Derives `PropertyAccess` for structs, see the `ply-rs-derive` crate.
+///
+/// Requires the `derive` feature.
+#[cfg(feature = "derive")]
+pub use ply_rs_derive::PropertyAccess;
+
+mod quality;
+pub use self::quality::*;
+
+mod remesh;
+
+#[cfg(feature = "nalgebra")]
+mod saliency;
+
+#[cfg(feature = "rand")]
+mod sampling;
+
+#[cfg(feature = "serde")]
+mod serialization;
+
+mod skeleton;
+
+mod solid_angle;
+
+#[cfg(feature = "kdtree")]
+mod spatial;
+
+#[cfg(feature = "nalgebra")]
+mod spectral;
+
+mod stats;
+pub use self::stats::*;
+
+mod subdivision;
+
+mod visibility;
+
+#[cfg(feature = "zstd")]
+mod zstandard;
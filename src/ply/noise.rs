@@ -0,0 +1,96 @@
+//! Synthetic noise for testing algorithms on imperfect data.
+
+use std::f64::consts::PI;
+use std::io::Result;
+use rand::{ Rng, SeedableRng };
+use rand::rngs::StdRng;
+use rand::seq::index;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::invalid_input;
+
+/// Draws from the standard normal distribution with the Box-Muller transform.
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Displaces every vertex by independent Gaussian noise with mean `0` and standard deviation `sigma` per coordinate.
+    ///
+    /// The `x`, `y`, and `z` properties are overwritten, converted to their declared types.
+    /// The same `seed` always produces the same noise.
+    ///
+    /// Requires the `rand` feature.
+    pub fn add_gaussian_noise(&mut self, sigma: f64, seed: u64) -> Result<()> {
+        if sigma.is_nan() || sigma < 0.0 {
+            return invalid_input("Standard deviation must not be negative.");
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut positions = self.read_vertex_positions()?;
+        for p in positions.iter_mut() {
+            for c in p.iter_mut() {
+                *c += sigma * standard_normal(&mut rng);
+            }
+        }
+        self.write_vertex_positions(&positions)
+    }
+    /// Moves a `fraction` of all vertices to uniformly distributed random positions within the bounding box.
+    ///
+    /// `fraction` must lie in `[0, 1]`, the number of affected vertices is rounded to the nearest integer.
+    /// The `x`, `y`, and `z` properties are overwritten, converted to their declared types.
+    /// The same `seed` always produces the same noise.
+    ///
+    /// Requires the `rand` feature.
+    pub fn add_salt_pepper_noise(&mut self, fraction: f64, seed: u64) -> Result<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return invalid_input("Fraction must lie in [0, 1].");
+        }
+        let mut positions = self.read_vertex_positions()?;
+        let (mut min, mut max) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for p in &positions {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let count = (fraction * positions.len() as f64).round() as usize;
+        for v in index::sample(&mut rng, positions.len(), count).into_vec() {
+            for k in 0..3 {
+                positions[v][k] = min[k] + (max[k] - min[k]) * rng.gen::<f64>();
+            }
+        }
+        self.write_vertex_positions(&positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn gaussian_noise_statistics() {
+        let vertices = vec![[0.0; 3]; 2000];
+        let mut ply = fixtures::from_mesh(&vertices, &[]);
+        ply.add_gaussian_noise(0.5, 9).unwrap();
+        let positions = ply.read_vertex_positions().unwrap();
+        let values: Vec<f64> = positions.iter().flat_map(|p| p.to_vec()).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let std = (values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / values.len() as f64).sqrt();
+        assert!(mean.abs() < 0.05);
+        assert!((std - 0.5).abs() < 0.05);
+        assert!(ply.add_gaussian_noise(-1.0, 0).is_err());
+    }
+    #[test]
+    fn salt_pepper_noise_fraction() {
+        let mut ply = fixtures::grid(9);
+        let before = ply.read_vertex_positions().unwrap();
+        ply.add_salt_pepper_noise(0.2, 4).unwrap();
+        let after = ply.read_vertex_positions().unwrap();
+        let moved = before.iter().zip(after.iter()).filter(|(a, b)| a != b).count();
+        assert!(moved <= 20 && moved > 10);
+        assert!(after.iter().all(|p| p[0] >= 0.0 && p[0] <= 9.0 && p[2] == 0.0));
+        assert!(ply.add_salt_pepper_noise(1.5, 0).is_err());
+    }
+}
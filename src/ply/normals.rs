@@ -0,0 +1,72 @@
+//! Normals of faces.
+
+use super::{ Ply, DefaultElement };
+
+impl Ply<DefaultElement> {
+    /// Computes the unit normal of each face, facing the side from which its vertices appear counter-clockwise.
+    ///
+    /// Returns one normal per face, in the order of the `face` elements.
+    /// For triangles, this is the normalized cross product of two edges,
+    /// larger polygons use Newell's method to be robust against slightly non-planar faces.
+    /// Degenerate faces get the zero vector.
+    ///
+    /// Fails if the `vertex` or `face` element is missing, if vertices lack numeric `x`, `y` or `z` properties,
+    /// if faces lack integer `vertex_indices` (or `vertex_index`) lists, refer to missing vertices
+    /// or have less than three vertices.
+    pub fn compute_face_normals(&self) -> Result<Vec<[f32; 3]>, &'static str> {
+        if !self.header.has_element("vertex") {
+            return Err("No element `vertex` declared in header.");
+        }
+        if !self.header.has_element("face") {
+            return Err("No element `face` declared in header.");
+        }
+        let mesh = self.read_mesh().map_err(|_| "Vertices need `x`, `y` and `z`, faces need valid vertex indices.")?;
+        let mut normals = Vec::with_capacity(mesh.faces.len());
+        for f in &mesh.faces {
+            if f.len() < 3 {
+                return Err("Face has less than three vertices.");
+            }
+            let [x, y, z] = mesh.face_normal(f);
+            normals.push([x as f32, y as f32, z as f32]);
+        }
+        Ok(normals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Property;
+    use super::super::mesh::{ dot, fixtures, norm };
+    #[test]
+    fn face_normals_outward() {
+        let ply = fixtures::icosahedron();
+        let normals = ply.compute_face_normals().unwrap();
+        let barycentres = ply.compute_face_barycentres().unwrap();
+        assert_eq!(normals.len(), 20);
+        for (n, b) in normals.iter().zip(&barycentres) {
+            let n = [n[0] as f64, n[1] as f64, n[2] as f64];
+            assert!((norm(&n) - 1.0).abs() < 1e-6);
+            assert!(dot(&n, b) > 0.0);
+        }
+    }
+    #[test]
+    fn face_normals_grid() {
+        for n in fixtures::grid(2).compute_face_normals().unwrap() {
+            assert_eq!(n, [0.0, 0.0, 1.0]);
+        }
+    }
+    #[test]
+    fn face_normals_err() {
+        let mut ply = fixtures::grid(1);
+        ply.payload.get_mut("face").unwrap()[0].insert("vertex_indices".to_string(), Property::ListUInt(vec![0, 1]));
+        assert_eq!(ply.compute_face_normals(), Err("Face has less than three vertices."));
+        ply.header.elements.remove("face");
+        assert_eq!(ply.compute_face_normals(), Err("No element `face` declared in header."));
+        let mut ply = fixtures::grid(1);
+        ply.header.elements.remove("vertex");
+        assert_eq!(ply.compute_face_normals(), Err("No element `vertex` declared in header."));
+        let mut ply = fixtures::grid(1);
+        ply.payload.get_mut("vertex").unwrap()[0].remove("z");
+        assert!(ply.compute_face_normals().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Ambient occlusion by ray casting.
+
+use std::f64::consts::PI;
+use std::io::Result;
+use rand::{ Rng, SeedableRng };
+use rand::rngs::StdRng;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Vec3, add, scale, cross, normalize, intersect_ray_triangle, invalid_input };
+
+/// Two unit vectors that form an orthonormal basis together with the unit vector `n`.
+fn tangent_frame(n: &Vec3) -> (Vec3, Vec3) {
+    let helper = if n[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+    let t = normalize(&cross(n, &helper));
+    (t, cross(n, &t))
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Approximates the ambient occlusion at each vertex by casting rays into the hemisphere around the vertex normal.
+    ///
+    /// Each vertex shoots `rays_per_vertex` cosine-weighted rays, stratified over a grid of the sample domain
+    /// with exactly `rays_per_vertex` cells, so every direction around the normal is sampled for any ray count.
+    /// A ray is occluded if it hits any triangle that isn't incident to the vertex (Möller-Trumbore test).
+    /// The result is `1 - hit_fraction`: `1` for fully exposed vertices, `0` for fully occluded ones.
+    /// Vertices without a normal, i.e. without adjacent faces, have a value of `1`.
+    ///
+    /// The same `seed` always produces the same result.
+    /// Every ray is tested against every triangle, the costs grow with vertices × rays × triangles.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ply_rs::ply::{ Ply, DefaultElement, PropertyDef, PropertyType, ScalarType, Property };
+    /// # let mut ply = Ply::<DefaultElement>::new();
+    /// let ao = ply.compute_ambient_occlusion(64, 0).unwrap();
+    /// let values = ao.iter().map(|&a| Property::Float(a as f32)).collect();
+    /// let def = PropertyDef::new("ambient_occlusion".to_string(), PropertyType::Scalar(ScalarType::Float));
+    /// ply.add_property_to_element("vertex", def, values).unwrap();
+    /// ```
+    pub fn compute_ambient_occlusion(&self, rays_per_vertex: usize, seed: u64) -> Result<Vec<f64>> {
+        if rays_per_vertex == 0 {
+            return invalid_input("At least one ray per vertex is required.");
+        }
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        let normals = mesh.vertex_normals(&triangles);
+        // grid of `radial × angular` cells, the most square one that has exactly one cell per ray
+        #[allow(unknown_lints, clippy::manual_is_multiple_of)] // `is_multiple_of` needs Rust 1.87
+        let radial = (1..=(rays_per_vertex as f64).sqrt() as usize).rev().find(|d| rays_per_vertex % d == 0).unwrap_or(1);
+        let angular = rays_per_vertex / radial;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut occlusion = Vec::with_capacity(mesh.vertices.len());
+        for (v, (origin, normal)) in mesh.vertices.iter().zip(normals.iter()).enumerate() {
+            if *normal == [0.0; 3] {
+                occlusion.push(1.0);
+                continue;
+            }
+            let (t, b) = tangent_frame(normal);
+            let mut hits = 0;
+            for i in 0..rays_per_vertex {
+                let u1 = ((i % radial) as f64 + rng.gen::<f64>()) / radial as f64;
+                let u2 = ((i / radial) as f64 + rng.gen::<f64>()) / angular as f64;
+                let (r, phi) = (u1.sqrt(), 2.0 * PI * u2);
+                let direction = add(&add(&scale(&t, r * phi.cos()), &scale(&b, r * phi.sin())), &scale(normal, (1.0 - u1).sqrt()));
+                let hit = triangles.iter().filter(|tri| !tri.contains(&v)).any(|tri| {
+                    let (a, b, c) = (&mesh.vertices[tri[0]], &mesh.vertices[tri[1]], &mesh.vertices[tri[2]]);
+                    intersect_ray_triangle(origin, &direction, a, b, c).is_some()
+                });
+                if hit {
+                    hits += 1;
+                }
+            }
+            occlusion.push(1.0 - hits as f64 / rays_per_vertex as f64);
+        }
+        Ok(occlusion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn ambient_occlusion_convex_unoccluded() {
+        let ply = fixtures::icosahedron();
+        let ao = ply.compute_ambient_occlusion(16, 1).unwrap();
+        assert!(ao.iter().all(|&a| a == 1.0));
+    }
+    #[test]
+    fn ambient_occlusion_covered() {
+        // a flat grid in the plane z = 0, covered by a large triangle at z = 1, facing down
+        let mut vertices: Vec<[f64; 3]> = (0..9).map(|i| [(i % 3) as f64, (i / 3) as f64, 0.0]).collect();
+        vertices.extend_from_slice(&[[-100.0, -100.0, 1.0], [100.0, -100.0, 1.0], [0.0, 100.0, 1.0]]);
+        let faces = vec![
+            vec![0, 1, 4], vec![0, 4, 3], vec![1, 2, 5], vec![1, 5, 4],
+            vec![3, 4, 7], vec![3, 7, 6], vec![4, 5, 8], vec![4, 8, 7],
+            vec![9, 11, 10],
+        ];
+        let ply = fixtures::from_mesh(&vertices, &faces);
+        let ao = ply.compute_ambient_occlusion(64, 3).unwrap();
+        assert!(ao[4] < 0.1);
+        assert_eq!(ao, ply.compute_ambient_occlusion(64, 3).unwrap());
+    }
+    #[test]
+    fn ambient_occlusion_non_square_ray_count_unbiased() {
+        // a flat patch around the origin, half of the hemisphere is covered by a roof at z = 1
+        let roof = |side: f64| {
+            let mut vertices = vec![[0.0, 0.0, 0.0], [0.1, 0.1, 0.0], [-0.1, 0.1, 0.0], [-0.1, -0.1, 0.0], [0.1, -0.1, 0.0]];
+            vertices.extend_from_slice(&[[0.0, -1e3, 1.0], [side * 1e3, -1e3, 1.0], [side * 1e3, 1e3, 1.0], [0.0, 1e3, 1.0]]);
+            let faces = vec![vec![0, 1, 2], vec![0, 2, 3], vec![0, 3, 4], vec![0, 4, 1], vec![5, 6, 7], vec![5, 7, 8]];
+            fixtures::from_mesh(&vertices, &faces)
+        };
+        for &n in &[2, 5, 6] {
+            let mean = |side: f64| {
+                let ply = roof(side);
+                (0..200).map(|seed| ply.compute_ambient_occlusion(n, seed).unwrap()[0]).sum::<f64>() / 200.0
+            };
+            let (left, right) = (mean(-1.0), mean(1.0));
+            assert!((left - 0.5).abs() < 0.1, "n = {}: {}", n, left);
+            assert!((right - 0.5).abs() < 0.1, "n = {}: {}", n, right);
+        }
+    }
+    #[test]
+    fn ambient_occlusion_zero_rays_err() {
+        assert!(fixtures::icosahedron().compute_ambient_occlusion(0, 0).is_err());
+    }
+}
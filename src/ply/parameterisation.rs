@@ -0,0 +1,212 @@
+//! Flattening of disk-like meshes into the plane.
+
+use std::f64::consts::PI;
+use std::io::Result;
+use nalgebra::DMatrix;
+use nalgebra_sparse::{ CooMatrix, CscMatrix, CsrMatrix };
+use super::Ply;
+use super::PropertyAccess;
+use super::harmonic::{ solve_harmonic, solve_sparse_spd };
+use super::mesh::{ Mesh, sub, dot, cross, norm, normalize, dist, invalid_input };
+
+/// Returns the single boundary loop of a mesh that is topologically a disk.
+fn disk_boundary(mesh: &Mesh, triangles: &[[usize; 3]]) -> Result<Vec<usize>> {
+    let mut referenced = vec![false; mesh.vertices.len()];
+    for t in triangles {
+        for &v in t {
+            referenced[v] = true;
+        }
+    }
+    if let Some(v) = referenced.iter().position(|&r| !r) {
+        return invalid_input(&format!("Vertex {} isn't part of any face.", v));
+    }
+    let mut loops = Mesh::boundary_loops(triangles);
+    match loops.len() {
+        0 => return invalid_input("Mesh is closed, a disk-like mesh with a single boundary is required."),
+        1 => (),
+        n => return invalid_input(&format!("Mesh has {} boundaries, a disk-like mesh without holes is required.", n)),
+    }
+    let edges = Mesh::edge_triangles(triangles).len();
+    let euler = mesh.vertices.len() as i64 - edges as i64 + triangles.len() as i64;
+    if euler != 1 {
+        return invalid_input(&format!("Mesh has Euler characteristic {}, a disk-like mesh requires 1.", euler));
+    }
+    Ok(loops.remove(0))
+}
+
+/// Solves the sparse least squares problem `min |A x - b|` using the normal equations.
+fn solve_least_squares(a: &CooMatrix<f64>, b: &DMatrix<f64>) -> Result<DMatrix<f64>> {
+    let a = CsrMatrix::from(a);
+    let at = a.transpose();
+    let normal = &at * &a;
+    let rhs = &at * b;
+    match solve_sparse_spd(&CscMatrix::from(&normal), &rhs) {
+        Some(x) => Ok(x),
+        None => invalid_input("Linear system is singular, the mesh might contain degenerated triangles."),
+    }
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes a conformal parameterization of a disk-like mesh with least squares conformal maps (LSCM).
+    ///
+    /// Two boundary vertices, the first boundary vertex and the boundary vertex farthest away from it,
+    /// are pinned to the antipodal positions `(-1, 0)` and `(1, 0)` on the unit circle.
+    /// The remaining boundary is free, all other UV coordinates result from the sparse least squares system
+    /// of Lévy et al., "Least Squares Conformal Maps for Automatic Texture Atlas Generation" (2002).
+    ///
+    /// Returns one UV coordinate per vertex.
+    /// The mesh must be simply connected with exactly one boundary, otherwise an error is returned.
+    ///
+    /// The normal equations of the sparse system are solved with a sparse Cholesky factorization.
+    ///
+    /// Requires the `nalgebra` feature.
+    pub fn conformal_map_disk(&self) -> Result<Vec<[f64; 2]>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        let boundary = disk_boundary(&mesh, &triangles)?;
+        let n = mesh.vertices.len();
+        let pin_a = boundary[0];
+        let pin_b = *boundary.iter().max_by(|&&a, &&b| {
+            dist(&mesh.vertices[pin_a], &mesh.vertices[a]).total_cmp(&dist(&mesh.vertices[pin_a], &mesh.vertices[b]))
+        }).unwrap();
+        let pinned = |v: usize| -> Option<[f64; 2]> {
+            if v == pin_a {
+                Some([-1.0, 0.0])
+            } else if v == pin_b {
+                Some([1.0, 0.0])
+            } else {
+                None
+            }
+        };
+        // unknowns: u and v of every free vertex
+        let mut column = vec![usize::MAX; n];
+        let mut free = 0;
+        for (v, c) in column.iter_mut().enumerate() {
+            if pinned(v).is_none() {
+                *c = free;
+                free += 1;
+            }
+        }
+        let mut a = CooMatrix::<f64>::new(2 * triangles.len(), 2 * free);
+        let mut b = DMatrix::<f64>::zeros(2 * triangles.len(), 1);
+        for (ti, t) in triangles.iter().enumerate() {
+            let p = [&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]];
+            let e1 = sub(p[1], p[0]);
+            let e2 = sub(p[2], p[0]);
+            let double_area = norm(&cross(&e1, &e2));
+            if double_area <= 0.0 {
+                continue;
+            }
+            let x_axis = normalize(&e1);
+            let y_axis = normalize(&cross(&normalize(&cross(&e1, &e2)), &x_axis));
+            let local = [[0.0, 0.0], [norm(&e1), 0.0], [dot(&e2, &x_axis), dot(&e2, &y_axis)]];
+            let weight = 1.0 / double_area.sqrt();
+            let (re, im) = (2 * ti, 2 * ti + 1);
+            for j in 0..3 {
+                let (k, l) = ((j + 1) % 3, (j + 2) % 3);
+                // W_j = (x_l - x_k) + i (y_l - y_k)
+                let w_re = (local[l][0] - local[k][0]) * weight;
+                let w_im = (local[l][1] - local[k][1]) * weight;
+                match pinned(t[j]) {
+                    Some(uv) => {
+                        b[re] -= w_re * uv[0] - w_im * uv[1];
+                        b[im] -= w_im * uv[0] + w_re * uv[1];
+                    },
+                    None => {
+                        let (cu, cv) = (2 * column[t[j]], 2 * column[t[j]] + 1);
+                        a.push(re, cu, w_re);
+                        a.push(re, cv, -w_im);
+                        a.push(im, cu, w_im);
+                        a.push(im, cv, w_re);
+                    },
+                }
+            }
+        }
+        let x = solve_least_squares(&a, &b)?;
+        Ok((0..n).map(|v| match pinned(v) {
+            Some(uv) => uv,
+            None => [x[2 * column[v]], x[2 * column[v] + 1]],
+        }).collect())
+    }
+    /// Computes a harmonic parameterization of a disk-like mesh.
+    ///
+    /// The boundary vertices are distributed uniformly around the unit circle, counter-clockwise
+    /// when the faces are oriented counter-clockwise, starting with the first boundary vertex at `(1, 0)`.
+    /// The UV coordinates of the interior vertices solve the Laplace equation with cotangent weights,
+    /// separately for `u` and `v` (Eck et al., "Multiresolution Analysis of Arbitrary Meshes", 1995).
+    ///
+    /// In contrast to `conformal_map_disk()`, the whole boundary is fixed, which makes the map more robust
+    /// but introduces angle distortion near the boundary.
+    ///
+    /// Returns one UV coordinate per vertex.
+    /// The mesh must be simply connected with exactly one boundary, otherwise an error is returned.
+    ///
+    /// Both sparse systems share one matrix and are solved with a single sparse Cholesky factorization.
+    ///
+    /// Requires the `nalgebra` feature.
+    pub fn harmonic_parameterise(&self) -> Result<Vec<[f64; 2]>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        let boundary = disk_boundary(&mesh, &triangles)?;
+        let mut fixed = vec![None; mesh.vertices.len()];
+        for (k, &v) in boundary.iter().enumerate() {
+            let phi = 2.0 * PI * k as f64 / boundary.len() as f64;
+            fixed[v] = Some(vec![phi.cos(), phi.sin()]);
+        }
+        let uv = solve_harmonic(&mesh, &triangles, &fixed, 2)?;
+        Ok((0..mesh.vertices.len()).map(|v| [uv[(v, 0)], uv[(v, 1)]]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn conformal_map_flat_grid() {
+        // a flat grid is mapped by a similarity transform
+        let ply = fixtures::grid(2);
+        let uv = ply.conformal_map_disk().unwrap();
+        assert_eq!(uv.len(), 9);
+        assert_eq!(uv[0], [-1.0, 0.0]);
+        assert_eq!(uv[8], [1.0, 0.0]);
+        assert!(uv[4][0].abs() < 1e-9 && uv[4][1].abs() < 1e-9);
+    }
+    #[test]
+    fn harmonic_parameterise_grid() {
+        let ply = fixtures::grid(2);
+        let uv = ply.harmonic_parameterise().unwrap();
+        // boundary on the unit circle, the symmetric center maps to the origin
+        for (v, p) in uv.iter().enumerate() {
+            let r = (p[0] * p[0] + p[1] * p[1]).sqrt();
+            if v == 4 {
+                assert!(r < 1e-9);
+            } else {
+                assert!((r - 1.0).abs() < 1e-9);
+            }
+        }
+        assert!(fixtures::icosahedron().harmonic_parameterise().is_err());
+    }
+    #[test]
+    fn parameterise_large_grid() {
+        let ply = fixtures::grid(50);
+        let harmonic = ply.harmonic_parameterise().unwrap();
+        assert_eq!(harmonic.len(), 51 * 51);
+        assert!(harmonic.iter().all(|p| p[0] * p[0] + p[1] * p[1] <= 1.0 + 1e-9));
+        let conformal = ply.conformal_map_disk().unwrap();
+        assert_eq!(conformal.len(), 51 * 51);
+        assert!(conformal.iter().all(|p| p[0].is_finite() && p[1].is_finite()));
+    }
+    #[test]
+    fn conformal_map_closed_err() {
+        assert!(fixtures::icosahedron().conformal_map_disk().is_err());
+    }
+    #[test]
+    fn conformal_map_hole_err() {
+        let mut ply = fixtures::grid(3);
+        // remove both triangles of the center quad
+        ply.payload.get_mut("face").unwrap().drain(8..10);
+        assert!(ply.conformal_map_disk().is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Persistent homology of scalar fields on meshes.
+
+use std::io::Result;
+use super::{ Ply, PropertyAccess, PropertyType };
+use super::mesh::{ find, invalid_input };
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the 0-dimensional persistence diagram of the sublevel set filtration of a scalar vertex property.
+    ///
+    /// The scalar vertex property `property` is used as height function on the graph formed by the face edges.
+    /// Vertices are added in order of increasing height, the connected components are tracked with union-find.
+    /// When two components merge, the younger one (the one with the higher minimum) dies, following the elder rule.
+    ///
+    /// Returns one `(birth, death)` pair per component, sorted by decreasing persistence `death - birth`.
+    /// Components that never merge, one per connected part of the mesh, have a death of `f64::INFINITY`.
+    /// Pairs with zero persistence are omitted.
+    /// The number of infinite pairs is the Betti number `b0`.
+    pub fn compute_persistence_0(&self, property: &str) -> Result<Vec<(f64, f64)>> {
+        let def = match self.header.elements.get("vertex").and_then(|e| e.properties.get(property)) {
+            Some(d) => d.clone(),
+            None => return invalid_input(&format!("No declaration for property `{}` of `vertex` found.", property)),
+        };
+        if let PropertyType::List(_, _) = def.data_type {
+            return invalid_input(&format!("Property `{}` of `vertex` must be a scalar.", property));
+        }
+        let heights: Vec<f64> = self.read_scalar_rows("vertex", &[def])?.into_iter().map(|row| row[0]).collect();
+        if let Some(v) = heights.iter().position(|h| h.is_nan()) {
+            return invalid_input(&format!("Vertex {} has no valid value for property `{}`.", v, property));
+        }
+        let n = heights.len();
+        let mut neighbours = vec![Vec::new(); n];
+        for f in self.read_face_indices(n)? {
+            for k in 0..f.len() {
+                let (a, b) = (f[k], f[(k + 1) % f.len()]);
+                if a != b {
+                    neighbours[a].push(b);
+                    neighbours[b].push(a);
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| heights[a].total_cmp(&heights[b]).then(a.cmp(&b)));
+        let mut parent: Vec<usize> = (0..n).collect();
+        let mut added = vec![false; n];
+        let mut pairs = Vec::new();
+        for &v in &order {
+            added[v] = true;
+            for &u in &neighbours[v] {
+                if !added[u] {
+                    continue;
+                }
+                let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+                if ru == rv {
+                    continue;
+                }
+                let (elder, younger) = if heights[ru] <= heights[rv] { (ru, rv) } else { (rv, ru) };
+                if heights[younger] < heights[v] {
+                    pairs.push((heights[younger], heights[v]));
+                }
+                parent[younger] = elder;
+            }
+        }
+        for (v, &h) in heights.iter().enumerate() {
+            if find(&mut parent, v) == v {
+                pairs.push((h, f64::INFINITY));
+            }
+        }
+        pairs.sort_by(|a, b| (b.1 - b.0).total_cmp(&(a.1 - a.0)).then(a.0.total_cmp(&b.0)));
+        Ok(pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::super::mesh::fixtures;
+    fn with_height(mut ply: Ply<DefaultElement>, heights: &[f64]) -> Ply<DefaultElement> {
+        let values = heights.iter().map(|&h| Property::Double(h)).collect();
+        let def = PropertyDef::new("height".to_string(), PropertyType::Scalar(ScalarType::Double));
+        ply.add_property_to_element("vertex", def, values).unwrap();
+        ply
+    }
+    #[test]
+    fn persistence_two_minima() {
+        // a strip of triangles along a height profile with minima at 0 and 2 and a saddle at 3
+        let vertices: Vec<_> = (0..5).map(|i| [i as f64, (i % 2) as f64, 0.0]).collect();
+        let faces = vec![vec![0, 1, 2], vec![1, 3, 2], vec![2, 3, 4]];
+        let ply = with_height(fixtures::from_mesh(&vertices, &faces), &[0.0, 5.0, 3.0, 1.0, 6.0]);
+        let pairs = ply.compute_persistence_0("height").unwrap();
+        assert_eq!(pairs, vec![(0.0, f64::INFINITY), (1.0, 3.0)]);
+    }
+    #[test]
+    fn persistence_components() {
+        let vertices = [[0.0; 3]; 3];
+        let ply = with_height(fixtures::from_mesh(&vertices, &[]), &[2.0, 1.0, 3.0]);
+        let pairs = ply.compute_persistence_0("height").unwrap();
+        assert_eq!(pairs.len(), 3);
+        assert!(pairs.iter().all(|p| p.1 == f64::INFINITY));
+        assert!(ply.compute_persistence_0("missing").is_err());
+    }
+}
@@ -1,13 +1,23 @@
+use std::convert::TryFrom;
 use std::fmt::{ Display, Formatter };
 use std::fmt;
+use std::io;
+use std::io::ErrorKind;
 use super::PropertyType;
+use super::Property;
 use super::KeyMap;
 use super::PropertyAccess;
+use super::Addable;
+use super::ConsistencyError;
+use super::ScalarType;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
 
 /// Models all necessary information to interact with a PLY file.
 ///
 /// The generic parameter `E` is the element type used to store the payload data.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Ply<E: PropertyAccess> {
     /// All header information found in a PLY file.
     pub header: Header,
@@ -37,12 +47,247 @@ impl<E: PropertyAccess> Ply<E> {
             payload: Payload::new(),
         }
     }
+    /// Adds a property to every element with the name `element_name` and declares it in the header.
+    ///
+    /// `values` must contain exactly one property per element, they are assigned in payload order.
+    /// An existing property with the same name is replaced.
+    pub fn add_property_to_element(&mut self, element_name: &str, property_def: PropertyDef, values: Vec<Property>) -> io::Result<()> {
+        let element_def = match self.header.elements.get_mut(element_name) {
+            Some(e) => e,
+            None => return Err(io::Error::new(ErrorKind::InvalidInput, format!("No declaration for element `{}` found.", element_name))),
+        };
+        let elements = self.payload.get_mut(element_name);
+        let count = elements.as_ref().map_or(0, |e| e.len());
+        if count != values.len() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("Element `{}` has {} entries, but {} values were given.", element_name, count, values.len())
+            ));
+        }
+        for (e, v) in elements.into_iter().flatten().zip(values) {
+            e.set_property(property_def.name.clone(), v);
+        }
+        element_def.properties.add(property_def);
+        Ok(())
+    }
+    /// Names of all elements declared in the header, in declaration order.
+    pub fn element_names(&self) -> impl Iterator<Item = &str> {
+        self.header.elements.keys().map(|k| k.as_str())
+    }
+    /// Elements named `element_name` in the payload, if present.
+    pub fn payload_ref(&self, element_name: &str) -> Option<&Vec<E>> {
+        self.payload.get(element_name)
+    }
+    /// Mutable elements named `element_name` in the payload, if present.
+    ///
+    /// Call `make_consistent()` afterwards if the number of elements changed.
+    pub fn payload_mut(&mut self, element_name: &str) -> Option<&mut Vec<E>> {
+        self.payload.get_mut(element_name)
+    }
+    /// Calls `f` on every element named `element_name` in the payload, does nothing if there is none.
+    pub fn for_each_element<F: FnMut(&E)>(&self, element_name: &str, f: F) {
+        if let Some(elements) = self.payload.get(element_name) {
+            elements.iter().for_each(f);
+        }
+    }
+    /// Calls `f` on every element named `element_name` in the payload, allowing to modify them in place.
+    ///
+    /// Does nothing if there is no such element.
+    pub fn for_each_element_mut<F: FnMut(&mut E)>(&mut self, element_name: &str, f: F) {
+        if let Some(elements) = self.payload.get_mut(element_name) {
+            elements.iter_mut().for_each(f);
+        }
+    }
+    /// Declares the element `def` and inserts `elements` as its payload.
+    ///
+    /// `def.count` is set to the number of elements.
+    /// Fails without modifying `self` if an element with the same name already exists in the header or the payload.
+    pub fn add_element_payload(&mut self, mut def: ElementDef, elements: Vec<E>) -> Result<(), ConsistencyError> {
+        if self.header.elements.contains_key(&def.name) || self.payload.contains_key(&def.name) {
+            return Err(ConsistencyError::new(&format!("Element `{}` already exists.", def.name)));
+        }
+        def.count = elements.len();
+        self.payload.insert(def.name.clone(), elements);
+        self.header.elements.add(def);
+        Ok(())
+    }
+    /// Converts the elements named `element_name` to another element type `E2`.
+    ///
+    /// The header is kept unchanged.
+    /// Payloads of other elements are dropped, since they can't be converted to `E2`,
+    /// hence their counts become zero with the next `make_consistent()`.
+    pub fn map_payload<E2: PropertyAccess, F: FnMut(E) -> E2>(mut self, element_name: &str, f: F) -> Ply<E2> {
+        let mut payload = Payload::new();
+        if let Some(elements) = self.payload.remove(element_name) {
+            payload.insert(element_name.to_string(), elements.into_iter().map(f).collect());
+        }
+        Ply {
+            header: self.header,
+            payload,
+        }
+    }
+    /// Keeps only the elements named `element_name` for which `f` returns `true`.
+    ///
+    /// The element count in the header is updated with `make_consistent()`, whose result is returned.
+    /// References to removed elements, e.g. vertex indices of faces, are not updated.
+    pub fn retain_elements_by<F: Fn(&E) -> bool>(&mut self, element_name: &str, f: F) -> Result<(), ConsistencyError> {
+        if let Some(elements) = self.payload.get_mut(element_name) {
+            elements.retain(f);
+        }
+        self.make_consistent()
+    }
+    /// Removes the element named `name` from the header and the payload.
+    ///
+    /// Returns the removed payload, or `None` if neither the header nor the payload contained the element.
+    /// An element only declared in the header yields an empty list.
+    pub fn remove_element(&mut self, name: &str) -> Option<Vec<E>> {
+        let def = self.header.elements.remove(name);
+        match self.payload.remove(name) {
+            Some(elements) => Some(elements),
+            None => def.map(|_| Vec::new()),
+        }
+    }
+    /// Appends the payload of `other` to the payload of `self`.
+    ///
+    /// Elements present in both must have the same properties with the same types in the same order,
+    /// elements only present in `other` are added.
+    /// If `self` has a `vertex` element, the `vertex_indices` (or `vertex_index`) of the faces in `other`
+    /// are offset by the original vertex count of `self`.
+    /// Header information other than the elements is kept from `self`.
+    ///
+    /// `self` is left unchanged if either ply is inconsistent or the elements don't match.
+    pub fn merge(&mut self, mut other: Ply<E>) -> Result<(), ConsistencyError> {
+        self.check_consistency()?;
+        other.make_consistent()?;
+        for (name, other_def) in &other.header.elements {
+            if let Some(def) = self.header.elements.get(name) {
+                if !def.properties.iter().eq(other_def.properties.iter()) {
+                    return Err(ConsistencyError::new(&format!("Element `{}` has different properties in both plys.", name)));
+                }
+            }
+        }
+        let vertex_count = self.payload.get("vertex").map_or(0, |v| v.len());
+        if vertex_count > 0 {
+            if let (Some(def), Some(faces)) = (other.header.elements.get("face"), other.payload.get_mut("face")) {
+                let index_def = def.properties.get("vertex_indices").or_else(|| def.properties.get("vertex_index"));
+                if let Some(&PropertyDef { ref name, data_type: PropertyType::List(_, ref element_type) }) = index_def {
+                    let offset_faces = faces.iter()
+                        .map(|f| offset_indices(f, name, element_type, vertex_count))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    for (f, indices) in faces.iter_mut().zip(offset_faces) {
+                        if let Some(indices) = indices {
+                            f.set_property(name.clone(), indices);
+                        }
+                    }
+                }
+            }
+        }
+        for (name, other_def) in other.header.elements {
+            if !self.header.elements.contains_key(&name) {
+                self.header.elements.add(other_def);
+            }
+        }
+        for (name, elements) in other.payload {
+            self.payload.entry(name).or_default().extend(elements);
+        }
+        // Can't fail anymore, since both plys were consistent and the elements match.
+        self.make_consistent()
+    }
+    /// Computes the exact size of the payload in bytes when written in binary.
+    ///
+    /// Returns `None` for ascii encoding, where the size depends on the values,
+    /// or if an element lacks a list property declared in the header.
+    /// Elements without declaration are ignored.
+    pub fn total_binary_payload_size(&self) -> Option<usize> {
+        if self.header.encoding == Encoding::Ascii {
+            return None;
+        }
+        let mut size = 0;
+        for (name, def) in &self.header.elements {
+            let elements = match self.payload.get(name) {
+                Some(e) => e,
+                None => continue,
+            };
+            size += elements.len() * def.min_binary_bytes_per_element();
+            for (key, p) in &def.properties {
+                if let PropertyType::List(_, ref t) = p.data_type {
+                    for e in elements {
+                        size += typed_list_len(e, key, t)? * t.byte_width();
+                    }
+                }
+            }
+        }
+        Some(size)
+    }
+}
+
+impl<E: PropertyAccess> Default for Ply<E> {
+    /// Same as `Ply::new()`.
+    fn default() -> Self {
+        Ply::new()
+    }
+}
+
+impl<E: PropertyAccess + Clone> Ply<E> {
+    /// Returns a copy of `self` to be written with `target` encoding.
+    ///
+    /// The payload is cloned as is, only the encoding in the header changes.
+    /// The copy is made consistent, see `make_consistent()`, which fails if `self` can't be written at all.
+    pub fn convert_encoding(&self, target: Encoding) -> Result<Ply<E>, ConsistencyError> {
+        let mut converted = self.clone();
+        converted.header.encoding = target;
+        converted.make_consistent()?;
+        Ok(converted)
+    }
+}
+
+/// Length of the list property `key` as returned by the getter for element type `t`, i.e. of the values the writer writes.
+fn typed_list_len<E: PropertyAccess>(e: &E, key: &str, t: &ScalarType) -> Option<usize> {
+    match *t {
+        ScalarType::Char => e.get_list_char(key).map(|l| l.len()),
+        ScalarType::UChar => e.get_list_uchar(key).map(|l| l.len()),
+        ScalarType::Short => e.get_list_short(key).map(|l| l.len()),
+        ScalarType::UShort => e.get_list_ushort(key).map(|l| l.len()),
+        ScalarType::Int => e.get_list_int(key).map(|l| l.len()),
+        ScalarType::UInt => e.get_list_uint(key).map(|l| l.len()),
+        ScalarType::Long => e.get_list_long(key).map(|l| l.len()),
+        ScalarType::ULong => e.get_list_ulong(key).map(|l| l.len()),
+        ScalarType::Float => e.get_list_float(key).map(|l| l.len()),
+        ScalarType::Double => e.get_list_double(key).map(|l| l.len()),
+    }
+}
+
+/// Adds `offset` to a list of vertex indices, returns `None` if the property is missing.
+fn offset_indices<E: PropertyAccess>(face: &E, name: &str, element_type: &ScalarType, offset: usize) -> Result<Option<Property>, ConsistencyError> {
+    macro_rules! offset_list {
+        ($getter:ident, $variant:ident, $t:ty) => {
+            match face.$getter(name) {
+                None => None,
+                Some(l) => Some(Property::$variant(l.iter()
+                    .map(|&i| <$t>::try_from(offset).ok().and_then(|o| i.checked_add(o)))
+                    .collect::<Option<Vec<$t>>>()
+                    .ok_or_else(|| ConsistencyError::new(&format!("Offset vertex index of property `{}` doesn't fit into its type.", name)))?)),
+            }
+        };
+    }
+    Ok(match *element_type {
+        ScalarType::Char => offset_list!(get_list_char, ListChar, i8),
+        ScalarType::UChar => offset_list!(get_list_uchar, ListUChar, u8),
+        ScalarType::Short => offset_list!(get_list_short, ListShort, i16),
+        ScalarType::UShort => offset_list!(get_list_ushort, ListUShort, u16),
+        ScalarType::Int => offset_list!(get_list_int, ListInt, i32),
+        ScalarType::UInt => offset_list!(get_list_uint, ListUInt, u32),
+        ScalarType::Long => offset_list!(get_list_long, ListLong, i64),
+        ScalarType::ULong => offset_list!(get_list_ulong, ListULong, u64),
+        ScalarType::Float | ScalarType::Double => return Err(ConsistencyError::new(&format!("Vertex indices `{}` must be integers.", name))),
+    })
 }
 
 // Header Types
 
 /// Models the header of a PLY file.
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Header {
     /// In which format is the payload encoded?
     ///
@@ -66,12 +311,61 @@ impl Header {
     pub fn new() -> Self {
         Header {
             encoding: Encoding::Ascii,
-            version: Version{major: 1, minor: 0},
+            version: Version::default(),
             obj_infos: Vec::new(),
             elements: KeyMap::new(),
             comments: Vec::new(),
         }
     }
+    /// Binary encoding matching the byte order of the target platform.
+    ///
+    /// Use it as `encoding` to write binary files without byte swapping.
+    pub fn native_binary_encoding() -> Encoding {
+        if cfg!(target_endian = "big") {
+            Encoding::BinaryBigEndian
+        } else {
+            Encoding::BinaryLittleEndian
+        }
+    }
+    /// Returns `true` if an element named `name` is declared.
+    pub fn has_element(&self, name: &str) -> bool {
+        self.elements.contains_key(name)
+    }
+    /// Returns `true` if the element named `element` declares a property named `property`.
+    pub fn has_property(&self, element: &str, property: &str) -> bool {
+        self.elements.get(element).is_some_and(|e| e.properties.contains_key(property))
+    }
+}
+
+impl Default for Header {
+    /// Same as `Header::new()`.
+    fn default() -> Self {
+        Header::new()
+    }
+}
+
+/// Formats the header as it appears in a PLY file, from `ply` to `end_header`, each line ending with `\n`.
+///
+/// No validation is performed, invalid headers are formatted as they are.
+/// Use `Writer::write_header()` to reject headers which don't result in a valid PLY file.
+impl Display for Header {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        writeln!(f, "ply")?;
+        writeln!(f, "format {} {}", self.encoding, self.version)?;
+        for c in &self.comments {
+            writeln!(f, "comment {}", c)?;
+        }
+        for oi in &self.obj_infos {
+            writeln!(f, "obj_info {}", oi)?;
+        }
+        for (_, e) in &self.elements {
+            writeln!(f, "element {} {}", e.name, e.count)?;
+            for (_, p) in &e.properties {
+                writeln!(f, "property {} {}", p.data_type, p.name)?;
+            }
+        }
+        writeln!(f, "end_header")
+    }
 }
 
 /// Alias to give object informations an explicit type.
@@ -84,11 +378,19 @@ pub type Comment = String;
 ///
 /// At time of writing, the only existin version for a PLY file is "1.0".
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Version {
     pub major: u16,
     pub minor: u8,
 }
 
+impl Default for Version {
+    /// Version 1.0.
+    fn default() -> Self {
+        Version{major: 1, minor: 0}
+    }
+}
+
 impl Display for Version {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
         f.write_str(&format!("{}.{}", self.major, self.minor))
@@ -97,6 +399,7 @@ impl Display for Version {
 
 /// Models possible encoding standards for the payload.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Encoding {
     /// Write numbers in their ascii representation (e.g. -13, 6.28, etc.).
     /// Properties are separated by spaces and elements are separated by line breaks.
@@ -126,6 +429,7 @@ impl Display for Encoding {
 /// We might model it as consisting of three coordinates: x, y, and z.
 /// Usually, one finds a list of elements in a ply file.
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ElementDef {
     /// Name of the element.
     ///
@@ -161,10 +465,64 @@ impl ElementDef {
             properties: KeyMap::new(),
         }
     }
+    /// Declares a new property, replacing an existing one with the same name.
+    ///
+    /// Returns `self` to allow chaining:
+    ///
+    /// ```rust
+    /// # use ply_rs::ply::{ ElementDef, PropertyType, ScalarType };
+    /// let mut vertex = ElementDef::new("vertex".to_string());
+    /// vertex.add_property("x", PropertyType::Scalar(ScalarType::Float))
+    ///     .add_property("y", PropertyType::Scalar(ScalarType::Float));
+    /// assert_eq!(vertex.properties.len(), 2);
+    /// ```
+    pub fn add_property(&mut self, name: &str, data_type: PropertyType) -> &mut Self {
+        self.properties.add(PropertyDef::new(name.to_string(), data_type));
+        self
+    }
+    /// Definition of the property named `name`, if declared.
+    ///
+    /// ```rust
+    /// # use ply_rs::ply::{ ElementDef, PropertyType, ScalarType };
+    /// let mut vertex = ElementDef::new("vertex".to_string());
+    /// vertex.add_property("x", PropertyType::Scalar(ScalarType::Float));
+    /// assert_eq!(vertex.find_property("x").unwrap().data_type, PropertyType::Scalar(ScalarType::Float));
+    /// assert!(vertex.find_property("y").is_none());
+    /// ```
+    pub fn find_property(&self, name: &str) -> Option<&PropertyDef> {
+        self.properties.get(name)
+    }
+    /// Mutable definition of the property named `name`, if declared.
+    ///
+    /// Use `add_property()` to rename a property, since the name is also the key in `properties`.
+    pub fn find_property_mut(&mut self, name: &str) -> Option<&mut PropertyDef> {
+        self.properties.get_mut(name)
+    }
+    /// Names of all properties, in declaration order.
+    pub fn property_names(&self) -> impl Iterator<Item = &str> {
+        self.properties.keys().map(|k| k.as_str())
+    }
+    /// Lower bound for the size of one element in a binary payload.
+    ///
+    /// Sums the widths of all scalar properties and of the length prefix of each list, assuming empty lists.
+    pub fn min_binary_bytes_per_element(&self) -> usize {
+        self.properties.values().map(|p| match p.data_type {
+            PropertyType::Scalar(ref t) => t.byte_width(),
+            PropertyType::List(ref index_type, _) => index_type.byte_width(),
+        }).sum()
+    }
+}
+
+impl Default for ElementDef {
+    /// Element definition with an empty name, set one before writing.
+    fn default() -> Self {
+        ElementDef::new(String::new())
+    }
 }
 
 /// Defines a property of an element.
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PropertyDef {
     /// Unique name of property.
     ///
@@ -186,5 +544,12 @@ impl PropertyDef {
     }
 }
 
+impl Default for PropertyDef {
+    /// Scalar `char` property with an empty name, set one before writing.
+    fn default() -> Self {
+        PropertyDef::new(String::new(), PropertyType::Scalar(ScalarType::Char))
+    }
+}
+
 /// The part after `end_header`, contains the main data.
 pub type Payload<E> = KeyMap<Vec<E>>;
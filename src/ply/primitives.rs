@@ -0,0 +1,224 @@
+//! Procedurally generated meshes.
+
+use std::f64::consts::PI;
+#[cfg(feature = "rand")]
+use rand::{ Rng, SeedableRng };
+#[cfg(feature = "rand")]
+use rand::rngs::StdRng;
+use super::{ Ply, DefaultElement, ElementDef, PropertyDef, PropertyType, ScalarType, Property, Addable };
+use super::mesh::Vec3;
+
+/// Builds a `Ply` with float `x`, `y`, `z`, `nx`, `ny`, `nz` vertices and a `vertex_indices` face list.
+fn build_mesh(vertices: &[(Vec3, Vec3)], faces: &[[usize; 3]]) -> Ply<DefaultElement> {
+    let mut ply = Ply::<DefaultElement>::new();
+    let mut vertex_def = ElementDef::new("vertex".to_string());
+    for name in &["x", "y", "z", "nx", "ny", "nz"] {
+        vertex_def.properties.add(PropertyDef::new(name.to_string(), PropertyType::Scalar(ScalarType::Float)));
+    }
+    vertex_def.count = vertices.len();
+    let mut face_def = ElementDef::new("face".to_string());
+    face_def.properties.add(PropertyDef::new("vertex_indices".to_string(), PropertyType::List(ScalarType::UChar, ScalarType::Int)));
+    face_def.count = faces.len();
+    ply.header.elements.add(vertex_def);
+    ply.header.elements.add(face_def);
+
+    ply.payload.insert("vertex".to_string(), vertices.iter().map(|(p, n)| {
+        let mut e = DefaultElement::new();
+        e.insert("x".to_string(), Property::Float(p[0] as f32));
+        e.insert("y".to_string(), Property::Float(p[1] as f32));
+        e.insert("z".to_string(), Property::Float(p[2] as f32));
+        e.insert("nx".to_string(), Property::Float(n[0] as f32));
+        e.insert("ny".to_string(), Property::Float(n[1] as f32));
+        e.insert("nz".to_string(), Property::Float(n[2] as f32));
+        e
+    }).collect());
+    ply.payload.insert("face".to_string(), faces.iter().map(|f| {
+        let mut e = DefaultElement::new();
+        e.insert("vertex_indices".to_string(), Property::ListInt(f.iter().map(|&i| i as i32).collect()));
+        e
+    }).collect());
+    ply
+}
+
+impl Ply<DefaultElement> {
+    /// Generates a triangulated cylinder around the z-axis, centred at the origin.
+    ///
+    /// The mantle consists of `segments` quads, each split into two triangles.
+    /// With `with_caps`, both ends are closed by a triangle fan around a center vertex.
+    /// The caps have their own vertices, such that every vertex has the correct normal
+    /// (`nx`, `ny`, `nz`) for flat shading of the caps and smooth shading of the mantle.
+    ///
+    /// All faces are oriented counter-clockwise when seen from outside.
+    /// `segments` is raised to at least `3`.
+    pub fn cylinder(radius: f64, height: f64, segments: usize, with_caps: bool) -> Ply<DefaultElement> {
+        let segments = segments.max(3);
+        let half = height / 2.0;
+        let ring: Vec<(f64, f64)> = (0..segments).map(|i| {
+            let phi = 2.0 * PI * i as f64 / segments as f64;
+            (phi.cos(), phi.sin())
+        }).collect();
+
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        // mantle: bottom ring at 2i, top ring at 2i + 1
+        for &(c, s) in &ring {
+            vertices.push(([radius * c, radius * s, -half], [c, s, 0.0]));
+            vertices.push(([radius * c, radius * s, half], [c, s, 0.0]));
+        }
+        for i in 0..segments {
+            let j = (i + 1) % segments;
+            faces.push([2 * i, 2 * j, 2 * j + 1]);
+            faces.push([2 * i, 2 * j + 1, 2 * i + 1]);
+        }
+        if with_caps {
+            for &(z, nz) in &[(-half, -1.0), (half, 1.0)] {
+                let center = vertices.len();
+                vertices.push(([0.0, 0.0, z], [0.0, 0.0, nz]));
+                for &(c, s) in &ring {
+                    vertices.push(([radius * c, radius * s, z], [0.0, 0.0, nz]));
+                }
+                for i in 0..segments {
+                    let (a, b) = (center + 1 + i, center + 1 + (i + 1) % segments);
+                    if nz > 0.0 {
+                        faces.push([center, a, b]);
+                    } else {
+                        faces.push([center, b, a]);
+                    }
+                }
+            }
+        }
+        build_mesh(&vertices, &faces)
+    }
+    /// Generates a triangulated torus around the z-axis, centred at the origin.
+    ///
+    /// `major_radius` is the distance from the center to the middle of the tube, `minor_radius` the radius of the tube.
+    /// The surface consists of `major_segments * minor_segments` quads, each split into two triangles.
+    /// Vertices on the seams are shared, the mesh is closed.
+    /// The normals (`nx`, `ny`, `nz`) are evaluated analytically.
+    ///
+    /// All faces are oriented counter-clockwise when seen from outside.
+    /// Both segment counts are raised to at least `3`.
+    pub fn torus(major_radius: f64, minor_radius: f64, major_segments: usize, minor_segments: usize) -> Ply<DefaultElement> {
+        let (major_segments, minor_segments) = (major_segments.max(3), minor_segments.max(3));
+        let mut vertices = Vec::with_capacity(major_segments * minor_segments);
+        let mut faces = Vec::with_capacity(2 * major_segments * minor_segments);
+        // vertex (i, j) at i * minor_segments + j
+        for i in 0..major_segments {
+            let phi = 2.0 * PI * i as f64 / major_segments as f64;
+            for j in 0..minor_segments {
+                let theta = 2.0 * PI * j as f64 / minor_segments as f64;
+                let r = major_radius + minor_radius * theta.cos();
+                let p = [r * phi.cos(), r * phi.sin(), minor_radius * theta.sin()];
+                let n = [theta.cos() * phi.cos(), theta.cos() * phi.sin(), theta.sin()];
+                vertices.push((p, n));
+            }
+        }
+        for i in 0..major_segments {
+            let next_i = (i + 1) % major_segments;
+            for j in 0..minor_segments {
+                let next_j = (j + 1) % minor_segments;
+                let a = i * minor_segments + j;
+                let b = next_i * minor_segments + j;
+                let c = next_i * minor_segments + next_j;
+                let d = i * minor_segments + next_j;
+                faces.push([a, b, c]);
+                faces.push([a, c, d]);
+            }
+        }
+        build_mesh(&vertices, &faces)
+    }
+    /// Generates `n` points uniformly distributed within the axis-aligned box `bounds`.
+    ///
+    /// `bounds` holds the `[min, max]` range of the x-, y- and z-axis.
+    /// The points are stored as `vertex` element with double properties `x`, `y` and `z`.
+    /// The same `seed` always produces the same point cloud.
+    ///
+    /// Requires the `rand` feature.
+    #[cfg(feature = "rand")]
+    pub fn random_point_cloud(n: usize, bounds: [[f64; 2]; 3], seed: u64) -> Ply<DefaultElement> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut ply = Ply::<DefaultElement>::new();
+        let mut vertex_def = ElementDef::new("vertex".to_string());
+        for name in &["x", "y", "z"] {
+            vertex_def.properties.add(PropertyDef::new(name.to_string(), PropertyType::Scalar(ScalarType::Double)));
+        }
+        vertex_def.count = n;
+        ply.header.elements.add(vertex_def);
+        ply.payload.insert("vertex".to_string(), (0..n).map(|_| {
+            let mut e = DefaultElement::new();
+            for (name, [min, max]) in ["x", "y", "z"].iter().zip(bounds.iter()) {
+                e.insert(name.to_string(), Property::Double(min + (max - min) * rng.gen::<f64>()));
+            }
+            e
+        }).collect());
+        ply
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::super::mesh::{ dot, cross };
+
+    fn signed_volume(ply: &Ply<DefaultElement>) -> f64 {
+        let mesh = ply.read_mesh().unwrap();
+        mesh.triangles().iter().map(|t| {
+            let (a, b, c) = (&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]);
+            dot(a, &cross(b, c)) / 6.0
+        }).sum()
+    }
+    #[test]
+    fn cylinder_counts() {
+        let ply = Ply::<DefaultElement>::cylinder(1.0, 2.0, 16, false);
+        assert_eq!(ply.payload["vertex"].len(), 32);
+        assert_eq!(ply.payload["face"].len(), 32);
+        let ply = Ply::<DefaultElement>::cylinder(1.0, 2.0, 16, true);
+        assert_eq!(ply.payload["vertex"].len(), 66);
+        assert_eq!(ply.payload["face"].len(), 64);
+        assert_eq!(ply.header.elements["face"].count, 64);
+    }
+    #[test]
+    fn cylinder_volume() {
+        let (r, h, s) = (2.0, 3.0, 32);
+        let ply = Ply::<DefaultElement>::cylinder(r, h, s, true);
+        let expected = s as f64 / 2.0 * r * r * (2.0 * std::f64::consts::PI / s as f64).sin() * h;
+        assert!((signed_volume(&ply) - expected).abs() < 1e-4);
+    }
+    #[test]
+    fn torus_closed() {
+        let ply = Ply::<DefaultElement>::torus(3.0, 1.0, 48, 24);
+        assert_eq!(ply.payload["vertex"].len(), 48 * 24);
+        assert_eq!(ply.header.elements["face"].count, 2 * 48 * 24);
+        let mesh = ply.read_mesh().unwrap();
+        assert!(mesh::Mesh::boundary_loops(&mesh.triangles()).is_empty());
+        // genus 1: the angle defects sum up to 0
+        assert!(ply.compute_angle_defects().unwrap().iter().sum::<f64>().abs() < 1e-3);
+        let expected = 2.0 * std::f64::consts::PI.powi(2) * 3.0;
+        let volume = signed_volume(&ply);
+        assert!(volume > 0.98 * expected && volume < expected);
+    }
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_point_cloud_bounds() {
+        let bounds = [[-1.0, 1.0], [2.0, 3.0], [5.0, 5.0]];
+        let ply = Ply::<DefaultElement>::random_point_cloud(100, bounds, 3);
+        assert_eq!(ply.header.elements["vertex"].count, 100);
+        let points = ply.read_vertex_positions().unwrap();
+        assert_eq!(points.len(), 100);
+        for p in &points {
+            for (c, b) in p.iter().zip(bounds.iter()) {
+                assert!(*c >= b[0] && *c <= b[1]);
+            }
+        }
+        assert_eq!(ply, Ply::<DefaultElement>::random_point_cloud(100, bounds, 3));
+        assert_ne!(ply, Ply::<DefaultElement>::random_point_cloud(100, bounds, 4));
+    }
+    #[test]
+    fn cylinder_write_read() {
+        let mut ply = Ply::<DefaultElement>::cylinder(1.0, 1.0, 3, true);
+        let mut buf = Vec::<u8>::new();
+        crate::writer::Writer::new().write_ply(&mut buf, &mut ply).unwrap();
+        let read = crate::parser::Parser::<DefaultElement>::new().read_ply(&mut buf.as_slice()).unwrap();
+        assert_eq!(read, ply);
+    }
+}
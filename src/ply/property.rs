@@ -1,8 +1,17 @@
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::hash::{ Hash, Hasher };
+use std::mem;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
 
 /// Scalar type used to encode properties in the payload.
 ///
 /// For the translation to rust types, see individual documentation.
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ScalarType {
     /// Signed 8 bit integer, rust: `i8`.
     Char,
@@ -16,17 +25,58 @@ pub enum ScalarType {
     Int,
     /// Unsigned 32 bit integer, rust: `u32`.
     UInt,
+    /// Signed 64 bit integer, rust: `i64`.
+    ///
+    /// Not part of the PLY specification, read from and written as `int64`.
+    Long,
+    /// Unsigned 64 bit integer, rust: `u64`.
+    ///
+    /// Not part of the PLY specification, read from and written as `uint64`.
+    ULong,
     /// 32 bit floating point number, rust: `f32`.
     Float,
     /// 64 bit floating point number, rust: `f64`.
     Double,
 }
 
+impl fmt::Display for ScalarType {
+    /// Name of the type as written in the header, e.g. `uchar`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(
+            match *self {
+                ScalarType::Char => "char",
+                ScalarType::UChar => "uchar",
+                ScalarType::Short => "short",
+                ScalarType::UShort => "ushort",
+                ScalarType::Int => "int",
+                ScalarType::UInt => "uint",
+                ScalarType::Long => "int64",
+                ScalarType::ULong => "uint64",
+                ScalarType::Float => "float",
+                ScalarType::Double => "double",
+            }
+        )
+    }
+}
+
+impl ScalarType {
+    /// Number of bytes used to store a value of this type in a binary payload.
+    pub fn byte_width(&self) -> usize {
+        match *self {
+            ScalarType::Char | ScalarType::UChar => 1,
+            ScalarType::Short | ScalarType::UShort => 2,
+            ScalarType::Int | ScalarType::UInt | ScalarType::Float => 4,
+            ScalarType::Long | ScalarType::ULong | ScalarType::Double => 8,
+        }
+    }
+}
+
 /// Data type used to encode properties in the payload.
 ///
 /// There are two possible types: scalars and lists.
 /// Lists are a sequence of scalars with a leading integer value defining how many elements the list contains.
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PropertyType {
     /// Simple, "one-number" type.
     Scalar(ScalarType),
@@ -42,8 +92,50 @@ pub enum PropertyType {
     List(ScalarType, ScalarType)
 }
 
+impl fmt::Display for PropertyType {
+    /// Type as written in the header, e.g. `float` or `list uchar int`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PropertyType::Scalar(ref t) => write!(f, "{}", t),
+            PropertyType::List(ref index_type, ref t) => write!(f, "list {} {}", index_type, t),
+        }
+    }
+}
+
+impl PropertyType {
+    /// Returns `true` for `PropertyType::List`.
+    pub fn is_list(&self) -> bool {
+        match *self {
+            PropertyType::List(..) => true,
+            PropertyType::Scalar(_) => false,
+        }
+    }
+    /// Returns `true` for `PropertyType::Scalar`.
+    pub fn is_scalar(&self) -> bool {
+        !self.is_list()
+    }
+    /// Type of a scalar property, `None` for lists.
+    pub fn scalar_type(&self) -> Option<&ScalarType> {
+        match *self {
+            PropertyType::Scalar(ref t) => Some(t),
+            PropertyType::List(..) => None,
+        }
+    }
+    /// Index and element type of a list property, `None` for scalars.
+    pub fn list_types(&self) -> Option<(&ScalarType, &ScalarType)> {
+        match *self {
+            PropertyType::List(ref index_type, ref t) => Some((index_type, t)),
+            PropertyType::Scalar(_) => None,
+        }
+    }
+}
+
 /// Wrapper used to implement a dynamic type system as required by the PLY file format.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// Properties are equal if they have the same variant and the same values.
+/// Unlike for `f32` and `f64`, all `NaN` values are equal to each other, which allows `Eq` and `Hash`,
+/// e.g. to collect unique values in a `HashSet`. As usual, `0.0` and `-0.0` are equal.
+#[derive(Debug, Clone)]
 pub enum Property {
     Char(i8),
     UChar(u8),
@@ -51,6 +143,8 @@ pub enum Property {
     UShort(u16),
     Int(i32),
     UInt(u32),
+    Long(i64),
+    ULong(u64),
     Float(f32),
     Double(f64),
     ListChar(Vec<i8>),
@@ -59,10 +153,237 @@ pub enum Property {
     ListUShort(Vec<u16>),
     ListInt(Vec<i32>),
     ListUInt(Vec<u32>),
+    ListLong(Vec<i64>),
+    ListULong(Vec<u64>),
     ListFloat(Vec<f32>),
     ListDouble(Vec<f64>),
 }
 
+impl Property {
+    /// Name of the variant, e.g. `"Float"` or `"ListUInt"`.
+    fn variant_name(&self) -> &'static str {
+        match *self {
+            Property::Char(_) => "Char",
+            Property::UChar(_) => "UChar",
+            Property::Short(_) => "Short",
+            Property::UShort(_) => "UShort",
+            Property::Int(_) => "Int",
+            Property::UInt(_) => "UInt",
+            Property::Long(_) => "Long",
+            Property::ULong(_) => "ULong",
+            Property::Float(_) => "Float",
+            Property::Double(_) => "Double",
+            Property::ListChar(_) => "ListChar",
+            Property::ListUChar(_) => "ListUChar",
+            Property::ListShort(_) => "ListShort",
+            Property::ListUShort(_) => "ListUShort",
+            Property::ListInt(_) => "ListInt",
+            Property::ListUInt(_) => "ListUInt",
+            Property::ListLong(_) => "ListLong",
+            Property::ListULong(_) => "ListULong",
+            Property::ListFloat(_) => "ListFloat",
+            Property::ListDouble(_) => "ListDouble",
+        }
+    }
+    /// Converts any scalar variant to `f64`, returns `None` for lists.
+    pub fn coerce_to_f64(&self) -> Option<f64> {
+        match *self {
+            Property::Char(v) => Some(v as f64),
+            Property::UChar(v) => Some(v as f64),
+            Property::Short(v) => Some(v as f64),
+            Property::UShort(v) => Some(v as f64),
+            Property::Int(v) => Some(v as f64),
+            Property::UInt(v) => Some(v as f64),
+            Property::Long(v) => Some(v as f64),
+            Property::ULong(v) => Some(v as f64),
+            Property::Float(v) => Some(v as f64),
+            Property::Double(v) => Some(v),
+            _ => None,
+        }
+    }
+    /// Converts any scalar variant to `i64`, returns `None` for lists.
+    ///
+    /// Floating point values are only converted if they are whole numbers within the range of `i64`.
+    pub fn coerce_to_i64(&self) -> Option<i64> {
+        match *self {
+            Property::Char(v) => Some(v as i64),
+            Property::UChar(v) => Some(v as i64),
+            Property::Short(v) => Some(v as i64),
+            Property::UShort(v) => Some(v as i64),
+            Property::Int(v) => Some(v as i64),
+            Property::UInt(v) => Some(v as i64),
+            Property::Long(v) => Some(v),
+            Property::ULong(v) => i64::try_from(v).ok(),
+            Property::Float(v) => whole_number(v as f64, i64::MIN as f64, i64::MAX as f64).map(|v| v as i64),
+            Property::Double(v) => whole_number(v, i64::MIN as f64, i64::MAX as f64).map(|v| v as i64),
+            _ => None,
+        }
+    }
+    /// Converts any scalar variant to `u64`, returns `None` for lists and negative values.
+    ///
+    /// Floating point values are only converted if they are whole numbers within the range of `u64`.
+    pub fn coerce_to_u64(&self) -> Option<u64> {
+        match *self {
+            Property::ULong(v) => Some(v),
+            Property::Float(v) => whole_number(v as f64, 0.0, u64::MAX as f64).map(|v| v as u64),
+            Property::Double(v) => whole_number(v, 0.0, u64::MAX as f64).map(|v| v as u64),
+            _ => self.coerce_to_i64().and_then(|v| u64::try_from(v).ok()),
+        }
+    }
+}
+
+/// Equality of floating point values where all `NaN` values are equal.
+fn float_eq(a: f64, b: f64) -> bool {
+    a == b || (a.is_nan() && b.is_nan())
+}
+
+/// Bits of `v`, identical for values equal according to `float_eq()`.
+fn float_bits(v: f64) -> u64 {
+    if v.is_nan() {
+        f64::NAN.to_bits()
+    } else if v == 0.0 {
+        0
+    } else {
+        v.to_bits()
+    }
+}
+
+fn float_list_eq<F: Copy + Into<f64>>(a: &[F], b: &[F]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(&x, &y)| float_eq(x.into(), y.into()))
+}
+
+impl PartialEq for Property {
+    fn eq(&self, other: &Property) -> bool {
+        match (self, other) {
+            (Property::Char(a), Property::Char(b)) => a == b,
+            (Property::UChar(a), Property::UChar(b)) => a == b,
+            (Property::Short(a), Property::Short(b)) => a == b,
+            (Property::UShort(a), Property::UShort(b)) => a == b,
+            (Property::Int(a), Property::Int(b)) => a == b,
+            (Property::UInt(a), Property::UInt(b)) => a == b,
+            (Property::Long(a), Property::Long(b)) => a == b,
+            (Property::ULong(a), Property::ULong(b)) => a == b,
+            (Property::Float(a), Property::Float(b)) => float_eq(*a as f64, *b as f64),
+            (Property::Double(a), Property::Double(b)) => float_eq(*a, *b),
+            (Property::ListChar(a), Property::ListChar(b)) => a == b,
+            (Property::ListUChar(a), Property::ListUChar(b)) => a == b,
+            (Property::ListShort(a), Property::ListShort(b)) => a == b,
+            (Property::ListUShort(a), Property::ListUShort(b)) => a == b,
+            (Property::ListInt(a), Property::ListInt(b)) => a == b,
+            (Property::ListUInt(a), Property::ListUInt(b)) => a == b,
+            (Property::ListLong(a), Property::ListLong(b)) => a == b,
+            (Property::ListULong(a), Property::ListULong(b)) => a == b,
+            (Property::ListFloat(a), Property::ListFloat(b)) => float_list_eq(a, b),
+            (Property::ListDouble(a), Property::ListDouble(b)) => float_list_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Property {}
+
+impl Hash for Property {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match *self {
+            Property::Char(v) => v.hash(state),
+            Property::UChar(v) => v.hash(state),
+            Property::Short(v) => v.hash(state),
+            Property::UShort(v) => v.hash(state),
+            Property::Int(v) => v.hash(state),
+            Property::UInt(v) => v.hash(state),
+            Property::Long(v) => v.hash(state),
+            Property::ULong(v) => v.hash(state),
+            Property::Float(v) => float_bits(v as f64).hash(state),
+            Property::Double(v) => float_bits(v).hash(state),
+            Property::ListChar(ref v) => v.hash(state),
+            Property::ListUChar(ref v) => v.hash(state),
+            Property::ListShort(ref v) => v.hash(state),
+            Property::ListUShort(ref v) => v.hash(state),
+            Property::ListInt(ref v) => v.hash(state),
+            Property::ListUInt(ref v) => v.hash(state),
+            Property::ListLong(ref v) => v.hash(state),
+            Property::ListULong(ref v) => v.hash(state),
+            Property::ListFloat(ref v) => {
+                v.len().hash(state);
+                v.iter().for_each(|&x| float_bits(x as f64).hash(state));
+            },
+            Property::ListDouble(ref v) => {
+                v.len().hash(state);
+                v.iter().for_each(|&x| float_bits(x).hash(state));
+            },
+        }
+    }
+}
+
+/// Returns `v` if it has no fractional part and lies within `[min, max)`.
+///
+/// The upper bound is exclusive, since `i64::MAX` and `u64::MAX` round up to the next power of two as `f64`.
+fn whole_number(v: f64, min: f64, max: f64) -> Option<f64> {
+    if v.fract() == 0.0 && v >= min && v < max {
+        Some(v)
+    } else {
+        None
+    }
+}
+
+/// Returned when a `Property` is converted into a rust type that doesn't match its variant.
+///
+/// No numeric conversion takes place: `Property::Double` only converts into `f64`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryFromPropertyError {
+    /// Variant that would have been required, e.g. `"Float"` for `f32`.
+    pub expected: &'static str,
+    /// Variant that has been found.
+    pub actual: &'static str,
+}
+
+impl fmt::Display for TryFromPropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expected property of variant `{}`, but found `{}`.", self.expected, self.actual)
+    }
+}
+
+impl error::Error for TryFromPropertyError {}
+
+macro_rules! try_from_property(
+    ($t:ty, $variant:ident) => (
+        impl TryFrom<Property> for $t {
+            type Error = TryFromPropertyError;
+            fn try_from(property: Property) -> Result<Self, Self::Error> {
+                match property {
+                    Property::$variant(x) => Ok(x),
+                    p => Err(TryFromPropertyError {
+                        expected: stringify!($variant),
+                        actual: p.variant_name(),
+                    }),
+                }
+            }
+        }
+    )
+);
+
+try_from_property!(i8, Char);
+try_from_property!(u8, UChar);
+try_from_property!(i16, Short);
+try_from_property!(u16, UShort);
+try_from_property!(i32, Int);
+try_from_property!(u32, UInt);
+try_from_property!(i64, Long);
+try_from_property!(u64, ULong);
+try_from_property!(f32, Float);
+try_from_property!(f64, Double);
+try_from_property!(Vec<i8>, ListChar);
+try_from_property!(Vec<u8>, ListUChar);
+try_from_property!(Vec<i16>, ListShort);
+try_from_property!(Vec<u16>, ListUShort);
+try_from_property!(Vec<i32>, ListInt);
+try_from_property!(Vec<u32>, ListUInt);
+try_from_property!(Vec<i64>, ListLong);
+try_from_property!(Vec<u64>, ListULong);
+try_from_property!(Vec<f32>, ListFloat);
+try_from_property!(Vec<f64>, ListDouble);
+
 /// Provides setters and getters for the Parser and the Writer.
 ///
 /// This trait allows you to create your own data structure for the case that the
@@ -80,52 +401,158 @@ pub trait PropertyAccess {
         // By default, do nothing
         // Sombody might only want to write, no point in bothering him/her with setter implementations.
     }
-    fn get_char(&self, _property_name: &String) -> Option<i8> {
+    fn get_char(&self, _property_name: &str) -> Option<i8> {
+        None
+    }
+    fn get_uchar(&self, _property_name: &str) -> Option<u8> {
+        None
+    }
+    fn get_short(&self, _property_name: &str) -> Option<i16> {
+        None
+    }
+    fn get_ushort(&self, _property_name: &str) -> Option<u16> {
+        None
+    }
+    fn get_int(&self, _property_name: &str) -> Option<i32> {
         None
     }
-    fn get_uchar(&self, _property_name: &String) -> Option<u8> {
+    fn get_uint(&self, _property_name: &str) -> Option<u32> {
         None
     }
-    fn get_short(&self, _property_name: &String) -> Option<i16> {
+    fn get_long(&self, _property_name: &str) -> Option<i64> {
         None
     }
-    fn get_ushort(&self, _property_name: &String) -> Option<u16> {
+    fn get_ulong(&self, _property_name: &str) -> Option<u64> {
         None
     }
-    fn get_int(&self, _property_name: &String) -> Option<i32> {
+    fn get_float(&self, _property_name: &str) -> Option<f32> {
         None
     }
-    fn get_uint(&self, _property_name: &String) -> Option<u32> {
+    fn get_double(&self, _property_name: &str) -> Option<f64> {
         None
     }
-    fn get_float(&self, _property_name: &String) -> Option<f32> {
+    fn get_list_char(&self, _property_name: &str) -> Option<&[i8]> {
         None
     }
-    fn get_double(&self, _property_name: &String) -> Option<f64> {
+    fn get_list_uchar(&self, _property_name: &str) -> Option<&[u8]> {
         None
     }
-    fn get_list_char(&self, _property_name: &String) -> Option<&[i8]> {
+    fn get_list_short(&self, _property_name: &str) -> Option<&[i16]> {
         None
     }
-    fn get_list_uchar(&self, _property_name: &String) -> Option<&[u8]> {
+    fn get_list_ushort(&self, _property_name: &str) -> Option<&[u16]> {
         None
     }
-    fn get_list_short(&self, _property_name: &String) -> Option<&[i16]> {
+    fn get_list_int(&self, _property_name: &str) -> Option<&[i32]> {
         None
     }
-    fn get_list_ushort(&self, _property_name: &String) -> Option<&[u16]> {
+    fn get_list_uint(&self, _property_name: &str) -> Option<&[u32]> {
         None
     }
-    fn get_list_int(&self, _property_name: &String) -> Option<&[i32]> {
+    fn get_list_long(&self, _property_name: &str) -> Option<&[i64]> {
         None
     }
-    fn get_list_uint(&self, _property_name: &String) -> Option<&[u32]> {
+    fn get_list_ulong(&self, _property_name: &str) -> Option<&[u64]> {
         None
     }
-    fn get_list_float(&self, _property_name: &String) -> Option<&[f32]> {
+    fn get_list_float(&self, _property_name: &str) -> Option<&[f32]> {
         None
     }
-    fn get_list_double(&self, _property_name: &String) -> Option<&[f64]> {
+    fn get_list_double(&self, _property_name: &str) -> Option<&[f64]> {
         None
     }
+    /// Returns the property regardless of its type, for elements storing `Property` values.
+    fn get_property(&self, _property_name: &str) -> Option<&Property> {
+        None
+    }
+    /// Returns the length of a list property regardless of its element type.
+    fn list_len(&self, _property_name: &str) -> Option<usize> {
+        None
+    }
+    /// Number of properties stored in the element.
+    fn property_count(&self) -> usize {
+        0
+    }
+    /// Names of all properties stored in the element.
+    fn property_keys(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use super::*;
+    #[test]
+    fn try_from_property_ok() {
+        let x: f32 = Property::Float(1.5).try_into().unwrap();
+        assert_eq!(x, 1.5);
+        let i: u8 = Property::UChar(7).try_into().unwrap();
+        assert_eq!(i, 7);
+        let l: Vec<u32> = Property::ListUInt(vec![1, 2]).try_into().unwrap();
+        assert_eq!(l, vec![1, 2]);
+    }
+    #[test]
+    fn try_from_property_err() {
+        let r: Result<f32, _> = Property::Double(1.5).try_into();
+        assert_eq!(r, Err(TryFromPropertyError { expected: "Float", actual: "Double" }));
+        let r: Result<Vec<i32>, _> = Property::Int(3).try_into();
+        assert_eq!(r.unwrap_err().to_string(), "Expected property of variant `ListInt`, but found `Int`.");
+    }
+    #[test]
+    fn property_type_helpers() {
+        let scalar = PropertyType::Scalar(ScalarType::Float);
+        let list = PropertyType::List(ScalarType::UChar, ScalarType::Int);
+        assert!(scalar.is_scalar() && !scalar.is_list());
+        assert!(list.is_list() && !list.is_scalar());
+        assert_eq!(scalar.scalar_type(), Some(&ScalarType::Float));
+        assert_eq!(list.scalar_type(), None);
+        assert_eq!(list.list_types(), Some((&ScalarType::UChar, &ScalarType::Int)));
+        assert_eq!(scalar.list_types(), None);
+    }
+    #[test]
+    fn coerce_scalars() {
+        assert_eq!(Property::Char(-3).coerce_to_f64(), Some(-3.0));
+        assert_eq!(Property::Float(0.5).coerce_to_f64(), Some(0.5));
+        assert_eq!(Property::ListInt(vec![1]).coerce_to_f64(), None);
+        assert_eq!(Property::UInt(u32::MAX).coerce_to_i64(), Some(u32::MAX as i64));
+        assert_eq!(Property::Double(-2.0).coerce_to_i64(), Some(-2));
+        assert_eq!(Property::Double(2.5).coerce_to_i64(), None);
+        assert_eq!(Property::Double(f64::NAN).coerce_to_i64(), None);
+        assert_eq!(Property::Double(1e19).coerce_to_i64(), None);
+        assert_eq!(Property::Double(1e19).coerce_to_u64(), Some(10_000_000_000_000_000_000));
+        assert_eq!(Property::Short(-1).coerce_to_u64(), None);
+        assert_eq!(Property::UChar(7).coerce_to_u64(), Some(7));
+        assert_eq!(Property::ListUChar(vec![7]).coerce_to_u64(), None);
+        assert_eq!(Property::ULong(u64::MAX).coerce_to_u64(), Some(u64::MAX));
+        assert_eq!(Property::ULong(u64::MAX).coerce_to_i64(), None);
+        assert_eq!(Property::Long(i64::MIN).coerce_to_i64(), Some(i64::MIN));
+    }
+    #[test]
+    fn property_eq_hash() {
+        use std::collections::HashSet;
+        assert_eq!(Property::Float(f32::NAN), Property::Float(-f32::NAN));
+        assert_eq!(Property::Double(0.0), Property::Double(-0.0));
+        assert_eq!(Property::ListDouble(vec![f64::NAN, 1.0]), Property::ListDouble(vec![f64::NAN, 1.0]));
+        assert_ne!(Property::Float(1.0), Property::Double(1.0));
+        assert_ne!(Property::ListFloat(vec![1.0]), Property::ListFloat(vec![1.0, 2.0]));
+        let colors: HashSet<Property> = vec![
+            Property::ListUChar(vec![255, 0, 0]),
+            Property::ListUChar(vec![0, 255, 0]),
+            Property::ListUChar(vec![255, 0, 0]),
+            Property::Double(f64::NAN),
+            Property::Double(f64::NAN),
+            Property::Double(0.0),
+            Property::Double(-0.0),
+        ].into_iter().collect();
+        assert_eq!(colors.len(), 4);
+        assert!(colors.contains(&Property::Double(-0.0)));
+    }
+    #[test]
+    fn byte_width() {
+        assert_eq!(ScalarType::UChar.byte_width(), 1);
+        assert_eq!(ScalarType::Short.byte_width(), 2);
+        assert_eq!(ScalarType::Float.byte_width(), 4);
+        assert_eq!(ScalarType::Double.byte_width(), 8);
+    }
 }
@@ -0,0 +1,130 @@
+//! Measures of mesh resolution and element quality.
+
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ sub, dist, angle, invalid_input };
+
+/// Summary of the edge lengths of a mesh, see `Ply::edge_length_statistics()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeLengthStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Population standard deviation.
+    pub std: f64,
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the length of every edge.
+    ///
+    /// Each edge shared by several faces is reported once,
+    /// in the order of `(a, b)` with `a < b`, sorted ascending.
+    pub fn compute_edge_lengths(&self) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        Ok(mesh.edges().iter().map(|&(a, b)| dist(&mesh.vertices[a], &mesh.vertices[b])).collect())
+    }
+    /// Computes the edge lengths and fails if there are none.
+    fn non_empty_edge_lengths(&self) -> Result<Vec<f64>> {
+        let lengths = self.compute_edge_lengths()?;
+        if lengths.is_empty() {
+            return invalid_input("Mesh has no edges.");
+        }
+        Ok(lengths)
+    }
+    /// Returns the mean edge length, a common length scale of a mesh.
+    pub fn mean_edge_length(&self) -> Result<f64> {
+        let lengths = self.non_empty_edge_lengths()?;
+        Ok(lengths.iter().sum::<f64>() / lengths.len() as f64)
+    }
+    /// Returns the median edge length.
+    ///
+    /// For an even number of edges, the upper of the two middle values is returned.
+    pub fn median_edge_length(&self) -> Result<f64> {
+        let mut lengths = self.non_empty_edge_lengths()?;
+        lengths.sort_unstable_by(|a, b| a.total_cmp(b));
+        Ok(lengths[lengths.len() / 2])
+    }
+    /// Returns minimum, maximum, mean, and standard deviation of the edge lengths.
+    pub fn edge_length_statistics(&self) -> Result<EdgeLengthStats> {
+        let lengths = self.non_empty_edge_lengths()?;
+        let n = lengths.len() as f64;
+        let mean = lengths.iter().sum::<f64>() / n;
+        let variance = lengths.iter().map(|l| (l - mean) * (l - mean)).sum::<f64>() / n;
+        Ok(EdgeLengthStats {
+            min: lengths.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: lengths.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std: variance.sqrt(),
+        })
+    }
+    /// Computes the ratio of the longest to the shortest edge of each face.
+    ///
+    /// An equilateral triangle has a ratio of `1`, larger values indicate worse shaped faces.
+    /// Faces with an edge of length zero have an infinite ratio.
+    /// For polygons, all edges along the boundary of the polygon are taken into account.
+    pub fn compute_aspect_ratios(&self) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        Ok(mesh.faces.iter().map(|f| {
+            let lengths = (0..f.len()).map(|k| dist(&mesh.vertices[f[k]], &mesh.vertices[f[(k + 1) % f.len()]]));
+            let (min, max) = lengths.fold((f64::INFINITY, 0.0f64), |(min, max), l| (min.min(l), max.max(l)));
+            max / min
+        }).collect())
+    }
+    /// Computes the smallest interior angle of each face in degrees.
+    ///
+    /// An equilateral triangle has a minimum angle of `60`, values close to `0` indicate needle-like faces.
+    /// For polygons, the angle at every corner is taken into account.
+    pub fn compute_minimum_angles(&self) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        Ok(mesh.faces.iter().map(|f| {
+            (0..f.len()).map(|k| {
+                let corner = &mesh.vertices[f[k]];
+                let next = &mesh.vertices[f[(k + 1) % f.len()]];
+                let previous = &mesh.vertices[f[(k + f.len() - 1) % f.len()]];
+                angle(&sub(next, corner), &sub(previous, corner)).to_degrees()
+            }).fold(f64::INFINITY, f64::min)
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn edge_lengths_grid() {
+        let ply = fixtures::grid(1);
+        let lengths = ply.compute_edge_lengths().unwrap();
+        assert_eq!(lengths.len(), 5);
+        assert!((ply.mean_edge_length().unwrap() - (4.0 + 2f64.sqrt()) / 5.0).abs() < 1e-12);
+        assert_eq!(ply.median_edge_length().unwrap(), 1.0);
+        let stats = ply.edge_length_statistics().unwrap();
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 2f64.sqrt());
+        assert!(stats.std > 0.0);
+    }
+    #[test]
+    fn edge_lengths_regular() {
+        let stats = fixtures::icosahedron().edge_length_statistics().unwrap();
+        assert!(stats.std < 1e-9);
+        assert!((stats.min - stats.max).abs() < 1e-9);
+    }
+    #[test]
+    fn aspect_ratios_and_angles() {
+        let ply = fixtures::icosahedron();
+        assert!(ply.compute_aspect_ratios().unwrap().iter().all(|&r| (r - 1.0).abs() < 1e-9));
+        assert!(ply.compute_minimum_angles().unwrap().iter().all(|&a| (a - 60.0).abs() < 1e-9));
+        let ply = fixtures::grid(1);
+        assert!(ply.compute_aspect_ratios().unwrap().iter().all(|&r| (r - 2f64.sqrt()).abs() < 1e-12));
+        assert!(ply.compute_minimum_angles().unwrap().iter().all(|&a| (a - 45.0).abs() < 1e-9));
+    }
+    #[test]
+    fn edge_lengths_no_edges_err() {
+        let ply = fixtures::from_mesh(&[[0.0; 3]], &[]);
+        assert!(ply.compute_edge_lengths().unwrap().is_empty());
+        assert!(ply.mean_edge_length().is_err());
+        assert!(ply.median_edge_length().is_err());
+    }
+}
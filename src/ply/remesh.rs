@@ -0,0 +1,414 @@
+//! Isotropic remeshing as described by Botsch and Kobbelt,
+//! "A Remeshing Approach to Multiresolution Modeling" (2004).
+
+use std::collections::{ HashMap, HashSet };
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::{ PropertyDef, PropertyType };
+use super::mesh::{ Vec3, add, sub, scale, dot, cross, dist, normalize, scalar_from_f64, invalid_input };
+
+/// Step size of the tangential smoothing.
+const SMOOTHING: f64 = 0.5;
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Rotates `t` such that the edge `{a, b}` comes first, returns `None` if `t` doesn't contain the edge.
+fn rotate_to_edge(t: &[usize; 3], a: usize, b: usize) -> Option<[usize; 3]> {
+    (0..3).map(|k| [t[k], t[(k + 1) % 3], t[(k + 2) % 3]])
+        .find(|r| (r[0] == a && r[1] == b) || (r[0] == b && r[1] == a))
+}
+
+/// Triangle mesh under modification.
+///
+/// Every vertex carries the values of all scalar vertex properties, the position is stored in the columns `xyz`.
+/// Every triangle remembers the face it originates from.
+struct Remesher {
+    rows: Vec<Vec<f64>>,
+    xyz: [usize; 3],
+    triangles: Vec<Option<[usize; 3]>>,
+    parents: Vec<usize>,
+}
+
+impl Remesher {
+    fn position(&self, v: usize) -> Vec3 {
+        [self.rows[v][self.xyz[0]], self.rows[v][self.xyz[1]], self.rows[v][self.xyz[2]]]
+    }
+    fn set_position(&mut self, v: usize, p: &Vec3) {
+        for (&column, &value) in self.xyz.iter().zip(p.iter()) {
+            self.rows[v][column] = value;
+        }
+    }
+    fn length(&self, (a, b): (usize, usize)) -> f64 {
+        dist(&self.position(a), &self.position(b))
+    }
+    fn normal(&self, t: &[usize; 3]) -> Vec3 {
+        let (a, b, c) = (self.position(t[0]), self.position(t[1]), self.position(t[2]));
+        cross(&sub(&b, &a), &sub(&c, &a))
+    }
+    fn edge_map(&self) -> HashMap<(usize, usize), Vec<usize>> {
+        let mut edges = HashMap::<(usize, usize), Vec<usize>>::new();
+        for (ti, t) in self.triangles.iter().enumerate() {
+            if let Some(t) = t {
+                for k in 0..3 {
+                    edges.entry(edge_key(t[k], t[(k + 1) % 3])).or_default().push(ti);
+                }
+            }
+        }
+        edges
+    }
+    /// Edges sorted by length, longest first if `descending`.
+    fn sorted_edges(&self, edges: &HashMap<(usize, usize), Vec<usize>>, descending: bool) -> Vec<(usize, usize)> {
+        let mut sorted: Vec<_> = edges.keys().map(|&e| (self.length(e), e)).collect();
+        sorted.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        if descending {
+            sorted.reverse();
+        }
+        sorted.into_iter().map(|(_, e)| e).collect()
+    }
+    fn boundary_vertices(&self, edges: &HashMap<(usize, usize), Vec<usize>>) -> Vec<bool> {
+        let mut boundary = vec![false; self.rows.len()];
+        for (&(a, b), t) in edges {
+            if t.len() == 1 {
+                boundary[a] = true;
+                boundary[b] = true;
+            }
+        }
+        boundary
+    }
+    fn vertex_triangles(&self) -> Vec<Vec<usize>> {
+        let mut incident = vec![Vec::new(); self.rows.len()];
+        for (ti, t) in self.triangles.iter().enumerate() {
+            if let Some(t) = t {
+                for &v in t {
+                    incident[v].push(ti);
+                }
+            }
+        }
+        incident
+    }
+    fn add_vertex(&mut self, row: Vec<f64>) -> usize {
+        self.rows.push(row);
+        self.rows.len() - 1
+    }
+    fn average(&self, a: usize, b: usize) -> Vec<f64> {
+        self.rows[a].iter().zip(self.rows[b].iter()).map(|(x, y)| (x + y) / 2.0).collect()
+    }
+
+    /// Splits all edges longer than `max_length` at their midpoint.
+    fn split_long_edges(&mut self, max_length: f64) {
+        loop {
+            let edges = self.edge_map();
+            let mut touched = HashSet::new();
+            let mut split = false;
+            for (a, b) in self.sorted_edges(&edges, true) {
+                if self.length((a, b)) <= max_length {
+                    break;
+                }
+                let incident = &edges[&(a, b)];
+                if incident.iter().any(|t| touched.contains(t)) {
+                    continue;
+                }
+                let m = self.add_vertex(self.average(a, b));
+                for &ti in incident {
+                    let t = rotate_to_edge(&self.triangles[ti].unwrap(), a, b).unwrap();
+                    self.triangles[ti] = Some([t[0], m, t[2]]);
+                    self.triangles.push(Some([m, t[1], t[2]]));
+                    self.parents.push(self.parents[ti]);
+                    touched.insert(ti);
+                }
+                split = true;
+            }
+            if !split {
+                break;
+            }
+        }
+    }
+
+    /// Collapses interior edges shorter than `min_length` into their midpoint,
+    /// unless this creates edges longer than `max_length`, breaks the topology, or flips a triangle.
+    fn collapse_short_edges(&mut self, min_length: f64, max_length: f64) {
+        loop {
+            let edges = self.edge_map();
+            let boundary = self.boundary_vertices(&edges);
+            let incident = self.vertex_triangles();
+            let mut touched = HashSet::new();
+            let mut collapsed = false;
+            for (a, b) in self.sorted_edges(&edges, false) {
+                if self.length((a, b)) >= min_length {
+                    break;
+                }
+                if boundary[a] || boundary[b] {
+                    continue;
+                }
+                let around: Vec<usize> = incident[a].iter().chain(incident[b].iter()).cloned().collect();
+                if around.iter().any(|t| touched.contains(t)) {
+                    continue;
+                }
+                let neighbours = |v: usize| -> HashSet<usize> {
+                    incident[v].iter().flat_map(|&t| self.triangles[t].unwrap().to_vec()).filter(|&u| u != v).collect()
+                };
+                let (na, nb) = (neighbours(a), neighbours(b));
+                // link condition for an interior edge
+                if na.intersection(&nb).count() != 2 {
+                    continue;
+                }
+                let midpoint = scale(&add(&self.position(a), &self.position(b)), 0.5);
+                if na.union(&nb).any(|&v| v != a && v != b && dist(&midpoint, &self.position(v)) > max_length) {
+                    continue;
+                }
+                let removed = &edges[&(a, b)];
+                let flips = around.iter().filter(|t| !removed.contains(t)).any(|&ti| {
+                    let t = self.triangles[ti].unwrap();
+                    let moved: Vec<Vec3> = t.iter().map(|&v| if v == a || v == b { midpoint } else { self.position(v) }).collect();
+                    let after = cross(&sub(&moved[1], &moved[0]), &sub(&moved[2], &moved[0]));
+                    dot(&after, &self.normal(&t)) <= 0.0
+                });
+                if flips {
+                    continue;
+                }
+                self.rows[a] = self.average(a, b);
+                for &ti in &around {
+                    touched.insert(ti);
+                    if removed.contains(&ti) {
+                        self.triangles[ti] = None;
+                    } else if let Some(t) = self.triangles[ti].as_mut() {
+                        for v in t.iter_mut() {
+                            if *v == b {
+                                *v = a;
+                            }
+                        }
+                    }
+                }
+                collapsed = true;
+            }
+            if !collapsed {
+                break;
+            }
+        }
+    }
+
+    /// Flips interior edges if this brings the valences closer to `6` (`4` on the boundary).
+    fn flip_edges(&mut self) {
+        let edges = self.edge_map();
+        let boundary = self.boundary_vertices(&edges);
+        let mut valence = vec![0i64; self.rows.len()];
+        for &(a, b) in edges.keys() {
+            valence[a] += 1;
+            valence[b] += 1;
+        }
+        let mut existing: HashSet<(usize, usize)> = edges.keys().cloned().collect();
+        let mut touched = HashSet::new();
+        let mut keys: Vec<_> = edges.keys().cloned().collect();
+        keys.sort_unstable();
+        for (a, b) in keys {
+            let incident = &edges[&(a, b)];
+            if incident.len() != 2 || incident.iter().any(|t| touched.contains(t)) {
+                continue;
+            }
+            let t0 = rotate_to_edge(&self.triangles[incident[0]].unwrap(), a, b).unwrap();
+            let t1 = rotate_to_edge(&self.triangles[incident[1]].unwrap(), a, b).unwrap();
+            // t0 = [u, w, c], t1 = [w, u, d] for consistently oriented faces
+            let (u, w, c, d) = (t0[0], t0[1], t0[2], t1[2]);
+            if t1[0] != w || c == d || existing.contains(&edge_key(c, d)) {
+                continue;
+            }
+            let target = |v: usize| if boundary[v] { 4 } else { 6 };
+            let deviation = |vu: i64, vw: i64, vc: i64, vd: i64| {
+                (vu - target(u)).pow(2) + (vw - target(w)).pow(2) + (vc - target(c)).pow(2) + (vd - target(d)).pow(2)
+            };
+            let before = deviation(valence[u], valence[w], valence[c], valence[d]);
+            let after = deviation(valence[u] - 1, valence[w] - 1, valence[c] + 1, valence[d] + 1);
+            if after >= before {
+                continue;
+            }
+            let (n0, n1) = ([d, w, c], [c, u, d]);
+            let normal = add(&self.normal(&t0), &self.normal(&t1));
+            if dot(&self.normal(&n0), &normal) <= 0.0 || dot(&self.normal(&n1), &normal) <= 0.0 {
+                continue;
+            }
+            self.triangles[incident[0]] = Some(n0);
+            self.triangles[incident[1]] = Some(n1);
+            valence[u] -= 1;
+            valence[w] -= 1;
+            valence[c] += 1;
+            valence[d] += 1;
+            existing.remove(&(a, b));
+            existing.insert(edge_key(c, d));
+            touched.insert(incident[0]);
+            touched.insert(incident[1]);
+        }
+    }
+
+    /// Moves every interior vertex towards the centroid of its neighbours, within its tangent plane.
+    fn smooth_tangentially(&mut self) {
+        let edges = self.edge_map();
+        let boundary = self.boundary_vertices(&edges);
+        let n = self.rows.len();
+        let mut sum = vec![[0.0; 3]; n];
+        let mut count = vec![0usize; n];
+        for &(a, b) in edges.keys() {
+            sum[a] = add(&sum[a], &self.position(b));
+            sum[b] = add(&sum[b], &self.position(a));
+            count[a] += 1;
+            count[b] += 1;
+        }
+        let mut normals = vec![[0.0; 3]; n];
+        for t in self.triangles.iter().flatten() {
+            let normal = self.normal(t);
+            for &v in t {
+                normals[v] = add(&normals[v], &normal);
+            }
+        }
+        let positions: Vec<Vec3> = (0..n).map(|v| self.position(v)).collect();
+        for v in 0..n {
+            if boundary[v] || count[v] == 0 {
+                continue;
+            }
+            let normal = normalize(&normals[v]);
+            let delta = sub(&scale(&sum[v], 1.0 / count[v] as f64), &positions[v]);
+            let tangential = sub(&delta, &scale(&normal, dot(&delta, &normal)));
+            self.set_position(v, &add(&positions[v], &scale(&tangential, SMOOTHING)));
+        }
+    }
+}
+
+impl<E: PropertyAccess + Clone> Ply<E> {
+    /// Remeshes the surface into near-equilateral triangles with edges of about `target_edge_length`.
+    ///
+    /// Each of the `iterations` follows the pipeline of Botsch and Kobbelt:
+    ///
+    /// 1. split edges longer than `4/3 * target_edge_length`,
+    /// 2. collapse edges shorter than `4/5 * target_edge_length`,
+    /// 3. flip edges to bring the valences closer to `6` (`4` on the boundary),
+    /// 4. move vertices towards the centroid of their neighbours within the tangent plane.
+    ///
+    /// Boundary vertices are neither collapsed nor moved, boundary edges are only split.
+    /// New vertices interpolate all scalar vertex properties of the edge they are inserted on,
+    /// new faces inherit all properties of the face they originate from.
+    /// Vertices that aren't referenced by any face are removed,
+    /// the `vertex` and `face` elements are replaced and their counts in the header adjusted.
+    ///
+    /// The mesh must consist of triangles only and every edge must be shared by at most two faces,
+    /// list properties on `vertex` aren't supported.
+    pub fn isotropic_remesh(&mut self, target_edge_length: f64, iterations: u32) -> Result<()> {
+        if target_edge_length.is_nan() || target_edge_length <= 0.0 {
+            return invalid_input("Target edge length must be positive.");
+        }
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        if let Some(f) = mesh.faces.iter().position(|f| f.len() != 3) {
+            return invalid_input(&format!("Face {} isn't a triangle, isotropic remeshing requires a triangle mesh.", f));
+        }
+        if let Some((e, _)) = mesh.edge_faces().iter().find(|(_, f)| f.len() > 2) {
+            return invalid_input(&format!("Edge {:?} is shared by more than two faces.", e));
+        }
+        if iterations == 0 {
+            return Ok(());
+        }
+        let vertex_defs: Vec<PropertyDef> = self.header.elements["vertex"].properties.values().cloned().collect();
+        if let Some(d) = vertex_defs.iter().find(|d| matches!(d.data_type, PropertyType::List(_, _))) {
+            return invalid_input(&format!("List property `{}` of `vertex` can't be interpolated.", d.name));
+        }
+        let column = |name: &str| vertex_defs.iter().position(|d| d.name == name).unwrap();
+        let triangles = mesh.triangles();
+        let mut remesher = Remesher {
+            rows: self.read_scalar_rows("vertex", &vertex_defs)?,
+            xyz: [column("x"), column("y"), column("z")],
+            triangles: triangles.into_iter().map(Some).collect(),
+            parents: (0..mesh.faces.len()).collect(),
+        };
+        for _ in 0..iterations {
+            remesher.split_long_edges(4.0 / 3.0 * target_edge_length);
+            remesher.collapse_short_edges(0.8 * target_edge_length, 4.0 / 3.0 * target_edge_length);
+            remesher.flip_edges();
+            remesher.smooth_tangentially();
+        }
+
+        // drop unreferenced vertices and removed triangles
+        let mut index = vec![usize::MAX; remesher.rows.len()];
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        let mut indices = Vec::new();
+        let old_faces = &self.payload["face"];
+        for (t, &parent) in remesher.triangles.iter().zip(remesher.parents.iter()) {
+            let t = match t {
+                Some(t) => t,
+                None => continue,
+            };
+            for &v in t {
+                if index[v] == usize::MAX {
+                    index[v] = vertices.len();
+                    let mut e = E::new();
+                    for (d, &value) in vertex_defs.iter().zip(remesher.rows[v].iter()) {
+                        if let PropertyType::Scalar(ref s) = d.data_type {
+                            e.set_property(d.name.clone(), scalar_from_f64(s, value));
+                        }
+                    }
+                    vertices.push(e);
+                }
+            }
+            indices.push(t.iter().map(|&v| index[v]).collect::<Vec<_>>());
+            faces.push(old_faces[parent].clone());
+        }
+        // convert the indices before modifying the payload, so errors leave `self` unchanged
+        let (index_name, index_properties) = self.face_index_properties(&indices)?;
+        for (f, index_property) in faces.iter_mut().zip(index_properties) {
+            f.set_property(index_name.clone(), index_property);
+        }
+        self.header.elements.get_mut("vertex").unwrap().count = vertices.len();
+        self.header.elements.get_mut("face").unwrap().count = faces.len();
+        self.payload.insert("vertex".to_string(), vertices);
+        self.payload.insert("face".to_string(), faces);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn isotropic_remesh_plane() {
+        let mut ply = fixtures::grid(4);
+        ply.isotropic_remesh(0.5, 5).unwrap();
+        let mesh = ply.read_mesh().unwrap();
+        assert!(mesh.faces.len() > 32);
+        assert!(mesh.vertices.iter().all(|p| p[2] == 0.0));
+        let stats = ply.edge_length_statistics().unwrap();
+        assert!(stats.mean > 0.35 && stats.mean < 0.7);
+        assert!(stats.max <= 4.0 / 3.0 * 0.5 + 1e-9);
+        // the boundary is preserved
+        let max_x = mesh.vertices.iter().map(|p| p[0]).fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(max_x, 4.0);
+    }
+    #[test]
+    fn isotropic_remesh_closed() {
+        let mut ply = fixtures::icosahedron();
+        let length = ply.mean_edge_length().unwrap();
+        ply.isotropic_remesh(length / 3.0, 3).unwrap();
+        let mesh = ply.read_mesh().unwrap();
+        assert!(mesh.vertices.len() > 12);
+        // still a closed surface of genus 0
+        let euler = mesh.vertices.len() as i64 - mesh.edges().len() as i64 + mesh.faces.len() as i64;
+        assert_eq!(euler, 2);
+        assert!(mesh.edge_faces().values().all(|f| f.len() == 2));
+        assert_eq!(ply.header.elements["vertex"].count, mesh.vertices.len());
+    }
+    #[test]
+    fn isotropic_remesh_err_unchanged() {
+        // splitting the edges of the grid exceeds the 256 vertices addressable by `uchar` indices
+        let mut ply = fixtures::with_uchar_indices(fixtures::grid(4));
+        let before = ply.clone();
+        assert!(ply.isotropic_remesh(0.1, 1).is_err());
+        assert_eq!(ply, before);
+    }
+    #[test]
+    fn isotropic_remesh_invalid_err() {
+        let mut ply = fixtures::icosahedron();
+        assert!(ply.isotropic_remesh(0.0, 1).is_err());
+        let mut ply = fixtures::from_mesh(&[[0.0; 3]; 4], &[vec![0, 1, 2, 3]]);
+        assert!(ply.isotropic_remesh(1.0, 1).is_err());
+    }
+}
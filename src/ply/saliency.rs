@@ -0,0 +1,122 @@
+//! Mesh saliency as described by Lee et al., "Mesh Saliency" (SIGGRAPH 2005).
+
+use std::collections::VecDeque;
+use std::io::Result;
+use nalgebra::DVector;
+use super::Ply;
+use super::PropertyAccess;
+use super::{ Property, PropertyDef, PropertyType, ScalarType };
+use super::curvature::CurvatureData;
+use super::mesh::{ Mesh, dist, invalid_input };
+
+/// Gaussian weighted average of `values` around each vertex.
+///
+/// The neighbourhood of a vertex contains all vertices reachable over edges
+/// that lie within a distance of `2 * sigma`.
+fn gaussian_weighted_average(mesh: &Mesh, neighbours: &[Vec<usize>], values: &DVector<f64>, sigma: f64) -> DVector<f64> {
+    let n = mesh.vertices.len();
+    let radius = 2.0 * sigma;
+    let mut visited = vec![usize::MAX; n];
+    let mut queue = VecDeque::new();
+    DVector::from_fn(n, |i, _| {
+        let center = &mesh.vertices[i];
+        let (mut weighted, mut total) = (0.0, 0.0);
+        visited[i] = i;
+        queue.push_back(i);
+        while let Some(j) = queue.pop_front() {
+            let d = dist(center, &mesh.vertices[j]);
+            let w = (-d * d / (2.0 * sigma * sigma)).exp();
+            weighted += w * values[j];
+            total += w;
+            for &k in &neighbours[j] {
+                if visited[k] != i && dist(center, &mesh.vertices[k]) <= radius {
+                    visited[k] = i;
+                    queue.push_back(k);
+                }
+            }
+        }
+        weighted / total
+    })
+}
+
+/// Normalizes `map` to `[0, 1]` and promotes maps with few distinct peaks.
+///
+/// The map is scaled by `(1 - m)²`, where `m` is the mean of all local maxima except the global one.
+fn suppress(map: &DVector<f64>, neighbours: &[Vec<usize>]) -> DVector<f64> {
+    let (min, max) = (map.min(), map.max());
+    if max - min <= 0.0 {
+        return DVector::zeros(map.len());
+    }
+    let normalized = map.map(|v| (v - min) / (max - min));
+    let (mut sum, mut count) = (0.0, 0);
+    let mut global_skipped = false;
+    for (i, &v) in normalized.iter().enumerate() {
+        if !neighbours[i].iter().all(|&j| normalized[j] <= v) {
+            continue;
+        }
+        if v >= 1.0 && !global_skipped {
+            global_skipped = true;
+            continue;
+        }
+        sum += v;
+        count += 1;
+    }
+    let mean_maxima = if count > 0 { sum / count as f64 } else { 0.0 };
+    normalized * (1.0 - mean_maxima).powi(2)
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the mesh saliency of each vertex and stores it as float property `saliency` of `vertex`.
+    ///
+    /// The saliency at scale `s` is the difference between the Gaussian weighted mean curvature at `s` and `2s`.
+    /// Five scales are evaluated: `σ, 2σ, ..., 5σ`, with `σ` being the mean edge length.
+    /// Each saliency map is normalized and non-linearly suppressed before they are summed up.
+    ///
+    /// High values mark regions that deserve more detail, for example when simplifying a mesh.
+    ///
+    /// Requires the `nalgebra` feature.
+    pub fn compute_saliency(&mut self) -> Result<()> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let edges = mesh.edges();
+        let sigma = edges.iter().map(|&(a, b)| dist(&mesh.vertices[a], &mesh.vertices[b])).sum::<f64>() / edges.len() as f64;
+        if sigma.is_nan() || sigma <= 0.0 {
+            return invalid_input("Mean edge length must be positive to compute saliency.");
+        }
+        let neighbours = mesh.vertex_neighbours();
+        let curvature = DVector::from_vec(CurvatureData::new(&mesh).mean);
+        let mut saliency = DVector::zeros(mesh.vertices.len());
+        for s in 1..=5 {
+            let scale = s as f64 * sigma;
+            let fine = gaussian_weighted_average(&mesh, &neighbours, &curvature, scale);
+            let coarse = gaussian_weighted_average(&mesh, &neighbours, &curvature, 2.0 * scale);
+            saliency += suppress(&(fine - coarse).abs(), &neighbours);
+        }
+        let values = saliency.iter().map(|&v| Property::Float(v as f32)).collect();
+        let def = PropertyDef::new("saliency".to_string(), PropertyType::Scalar(ScalarType::Float));
+        self.add_property_to_element("vertex", def, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    use super::super::Property;
+    #[test]
+    fn saliency_stored() {
+        let mut ply = fixtures::icosahedron();
+        ply.compute_saliency().unwrap();
+        assert!(ply.header.elements["vertex"].properties.contains_key("saliency"));
+        for v in &ply.payload["vertex"] {
+            match v["saliency"] {
+                Property::Float(s) => assert!(s.is_finite() && s >= 0.0),
+                _ => panic!("Unexpected property."),
+            }
+        }
+    }
+    #[test]
+    fn saliency_no_faces_err() {
+        let mut ply = fixtures::from_mesh(&[[0.0; 3]], &[]);
+        assert!(ply.compute_saliency().is_err());
+    }
+}
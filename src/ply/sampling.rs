@@ -0,0 +1,102 @@
+//! Random sampling of mesh surfaces.
+
+use std::io::Result;
+use rand::{ Rng, SeedableRng };
+use rand::rngs::StdRng;
+use super::{ Ply, DefaultElement, ElementDef, PropertyDef, PropertyType, Addable };
+use super::PropertyAccess;
+use super::mesh::{ scalar_from_f64, invalid_input };
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Samples `n` points uniformly distributed over the surface of the mesh.
+    ///
+    /// A triangle is chosen with a probability proportional to its area,
+    /// followed by a uniformly distributed position within the triangle.
+    /// Polygons are split into triangle fans.
+    ///
+    /// The result contains a single `vertex` element with the same scalar properties as the source vertices,
+    /// each interpolated barycentrically from the corners of the triangle.
+    /// This covers positions, normals, colors, etc., but note that interpolated normals aren't normalized again.
+    /// List properties of `vertex` aren't transferred.
+    ///
+    /// The same `seed` always produces the same points.
+    ///
+    /// Requires the `rand` feature.
+    pub fn surface_sample(&self, n: usize, seed: u64) -> Result<Ply<DefaultElement>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        let mut cumulative = Vec::with_capacity(triangles.len());
+        let mut total = 0.0;
+        for t in &triangles {
+            total += mesh.triangle_area(t);
+            cumulative.push(total);
+        }
+        if total.is_nan() || total <= 0.0 {
+            return invalid_input("Mesh has no surface area to sample.");
+        }
+        let defs: Vec<PropertyDef> = self.header.elements["vertex"].properties.values()
+            .filter(|d| matches!(d.data_type, PropertyType::Scalar(_)))
+            .cloned()
+            .collect();
+        let values = self.read_scalar_rows("vertex", &defs)?;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut samples = Vec::with_capacity(n);
+        for _ in 0..n {
+            let target = rng.gen::<f64>() * total;
+            let ti = cumulative.partition_point(|&c| c <= target).min(triangles.len() - 1);
+            let t = &triangles[ti];
+            let (r1, r2) = (rng.gen::<f64>().sqrt(), rng.gen::<f64>());
+            let weights = [1.0 - r1, r1 * (1.0 - r2), r1 * r2];
+            let mut e = DefaultElement::new();
+            for (p, d) in defs.iter().enumerate() {
+                let v = (0..3).map(|k| weights[k] * values[t[k]][p]).sum();
+                if let PropertyType::Scalar(ref s) = d.data_type {
+                    e.insert(d.name.clone(), scalar_from_f64(s, v));
+                }
+            }
+            samples.push(e);
+        }
+
+        let mut ply = Ply::<DefaultElement>::new();
+        let mut vertex_def = ElementDef::new("vertex".to_string());
+        for d in defs {
+            vertex_def.properties.add(d);
+        }
+        vertex_def.count = samples.len();
+        ply.header.elements.add(vertex_def);
+        ply.payload.insert("vertex".to_string(), samples);
+        Ok(ply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    use super::super::mesh::norm;
+    #[test]
+    fn surface_sample_on_plane() {
+        let ply = fixtures::grid(2);
+        let samples = ply.surface_sample(200, 5).unwrap();
+        assert_eq!(samples.header.elements["vertex"].count, 200);
+        let points = samples.read_vertex_positions().unwrap();
+        assert!(points.iter().all(|p| p[2] == 0.0 && p[0] >= 0.0 && p[0] <= 2.0 && p[1] >= 0.0 && p[1] <= 2.0));
+        // each half of the grid receives roughly half of the points
+        let left = points.iter().filter(|p| p[0] < 1.0).count();
+        assert!(left > 70 && left < 130);
+        assert_eq!(samples, ply.surface_sample(200, 5).unwrap());
+    }
+    #[test]
+    fn surface_sample_inside_sphere() {
+        let ply = fixtures::icosahedron();
+        let radius = norm(&ply.read_vertex_positions().unwrap()[0]);
+        let samples = ply.surface_sample(50, 1).unwrap();
+        assert!(samples.read_vertex_positions().unwrap().iter().all(|p| norm(p) <= radius + 1e-9));
+    }
+    #[test]
+    fn surface_sample_no_area_err() {
+        let ply = fixtures::from_mesh(&[[0.0; 3]; 3], &[vec![0, 1, 2]]);
+        assert!(ply.surface_sample(10, 0).is_err());
+    }
+}
@@ -0,0 +1,358 @@
+//! Serde support for `Property` and `Ply<DefaultElement>`.
+//!
+//! Properties serialize to plain numbers or sequences of numbers, e.g. `Property::Float(0.5)` becomes `0.5`
+//! and `Property::ListUInt(vec![0, 1, 2])` becomes `[0, 1, 2]`.
+//! The exact variant is restored from the header when deserializing a whole `Ply`.
+//!
+//! Formats like JSON can't represent NaN and infinity as numbers.
+//! For human readable formats, non-finite floats are hence written as the strings `"nan"`, `"inf"` and `"-inf"`,
+//! as in ascii PLY files, and read back from these strings.
+
+use std::convert::TryFrom;
+use std::fmt;
+use serde::de::{ self, Deserialize, Deserializer, SeqAccess, Visitor };
+use serde::ser::{ Serialize, Serializer };
+use super::{ DefaultElement, Header, KeyMap, Ply, Property, PropertyType, ScalarType };
+
+fn non_finite_name(v: f64) -> Option<&'static str> {
+    if v.is_nan() {
+        Some("nan")
+    } else if v == f64::INFINITY {
+        Some("inf")
+    } else if v == f64::NEG_INFINITY {
+        Some("-inf")
+    } else {
+        None
+    }
+}
+
+/// Float that serializes as a string if it is not finite and the format is human readable.
+struct Float<T>(T);
+
+macro_rules! impl_serialize_float {
+    ($t:ty, $serialize:ident) => {
+        impl Serialize for Float<$t> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match non_finite_name(self.0 as f64) {
+                    Some(name) if serializer.is_human_readable() => serializer.serialize_str(name),
+                    _ => serializer.$serialize(self.0),
+                }
+            }
+        }
+    };
+}
+
+impl_serialize_float!(f32, serialize_f32);
+impl_serialize_float!(f64, serialize_f64);
+
+impl Serialize for Property {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Property::Char(v) => serializer.serialize_i8(v),
+            Property::UChar(v) => serializer.serialize_u8(v),
+            Property::Short(v) => serializer.serialize_i16(v),
+            Property::UShort(v) => serializer.serialize_u16(v),
+            Property::Int(v) => serializer.serialize_i32(v),
+            Property::UInt(v) => serializer.serialize_u32(v),
+            Property::Long(v) => serializer.serialize_i64(v),
+            Property::ULong(v) => serializer.serialize_u64(v),
+            Property::Float(v) => Float(v).serialize(serializer),
+            Property::Double(v) => Float(v).serialize(serializer),
+            Property::ListChar(ref v) => v.serialize(serializer),
+            Property::ListUChar(ref v) => v.serialize(serializer),
+            Property::ListShort(ref v) => v.serialize(serializer),
+            Property::ListUShort(ref v) => v.serialize(serializer),
+            Property::ListInt(ref v) => v.serialize(serializer),
+            Property::ListUInt(ref v) => v.serialize(serializer),
+            Property::ListLong(ref v) => v.serialize(serializer),
+            Property::ListULong(ref v) => v.serialize(serializer),
+            Property::ListFloat(ref v) => serializer.collect_seq(v.iter().map(|&x| Float(x))),
+            Property::ListDouble(ref v) => serializer.collect_seq(v.iter().map(|&x| Float(x))),
+        }
+    }
+}
+
+/// A number as found in the serialized data, before its PLY type is known.
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+}
+
+/// Parses the names written for non-finite floats.
+fn parse_non_finite<Er: de::Error>(v: &str) -> Result<f64, Er> {
+    match v {
+        "nan" => Ok(f64::NAN),
+        "inf" => Ok(f64::INFINITY),
+        "-inf" => Ok(f64::NEG_INFINITY),
+        _ => Err(de::Error::invalid_value(de::Unexpected::Str(v), &"a number, \"nan\", \"inf\" or \"-inf\"")),
+    }
+}
+
+/// A property as found in the serialized data, before its PLY type is known.
+#[derive(Debug, Clone)]
+enum RawProperty {
+    Scalar(Number),
+    List(Vec<Number>),
+}
+
+struct NumberVisitor;
+
+impl<'de> Visitor<'de> for NumberVisitor {
+    type Value = Number;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number")
+    }
+    fn visit_i64<Er: de::Error>(self, v: i64) -> Result<Number, Er> {
+        Ok(Number::Signed(v))
+    }
+    fn visit_u64<Er: de::Error>(self, v: u64) -> Result<Number, Er> {
+        Ok(Number::Unsigned(v))
+    }
+    fn visit_f64<Er: de::Error>(self, v: f64) -> Result<Number, Er> {
+        Ok(Number::Float(v))
+    }
+    fn visit_str<Er: de::Error>(self, v: &str) -> Result<Number, Er> {
+        parse_non_finite(v).map(Number::Float)
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
+
+struct RawPropertyVisitor;
+
+impl<'de> Visitor<'de> for RawPropertyVisitor {
+    type Value = RawProperty;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number or a sequence of numbers")
+    }
+    fn visit_i64<Er: de::Error>(self, v: i64) -> Result<RawProperty, Er> {
+        Ok(RawProperty::Scalar(Number::Signed(v)))
+    }
+    fn visit_u64<Er: de::Error>(self, v: u64) -> Result<RawProperty, Er> {
+        Ok(RawProperty::Scalar(Number::Unsigned(v)))
+    }
+    fn visit_f64<Er: de::Error>(self, v: f64) -> Result<RawProperty, Er> {
+        Ok(RawProperty::Scalar(Number::Float(v)))
+    }
+    fn visit_str<Er: de::Error>(self, v: &str) -> Result<RawProperty, Er> {
+        parse_non_finite(v).map(|v| RawProperty::Scalar(Number::Float(v)))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RawProperty, A::Error> {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(n) = seq.next_element()? {
+            list.push(n);
+        }
+        Ok(RawProperty::List(list))
+    }
+}
+
+impl<'de> Deserialize<'de> for RawProperty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RawPropertyVisitor)
+    }
+}
+
+/// Narrowest of `Int`, `UInt`, `Long`, `ULong`, and `Double` able to hold all numbers.
+fn guess_type(numbers: &[Number]) -> ScalarType {
+    let (mut min, mut max) = (0i128, 0i128);
+    for n in numbers {
+        let v = match *n {
+            Number::Signed(v) => v as i128,
+            Number::Unsigned(v) => v as i128,
+            Number::Float(_) => return ScalarType::Double,
+        };
+        min = min.min(v);
+        max = max.max(v);
+    }
+    if min >= i32::MIN as i128 && max <= i32::MAX as i128 {
+        ScalarType::Int
+    } else if min >= 0 && max <= u32::MAX as i128 {
+        ScalarType::UInt
+    } else if min >= i64::MIN as i128 && max <= i64::MAX as i128 {
+        ScalarType::Long
+    } else if min >= 0 {
+        ScalarType::ULong
+    } else {
+        ScalarType::Double
+    }
+}
+
+macro_rules! cast_integer {
+    ($n:expr, $t:ty, $name:expr) => {
+        match $n {
+            Number::Signed(v) => <$t>::try_from(v).ok(),
+            Number::Unsigned(v) => <$t>::try_from(v).ok(),
+            Number::Float(_) => None,
+        }.ok_or_else(|| format!("Number `{:?}` doesn't fit into `{}`.", $n, $name))
+    };
+}
+
+fn cast_f64(n: Number) -> f64 {
+    match n {
+        Number::Signed(v) => v as f64,
+        Number::Unsigned(v) => v as f64,
+        Number::Float(v) => v,
+    }
+}
+
+fn scalar(n: Number, t: &ScalarType) -> Result<Property, String> {
+    Ok(match *t {
+        ScalarType::Char => Property::Char(cast_integer!(n, i8, "char")?),
+        ScalarType::UChar => Property::UChar(cast_integer!(n, u8, "uchar")?),
+        ScalarType::Short => Property::Short(cast_integer!(n, i16, "short")?),
+        ScalarType::UShort => Property::UShort(cast_integer!(n, u16, "ushort")?),
+        ScalarType::Int => Property::Int(cast_integer!(n, i32, "int")?),
+        ScalarType::UInt => Property::UInt(cast_integer!(n, u32, "uint")?),
+        ScalarType::Long => Property::Long(cast_integer!(n, i64, "int64")?),
+        ScalarType::ULong => Property::ULong(cast_integer!(n, u64, "uint64")?),
+        ScalarType::Float => Property::Float(cast_f64(n) as f32),
+        ScalarType::Double => Property::Double(cast_f64(n)),
+    })
+}
+
+fn list(numbers: &[Number], t: &ScalarType) -> Result<Property, String> {
+    Ok(match *t {
+        ScalarType::Char => Property::ListChar(numbers.iter().map(|&n| cast_integer!(n, i8, "char")).collect::<Result<_, _>>()?),
+        ScalarType::UChar => Property::ListUChar(numbers.iter().map(|&n| cast_integer!(n, u8, "uchar")).collect::<Result<_, _>>()?),
+        ScalarType::Short => Property::ListShort(numbers.iter().map(|&n| cast_integer!(n, i16, "short")).collect::<Result<_, _>>()?),
+        ScalarType::UShort => Property::ListUShort(numbers.iter().map(|&n| cast_integer!(n, u16, "ushort")).collect::<Result<_, _>>()?),
+        ScalarType::Int => Property::ListInt(numbers.iter().map(|&n| cast_integer!(n, i32, "int")).collect::<Result<_, _>>()?),
+        ScalarType::UInt => Property::ListUInt(numbers.iter().map(|&n| cast_integer!(n, u32, "uint")).collect::<Result<_, _>>()?),
+        ScalarType::Long => Property::ListLong(numbers.iter().map(|&n| cast_integer!(n, i64, "int64")).collect::<Result<_, _>>()?),
+        ScalarType::ULong => Property::ListULong(numbers.iter().map(|&n| cast_integer!(n, u64, "uint64")).collect::<Result<_, _>>()?),
+        ScalarType::Float => Property::ListFloat(numbers.iter().map(|&n| cast_f64(n) as f32).collect()),
+        ScalarType::Double => Property::ListDouble(numbers.iter().map(|&n| cast_f64(n)).collect()),
+    })
+}
+
+impl RawProperty {
+    /// Converts to the variant given by the property definition.
+    fn into_property(self, property_type: &PropertyType) -> Result<Property, String> {
+        match (self, property_type) {
+            (RawProperty::Scalar(n), PropertyType::Scalar(t)) => scalar(n, t),
+            (RawProperty::List(l), PropertyType::List(_, t)) => list(&l, t),
+            (RawProperty::Scalar(_), PropertyType::List(..)) => Err("Expected a list, found a number.".to_string()),
+            (RawProperty::List(_), PropertyType::Scalar(_)) => Err("Expected a number, found a list.".to_string()),
+        }
+    }
+}
+
+/// Without a header, the variant is guessed:
+/// Integers become the first of `Int`, `UInt`, `Long`, and `ULong` that holds them, all other numbers become `Double`.
+/// Lists are handled likewise, based on the smallest and the largest element.
+impl<'de> Deserialize<'de> for Property {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawProperty::deserialize(deserializer)?;
+        let property_type = match raw {
+            RawProperty::Scalar(n) => PropertyType::Scalar(guess_type(&[n])),
+            RawProperty::List(ref l) => PropertyType::List(ScalarType::UInt, guess_type(l)),
+        };
+        raw.into_property(&property_type).map_err(de::Error::custom)
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RawPly {
+    header: Header,
+    payload: KeyMap<Vec<KeyMap<RawProperty>>>,
+}
+
+/// Property variants are taken from the header, properties missing in the header are rejected.
+impl<'de> Deserialize<'de> for Ply<DefaultElement> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawPly::deserialize(deserializer)?;
+        let mut ply = Ply::<DefaultElement>::new();
+        for (name, raw_elements) in raw.payload {
+            let element_def = raw.header.elements.get(&name)
+                .ok_or_else(|| de::Error::custom(format!("Element `{}` not found in header.", name)))?;
+            let mut elements = Vec::with_capacity(raw_elements.len());
+            for raw_element in raw_elements {
+                let mut element = DefaultElement::new();
+                for (key, raw_property) in raw_element {
+                    let property_def = element_def.properties.get(&key)
+                        .ok_or_else(|| de::Error::custom(format!("Property `{}` of element `{}` not found in header.", key, name)))?;
+                    let property = raw_property.into_property(&property_def.data_type)
+                        .map_err(|e| de::Error::custom(format!("Property `{}` of element `{}`: {}", key, name, e)))?;
+                    element.insert(key, property);
+                }
+                elements.push(element);
+            }
+            ply.payload.insert(name, elements);
+        }
+        ply.header = raw.header;
+        Ok(ply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    use super::super::{ DefaultElement, Ply, Property };
+
+    #[test]
+    fn property_plain_values() {
+        assert_eq!(serde_json::to_string(&Property::Float(0.5)).unwrap(), "0.5");
+        assert_eq!(serde_json::to_string(&Property::ListUInt(vec![0, 1, 2])).unwrap(), "[0,1,2]");
+        assert_eq!(serde_json::from_str::<Property>("-3").unwrap(), Property::Int(-3));
+        assert_eq!(serde_json::from_str::<Property>("3000000000").unwrap(), Property::UInt(3_000_000_000));
+        assert_eq!(serde_json::from_str::<Property>("[1, 2.5]").unwrap(), Property::ListDouble(vec![1.0, 2.5]));
+    }
+    #[test]
+    fn property_guess_list_range() {
+        assert_eq!(serde_json::from_str::<Property>("[]").unwrap(), Property::ListInt(vec![]));
+        assert_eq!(serde_json::from_str::<Property>("[3000000000, 1]").unwrap(), Property::ListUInt(vec![3_000_000_000, 1]));
+        assert_eq!(serde_json::from_str::<Property>("[3000000000, -1]").unwrap(), Property::ListLong(vec![3_000_000_000, -1]));
+        assert_eq!(serde_json::from_str::<Property>("[-1, 3000000000]").unwrap(), Property::ListLong(vec![-1, 3_000_000_000]));
+        assert_eq!(
+            serde_json::from_str::<Property>("[-9007199254740993, 1]").unwrap(),
+            Property::ListLong(vec![-9_007_199_254_740_993, 1])
+        );
+        assert_eq!(serde_json::from_str::<Property>("-9007199254740993").unwrap(), Property::Long(-9_007_199_254_740_993));
+        assert_eq!(
+            serde_json::from_str::<Property>("[18446744073709551615, 0]").unwrap(),
+            Property::ListULong(vec![u64::MAX, 0])
+        );
+        assert_eq!(
+            serde_json::from_str::<Property>("[18446744073709551615, -1]").unwrap(),
+            Property::ListDouble(vec![u64::MAX as f64, -1.0])
+        );
+    }
+    #[test]
+    fn ply_round_trip() {
+        let ply = fixtures::icosahedron();
+        let json = serde_json::to_string(&ply).unwrap();
+        assert!(json.starts_with("{\"header\":{\"encoding\":\"Ascii\""));
+        let parsed: Ply<DefaultElement> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, ply);
+    }
+    #[test]
+    fn non_finite_round_trip() {
+        assert_eq!(serde_json::to_string(&Property::Float(f32::NAN)).unwrap(), "\"nan\"");
+        assert_eq!(serde_json::to_string(&Property::ListDouble(vec![f64::INFINITY, 1.0, f64::NEG_INFINITY])).unwrap(), "[\"inf\",1.0,\"-inf\"]");
+        assert!(match serde_json::from_str::<Property>("\"nan\"").unwrap() { Property::Double(v) => v.is_nan(), _ => false });
+        assert!(serde_json::from_str::<Property>("\"1.5\"").is_err());
+
+        let mut ply = fixtures::icosahedron();
+        let vertex = &mut ply.payload.get_mut("vertex").unwrap()[0];
+        vertex.insert("x".to_string(), Property::Double(f64::INFINITY));
+        vertex.insert("y".to_string(), Property::Double(f64::NEG_INFINITY));
+        let json = serde_json::to_string(&ply).unwrap();
+        let parsed: Ply<DefaultElement> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, ply);
+    }
+    #[test]
+    fn ply_type_mismatch_err() {
+        let ply = fixtures::icosahedron();
+        let mut json = serde_json::to_value(&ply).unwrap();
+        json["payload"]["face"][0]["vertex_indices"] = serde_json::json!(0);
+        assert!(serde_json::from_value::<Ply<DefaultElement>>(json.clone()).is_err());
+        json["payload"]["face"][0]["vertex_indices"] = serde_json::json!([0, -1, 2]);
+        assert!(serde_json::from_value::<Ply<DefaultElement>>(json).is_err());
+    }
+}
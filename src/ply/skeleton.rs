@@ -0,0 +1,182 @@
+//! Curve skeletons by mesh contraction.
+
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Vec3, add, sub, scale, norm, cross, cot, dist, find };
+
+/// Step size of the Laplacian contraction.
+const CONTRACTION: f64 = 0.5;
+/// Weight of the attraction towards the original positions.
+const ATTRACTION: f64 = 0.05;
+/// Triangles with less than this fraction of the initial mean area are collapsed during contraction.
+const DEGENERATE_AREA: f64 = 1e-4;
+
+/// Union-find over the vertices, with the position of each representative.
+struct Contraction {
+    parent: Vec<usize>,
+    positions: Vec<Vec3>,
+}
+
+impl Contraction {
+    /// Distinct representatives of the corners of `t`, `None` if the triangle has collapsed.
+    fn corners(&mut self, t: &[usize; 3]) -> Option<[usize; 3]> {
+        let c = [find(&mut self.parent, t[0]), find(&mut self.parent, t[1]), find(&mut self.parent, t[2])];
+        if c[0] == c[1] || c[1] == c[2] || c[2] == c[0] {
+            None
+        } else {
+            Some(c)
+        }
+    }
+    fn area(&self, c: &[usize; 3]) -> f64 {
+        let p = [&self.positions[c[0]], &self.positions[c[1]], &self.positions[c[2]]];
+        norm(&cross(&sub(p[1], p[0]), &sub(p[2], p[0]))) / 2.0
+    }
+    /// Collapses the shortest edge of the triangle `c` into its midpoint.
+    fn collapse_shortest_edge(&mut self, c: &[usize; 3]) {
+        let (a, b) = (0..3).map(|k| (c[k], c[(k + 1) % 3]))
+            .min_by(|&(a, b), &(u, v)| {
+                dist(&self.positions[a], &self.positions[b]).total_cmp(&dist(&self.positions[u], &self.positions[v]))
+            })
+            .unwrap();
+        self.positions[a] = scale(&add(&self.positions[a], &self.positions[b]), 0.5);
+        self.parent[b] = a;
+    }
+    /// Moves every representative along its cotangent Laplacian and towards its original position.
+    fn smooth(&mut self, triangles: &[[usize; 3]], original: &[Vec3]) {
+        let n = self.positions.len();
+        let mut laplace = vec![[0.0; 3]; n];
+        let mut weights = vec![0.0; n];
+        for t in triangles {
+            let c = match self.corners(t) {
+                Some(c) => c,
+                None => continue,
+            };
+            let p = [self.positions[c[0]], self.positions[c[1]], self.positions[c[2]]];
+            for k in 0..3 {
+                let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+                let w = cot(&sub(&p[i], &p[k]), &sub(&p[j], &p[k])).max(0.0);
+                laplace[c[i]] = add(&laplace[c[i]], &scale(&sub(&p[j], &p[i]), w));
+                laplace[c[j]] = add(&laplace[c[j]], &scale(&sub(&p[i], &p[j]), w));
+                weights[c[i]] += w;
+                weights[c[j]] += w;
+            }
+        }
+        for v in 0..n {
+            if self.parent[v] != v || weights[v] <= 0.0 {
+                continue;
+            }
+            let step = scale(&laplace[v], CONTRACTION / weights[v]);
+            let pull = scale(&sub(&original[v], &self.positions[v]), ATTRACTION);
+            self.positions[v] = add(&self.positions[v], &add(&step, &pull));
+        }
+    }
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Extracts a curve skeleton by contracting the mesh along its mean curvature flow.
+    ///
+    /// Each of the `contraction_iters` iterations moves the vertices along the cotangent Laplacian,
+    /// which approximates the mean curvature normal, while a weak attraction term pulls them back towards
+    /// their original positions (Tagliasacchi et al., "Mean Curvature Skeletons", 2012).
+    /// Triangles that degenerate to (almost) zero area are collapsed by contracting their shortest edge.
+    /// Afterwards, the remaining triangles are collapsed shortest edge first until only edges are left.
+    ///
+    /// The skeleton is returned as adjacency list indexed by vertex:
+    /// Each surviving vertex lists its neighbours in the skeleton graph,
+    /// vertices that have been merged into another one have an empty list.
+    /// The `x`, `y`, and `z` properties of all vertices are replaced by the contracted positions,
+    /// merged vertices take the position of the vertex they have been merged into.
+    /// The faces are not modified.
+    ///
+    /// Polygons are split into triangle fans.
+    pub fn compute_mean_curvature_skeleton(&mut self, contraction_iters: u32) -> Result<Vec<Vec<usize>>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        let n = mesh.vertices.len();
+        let mut contraction = Contraction {
+            parent: (0..n).collect(),
+            positions: mesh.vertices.clone(),
+        };
+        let mean_area = triangles.iter().map(|t| mesh.triangle_area(t)).sum::<f64>() / triangles.len().max(1) as f64;
+        for _ in 0..contraction_iters {
+            contraction.smooth(&triangles, &mesh.vertices);
+            for t in &triangles {
+                if let Some(c) = contraction.corners(t) {
+                    if contraction.area(&c) < DEGENERATE_AREA * mean_area {
+                        contraction.collapse_shortest_edge(&c);
+                    }
+                }
+            }
+        }
+        // connectivity surgery: remove all remaining triangles
+        loop {
+            let mut shortest: Option<([usize; 3], f64)> = None;
+            for t in &triangles {
+                if let Some(c) = contraction.corners(t) {
+                    let p = &contraction.positions;
+                    let l = (0..3).map(|k| dist(&p[c[k]], &p[c[(k + 1) % 3]])).fold(f64::INFINITY, f64::min);
+                    match shortest {
+                        Some((_, s)) if s <= l => (),
+                        _ => shortest = Some((c, l)),
+                    }
+                }
+            }
+            match shortest {
+                Some((c, _)) => contraction.collapse_shortest_edge(&c),
+                None => break,
+            }
+        }
+
+        let mut adjacency = vec![Vec::new(); n];
+        for (a, b) in mesh.edges() {
+            let (ra, rb) = (find(&mut contraction.parent, a), find(&mut contraction.parent, b));
+            if ra != rb {
+                adjacency[ra].push(rb);
+                adjacency[rb].push(ra);
+            }
+        }
+        for list in adjacency.iter_mut() {
+            list.sort_unstable();
+            list.dedup();
+        }
+        let positions: Vec<Vec3> = (0..n).map(|v| contraction.positions[find(&mut contraction.parent, v)]).collect();
+        self.write_vertex_positions(&positions)?;
+        Ok(adjacency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use super::super::{ Ply, DefaultElement };
+    #[test]
+    fn skeleton_cylinder() {
+        let mut ply = Ply::<DefaultElement>::cylinder(1.0, 8.0, 12, false);
+        let skeleton = ply.compute_mean_curvature_skeleton(20).unwrap();
+        assert_eq!(skeleton.len(), 24);
+        let nodes: Vec<usize> = (0..24).filter(|&v| !skeleton[v].is_empty()).collect();
+        assert!(nodes.len() < 24);
+        for &v in &nodes {
+            for &u in &skeleton[v] {
+                assert!(skeleton[u].contains(&v));
+            }
+        }
+        // the skeleton is connected
+        let mut seen = HashSet::new();
+        let mut stack = vec![nodes[0]];
+        while let Some(v) = stack.pop() {
+            if seen.insert(v) {
+                stack.extend(skeleton[v].iter().cloned());
+            }
+        }
+        assert_eq!(seen.len(), nodes.len());
+        // the contracted vertices moved towards the axis
+        let positions = ply.read_vertex_positions().unwrap();
+        for &v in &nodes {
+            let p = positions[v];
+            assert!((p[0] * p[0] + p[1] * p[1]).sqrt() < 0.9);
+        }
+    }
+}
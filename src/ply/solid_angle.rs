@@ -0,0 +1,132 @@
+//! Solid angles at the corners of a triangle mesh and winding numbers.
+
+use std::f64::consts::PI;
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Vec3, scale, dot, cross, norm, normalize, sub };
+
+/// Signed solid angle of the triangle `(a, b, c)` seen from `q` (van Oosterom and Strackee).
+fn signed_solid_angle(q: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3) -> f64 {
+    let (a, b, c) = (sub(a, q), sub(b, q), sub(c, q));
+    let (la, lb, lc) = (norm(&a), norm(&b), norm(&c));
+    let det = dot(&a, &cross(&b, &c));
+    let denominator = la * lb * lc + dot(&a, &b) * lc + dot(&b, &c) * la + dot(&c, &a) * lb;
+    2.0 * det.atan2(denominator)
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the solid angle enclosed by the incident faces at each vertex, as a fraction of the full sphere.
+    ///
+    /// Each triangle `(v, a, b)` contributes the spherical triangle spanned by the directions to `a`, `b`,
+    /// and the inward vertex normal, evaluated with the formula of
+    /// van Oosterom and Strackee, "The Solid Angle of a Plane Triangle" (1983).
+    /// The sum is normalized by `4π`: an interior vertex of a flat region has a value of `0.5`,
+    /// convex corners have smaller, concave corners larger values.
+    /// Boundary vertices only cover part of their surroundings and may have values less than `0.5` even if flat.
+    ///
+    /// Faces must be oriented counter-clockwise when seen from outside, polygons are split into triangle fans.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ply_rs::ply::{ Ply, DefaultElement, PropertyDef, PropertyType, ScalarType, Property };
+    /// # let mut ply = Ply::<DefaultElement>::new();
+    /// let angles = ply.compute_solid_angles().unwrap();
+    /// let values = angles.iter().map(|&a| Property::Float(a as f32)).collect();
+    /// let def = PropertyDef::new("solid_angle".to_string(), PropertyType::Scalar(ScalarType::Float));
+    /// ply.add_property_to_element("vertex", def, values).unwrap();
+    /// ```
+    pub fn compute_solid_angles(&self) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        let normals = mesh.vertex_normals(&triangles);
+        let mut solid_angles = vec![0.0; mesh.vertices.len()];
+        for t in &triangles {
+            for k in 0..3 {
+                let v = t[k];
+                let p = &mesh.vertices[v];
+                let a = normalize(&sub(&mesh.vertices[t[(k + 1) % 3]], p));
+                let b = normalize(&sub(&mesh.vertices[t[(k + 2) % 3]], p));
+                let d = scale(&normals[v], -1.0);
+                let det = -dot(&d, &cross(&a, &b));
+                let denominator = 1.0 + dot(&d, &a) + dot(&a, &b) + dot(&b, &d);
+                let omega = 2.0 * det.atan2(denominator);
+                if omega.is_finite() {
+                    solid_angles[v] += omega;
+                }
+            }
+        }
+        Ok(solid_angles.iter().map(|&o| o / (4.0 * PI)).collect())
+    }
+    /// Computes the generalized winding number of the mesh at each point of `queries`.
+    ///
+    /// The winding number is the sum of the signed solid angles of all triangles seen from the query point,
+    /// divided by `4π` (Jacobson et al., "Robust Inside-Outside Segmentation using Generalized Winding Numbers", 2013).
+    /// For a closed mesh with counter-clockwise faces, it is `1` inside and `0` outside.
+    /// For open meshes or meshes with holes, the value varies smoothly in between,
+    /// a threshold of `0.5` is a robust inside/outside test.
+    ///
+    /// Polygons are split into triangle fans.
+    ///
+    /// # Remarks
+    ///
+    /// Every query is evaluated against every triangle, the complexity is `O(Q * F)`.
+    /// For large meshes and many queries, a hierarchical approximation would be required.
+    pub fn compute_winding_numbers(&self, queries: &[[f64; 3]]) -> Result<Vec<f64>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let triangles = mesh.triangles();
+        Ok(queries.iter().map(|q| {
+            let total: f64 = triangles.iter().map(|t| {
+                signed_solid_angle(q, &mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]])
+            }).sum();
+            total / (4.0 * PI)
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn solid_angles_flat_grid() {
+        let ply = fixtures::grid(2);
+        let s = ply.compute_solid_angles().unwrap();
+        assert!((s[4] - 0.5).abs() < 1e-9);
+        assert!((s[1] - 0.25).abs() < 1e-9);
+        assert!((s[0] - 0.125).abs() < 1e-9);
+    }
+    #[test]
+    fn solid_angles_cube_corner() {
+        // the three faces around a corner of a cube
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let ply = fixtures::from_mesh(&vertices, &[vec![0, 2, 1], vec![0, 3, 2], vec![0, 1, 3]]);
+        let s = ply.compute_solid_angles().unwrap();
+        assert!((s[0] - 0.125).abs() < 1e-9);
+    }
+    #[test]
+    fn winding_numbers_closed() {
+        let ply = fixtures::icosahedron();
+        let w = ply.compute_winding_numbers(&[[0.0, 0.0, 0.0], [0.1, -0.2, 0.3], [5.0, 0.0, 0.0]]).unwrap();
+        assert!((w[0] - 1.0).abs() < 1e-9);
+        assert!((w[1] - 1.0).abs() < 1e-9);
+        assert!(w[2].abs() < 1e-9);
+    }
+    #[test]
+    fn winding_numbers_open() {
+        // an icosahedron without one face still separates inside and outside
+        let mut ply = fixtures::icosahedron();
+        ply.payload.get_mut("face").unwrap().pop();
+        let w = ply.compute_winding_numbers(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0]]).unwrap();
+        assert!(w[0] > 0.5 && w[0] < 1.0);
+        assert!(w[1].abs() < 0.5);
+    }
+    #[test]
+    fn solid_angles_icosahedron_convex() {
+        let ply = fixtures::icosahedron();
+        let s = ply.compute_solid_angles().unwrap();
+        assert!(s.iter().all(|&v| v > 0.0 && v < 0.5 && (v - s[0]).abs() < 1e-9));
+    }
+}
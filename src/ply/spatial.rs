@@ -0,0 +1,96 @@
+//! Nearest neighbour queries backed by a k-d tree.
+
+use std::io::Result;
+use kdtree::KdTree;
+use kdtree::distance::squared_euclidean;
+use super::{ Ply, DefaultElement };
+use super::PropertyAccess;
+use super::mesh::{ Vec3, invalid_input };
+
+/// k-d tree over points, storing the index of each point.
+pub(crate) struct PointTree {
+    tree: KdTree<f64, usize, Vec3>,
+}
+
+impl PointTree {
+    pub fn new(points: &[Vec3]) -> Result<Self> {
+        let mut tree = KdTree::with_capacity(3, points.len());
+        for (i, p) in points.iter().enumerate() {
+            if let Err(e) = tree.add(*p, i) {
+                return invalid_input(&format!("Can't insert vertex {} into k-d tree: {}", i, e));
+            }
+        }
+        Ok(PointTree { tree })
+    }
+    /// Returns the index of and the distance to the point closest to `point`.
+    pub fn nearest(&self, point: &Vec3) -> Option<(usize, f64)> {
+        self.nearest_n(point, 1).first().cloned()
+    }
+    /// Returns indices and distances of the `n` points closest to `point`, sorted by distance.
+    pub fn nearest_n(&self, point: &Vec3, n: usize) -> Vec<(usize, f64)> {
+        match self.tree.nearest(point, n, &squared_euclidean) {
+            Ok(found) => found.iter().map(|&(d, &i)| (i, d.sqrt())).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Largest distance from a point in `from` to its nearest neighbour in `to`.
+fn directed_hausdorff(from: &[Vec3], to: &PointTree) -> Result<f64> {
+    let mut max = 0.0f64;
+    for (i, p) in from.iter().enumerate() {
+        match to.nearest(p) {
+            Some((_, d)) => max = max.max(d),
+            None => return invalid_input(&format!("No nearest neighbour found for vertex {}.", i)),
+        }
+    }
+    Ok(max)
+}
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes the symmetric Hausdorff distance between the vertices of `self` and `other`.
+    ///
+    /// The distance is `max(max_a min_b d(a, b), max_b min_a d(a, b))`,
+    /// where `a` and `b` run over the vertices of `self` and `other` respectively.
+    /// Nearest neighbours are looked up in k-d trees.
+    ///
+    /// # Remarks
+    ///
+    /// Only vertex-to-vertex distances are considered,
+    /// the result is therefore an approximation of the true Hausdorff distance between the surfaces,
+    /// which may be larger or smaller. It converges with increasing sampling density.
+    ///
+    /// Requires the `kdtree` feature.
+    pub fn hausdorff_distance(&self, other: &Ply<DefaultElement>) -> Result<f64> {
+        let a = self.read_vertex_positions()?;
+        let b = other.read_vertex_positions()?;
+        if a.is_empty() || b.is_empty() {
+            return invalid_input("Hausdorff distance requires vertices in both meshes.");
+        }
+        let a_to_b = directed_hausdorff(&a, &PointTree::new(&b)?)?;
+        let b_to_a = directed_hausdorff(&b, &PointTree::new(&a)?)?;
+        Ok(a_to_b.max(b_to_a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn hausdorff_identical_zero() {
+        let ply = fixtures::icosahedron();
+        assert_eq!(ply.hausdorff_distance(&fixtures::icosahedron()).unwrap(), 0.0);
+    }
+    #[test]
+    fn hausdorff_symmetric() {
+        let a = fixtures::from_mesh(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]], &[]);
+        let b = fixtures::from_mesh(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [4.0, 0.0, 0.0]], &[]);
+        assert_eq!(a.hausdorff_distance(&b).unwrap(), 3.0);
+        assert_eq!(b.hausdorff_distance(&a).unwrap(), 3.0);
+    }
+    #[test]
+    fn hausdorff_empty_err() {
+        let a = fixtures::from_mesh(&[], &[]);
+        assert!(a.hausdorff_distance(&fixtures::icosahedron()).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Spectral analysis of the mesh connectivity.
+
+use std::io::Result;
+use nalgebra::{ DMatrix, SymmetricEigen };
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ find, invalid_input };
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Computes `k` eigenvectors of the normalized graph Laplacian of the mesh.
+    ///
+    /// The Laplacian `L = I - D^(-1/2) A D^(-1/2)` is built from the edges of the `face` element.
+    /// The eigenvectors belonging to the `k` smallest eigenvalues are returned,
+    /// skipping the trivial eigenvectors of eigenvalue `0`.
+    /// There is one trivial eigenvector per connected component, which only indicates the component,
+    /// hence `k` must be smaller than the number of vertices minus the number of components.
+    /// Each eigenvector contains one value per vertex and can be used as intrinsic coordinate.
+    ///
+    /// The dense eigendecomposition needs `O(n³)` time and `O(n²)` memory in the number of vertices,
+    /// it is therefore only suited for small to medium sized meshes.
+    ///
+    /// Requires the `nalgebra` feature.
+    pub fn compute_spectral_embedding(&self, k: usize) -> Result<Vec<Vec<f64>>> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        let n = mesh.vertices.len();
+        let neighbours = mesh.vertex_neighbours();
+        let mut parent: Vec<usize> = (0..n).collect();
+        for (i, ns) in neighbours.iter().enumerate() {
+            for &j in ns {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                parent[ri] = rj;
+            }
+        }
+        // isolated vertices have eigenvalue 1, only components with edges have a trivial eigenvector
+        let components = (0..n).filter(|&v| find(&mut parent, v) == v && !neighbours[v].is_empty()).count();
+        if k + components > n {
+            return invalid_input(&format!("Can't compute {} non-trivial eigenvectors for {} vertices in {} components.", k, n, components));
+        }
+        let inv_sqrt_degree: Vec<f64> = neighbours.iter().map(|v| {
+            if v.is_empty() { 0.0 } else { 1.0 / (v.len() as f64).sqrt() }
+        }).collect();
+        let mut laplacian = DMatrix::<f64>::identity(n, n);
+        for (i, ns) in neighbours.iter().enumerate() {
+            for &j in ns {
+                laplacian[(i, j)] = -inv_sqrt_degree[i] * inv_sqrt_degree[j];
+            }
+        }
+        let eigen = SymmetricEigen::new(laplacian);
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[a].total_cmp(&eigen.eigenvalues[b]));
+        Ok(order.iter().skip(components).take(k).map(|&c| eigen.eigenvectors.column(c).iter().cloned().collect()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn spectral_embedding_dimensions() {
+        let ply = fixtures::grid(3);
+        let e = ply.compute_spectral_embedding(3).unwrap();
+        assert_eq!(e.len(), 3);
+        for v in &e {
+            assert_eq!(v.len(), 16);
+            let norm: f64 = v.iter().map(|x| x * x).sum();
+            assert!((norm - 1.0).abs() < 1e-9);
+        }
+        let d: f64 = e[0].iter().zip(e[1].iter()).map(|(a, b)| a * b).sum();
+        assert!(d.abs() < 1e-9);
+    }
+    #[test]
+    fn spectral_embedding_too_many_err() {
+        let ply = fixtures::icosahedron();
+        assert!(ply.compute_spectral_embedding(11).is_ok());
+        assert!(ply.compute_spectral_embedding(12).is_err());
+    }
+    #[test]
+    fn spectral_embedding_two_components() {
+        // two disjoint tetrahedra, every vertex has degree 3
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        for &offset in &[0.0, 10.0] {
+            let first = vertices.len();
+            vertices.extend_from_slice(&[[offset, 0.0, 0.0], [offset + 1.0, 0.0, 0.0], [offset, 1.0, 0.0], [offset, 0.0, 1.0]]);
+            for f in &[[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]] {
+                faces.push(f.iter().map(|&i| first + i).collect());
+            }
+        }
+        let ply = fixtures::from_mesh(&vertices, &faces);
+        let e = ply.compute_spectral_embedding(6).unwrap();
+        assert_eq!(e.len(), 6);
+        // the trivial eigenvectors are constant per component, all others are orthogonal to them
+        for v in &e {
+            assert!(v[..4].iter().sum::<f64>().abs() < 1e-9);
+            assert!(v[4..].iter().sum::<f64>().abs() < 1e-9);
+        }
+        assert!(ply.compute_spectral_embedding(7).is_err());
+    }
+}
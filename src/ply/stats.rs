@@ -0,0 +1,49 @@
+//! Quick summary of the structure of a PLY file.
+
+use std::collections::HashMap;
+use super::{ Ply, PropertyAccess };
+
+/// Element counts and property names of a `Ply`, taken from its header.
+///
+/// # Examples
+///
+/// ```rust
+/// # use ply_rs::ply::{ Ply, DefaultElement, ElementDef, PlyStats };
+/// let mut ply = Ply::<DefaultElement>::new();
+/// ply.header.elements.insert("vertex".to_string(), ElementDef::new("vertex".to_string()));
+/// let stats = PlyStats::from(&ply);
+/// assert_eq!(stats.element_counts["vertex"], 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlyStats {
+    /// Number of elements per element name, as declared in the header.
+    pub element_counts: HashMap<String, usize>,
+    /// Property names per element name, in declaration order.
+    pub property_names: HashMap<String, Vec<String>>,
+}
+
+/// The header should be consistent, see `Ply::make_consistent()`, otherwise counts may be outdated.
+impl<'a, E: PropertyAccess> From<&'a Ply<E>> for PlyStats {
+    fn from(ply: &'a Ply<E>) -> Self {
+        let elements = &ply.header.elements;
+        PlyStats {
+            element_counts: elements.iter().map(|(k, e)| (k.clone(), e.count)).collect(),
+            property_names: elements.iter().map(|(k, e)| (k.clone(), e.properties.keys().cloned().collect())).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    use super::PlyStats;
+
+    #[test]
+    fn stats_icosahedron() {
+        let stats = PlyStats::from(&fixtures::icosahedron());
+        assert_eq!(stats.element_counts["vertex"], 12);
+        assert_eq!(stats.element_counts["face"], 20);
+        assert_eq!(stats.property_names["vertex"], vec!["x", "y", "z"]);
+        assert_eq!(stats.property_names["face"], vec!["vertex_indices"]);
+    }
+}
@@ -0,0 +1,200 @@
+//! Subdivision surfaces.
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::{ PropertyDef, PropertyType };
+use super::mesh::{ Mesh, scalar_from_f64, invalid_input };
+
+/// Weighted sum of the rows `indices` of `values`.
+fn combine(values: &[Vec<f64>], indices: &[usize], weights: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; values[indices[0]].len()];
+    for (&i, &w) in indices.iter().zip(weights) {
+        for (r, v) in result.iter_mut().zip(values[i].iter()) {
+            *r += w * v;
+        }
+    }
+    result
+}
+
+/// Applies one step of Loop subdivision to the vertex attributes `values` and the `triangles`.
+///
+/// `parents` maps each triangle to its original face and is updated alongside.
+fn subdivide(values: &mut Vec<Vec<f64>>, triangles: &mut Vec<[usize; 3]>, parents: &mut Vec<usize>) -> Result<()> {
+    let n = values.len();
+    let edge_triangles = Mesh::edge_triangles(triangles);
+    let mut edges: Vec<_> = edge_triangles.keys().cloned().collect();
+    edges.sort_unstable();
+    let mut neighbours = vec![Vec::new(); n];
+    let mut boundary_neighbours = vec![Vec::new(); n];
+    for &(a, b) in &edges {
+        let faces = &edge_triangles[&(a, b)];
+        if faces.len() > 2 {
+            return invalid_input(&format!("Edge ({}, {}) is shared by more than two faces.", a, b));
+        }
+        neighbours[a].push(b);
+        neighbours[b].push(a);
+        if faces.len() == 1 {
+            boundary_neighbours[a].push(b);
+            boundary_neighbours[b].push(a);
+        }
+    }
+
+    let mut new_values = Vec::with_capacity(n + edges.len());
+    for v in 0..n {
+        let ring = &neighbours[v];
+        let boundary = &boundary_neighbours[v];
+        new_values.push(if boundary.len() == 2 {
+            combine(values, &[v, boundary[0], boundary[1]], &[0.75, 0.125, 0.125])
+        } else if boundary.is_empty() && !ring.is_empty() {
+            let k = ring.len() as f64;
+            let beta = (0.625 - (0.375 + 0.25 * (2.0 * PI / k).cos()).powi(2)) / k;
+            let mut indices = vec![v];
+            indices.extend_from_slice(ring);
+            let mut weights = vec![1.0 - k * beta];
+            weights.extend(ring.iter().map(|_| beta));
+            combine(values, &indices, &weights)
+        } else {
+            // isolated vertices and boundary vertices touching several boundaries stay in place
+            values[v].clone()
+        });
+    }
+    let mut edge_vertex = HashMap::with_capacity(edges.len());
+    for (i, &(a, b)) in edges.iter().enumerate() {
+        let faces = &edge_triangles[&(a, b)];
+        let opposite = |t: usize| *triangles[t].iter().find(|&&v| v != a && v != b).unwrap();
+        new_values.push(if faces.len() == 2 {
+            combine(values, &[a, b, opposite(faces[0]), opposite(faces[1])], &[0.375, 0.375, 0.125, 0.125])
+        } else {
+            combine(values, &[a, b], &[0.5, 0.5])
+        });
+        edge_vertex.insert((a, b), n + i);
+    }
+
+    let mut new_triangles = Vec::with_capacity(4 * triangles.len());
+    let mut new_parents = Vec::with_capacity(4 * triangles.len());
+    for (t, &parent) in triangles.iter().zip(parents.iter()) {
+        let mid = |a: usize, b: usize| edge_vertex[&(a.min(b), a.max(b))];
+        let (ab, bc, ca) = (mid(t[0], t[1]), mid(t[1], t[2]), mid(t[2], t[0]));
+        new_triangles.extend_from_slice(&[[t[0], ab, ca], [ab, t[1], bc], [ca, bc, t[2]], [ab, bc, ca]]);
+        new_parents.extend_from_slice(&[parent; 4]);
+    }
+    *values = new_values;
+    *triangles = new_triangles;
+    *parents = new_parents;
+    Ok(())
+}
+
+impl<E: PropertyAccess + Clone> Ply<E> {
+    /// Refines a triangle mesh `iterations` times with Loop subdivision.
+    ///
+    /// Each step inserts a vertex on every edge and splits every triangle into four.
+    /// Vertices are repositioned with the stencils of Loop, "Smooth Subdivision Surfaces Based on Triangles" (1987),
+    /// boundaries are treated as cubic B-splines.
+    ///
+    /// All scalar properties of `vertex` are interpolated with the same weights as the positions,
+    /// integer properties are rounded.
+    /// New faces inherit all properties of the face they originate from.
+    /// The `vertex` and `face` elements are replaced and their counts in the header adjusted.
+    ///
+    /// The mesh must consist of triangles only and every edge must be shared by at most two faces,
+    /// list properties on `vertex` aren't supported.
+    pub fn subdivide_loop(&mut self, iterations: u32) -> Result<()> {
+        let mesh = self.read_mesh()?;
+        mesh.require_faces()?;
+        if let Some(f) = mesh.faces.iter().position(|f| f.len() != 3) {
+            return invalid_input(&format!("Face {} isn't a triangle, Loop subdivision requires a triangle mesh.", f));
+        }
+        if iterations == 0 {
+            return Ok(());
+        }
+        let vertex_defs: Vec<PropertyDef> = self.header.elements["vertex"].properties.values().cloned().collect();
+        if let Some(d) = vertex_defs.iter().find(|d| matches!(d.data_type, PropertyType::List(_, _))) {
+            return invalid_input(&format!("List property `{}` of `vertex` can't be interpolated.", d.name));
+        }
+        let mut values = self.read_scalar_rows("vertex", &vertex_defs)?;
+        let mut triangles = mesh.triangles();
+        let mut parents: Vec<usize> = (0..triangles.len()).collect();
+        for _ in 0..iterations {
+            subdivide(&mut values, &mut triangles, &mut parents)?;
+        }
+
+        let vertices: Vec<E> = values.iter().map(|row| {
+            let mut e = E::new();
+            for (d, &v) in vertex_defs.iter().zip(row.iter()) {
+                if let PropertyType::Scalar(ref t) = d.data_type {
+                    e.set_property(d.name.clone(), scalar_from_f64(t, v));
+                }
+            }
+            e
+        }).collect();
+        // convert the indices before modifying the payload, so errors leave `self` unchanged
+        let indices: Vec<Vec<usize>> = triangles.iter().map(|t| t.to_vec()).collect();
+        let (index_name, index_properties) = self.face_index_properties(&indices)?;
+        let old_faces = &self.payload["face"];
+        let faces: Vec<E> = parents.iter().zip(index_properties).map(|(&p, index_property)| {
+            let mut f = old_faces[p].clone();
+            f.set_property(index_name.clone(), index_property);
+            f
+        }).collect();
+        self.header.elements.get_mut("vertex").unwrap().count = vertices.len();
+        self.header.elements.get_mut("face").unwrap().count = faces.len();
+        self.payload.insert("vertex".to_string(), vertices);
+        self.payload.insert("face".to_string(), faces);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::{ fixtures, norm };
+    #[test]
+    fn subdivide_loop_counts() {
+        let mut ply = fixtures::icosahedron();
+        ply.subdivide_loop(2).unwrap();
+        // V' = V + E, F' = 4 F
+        assert_eq!(ply.header.elements["face"].count, 320);
+        assert_eq!(ply.header.elements["vertex"].count, 162);
+        assert_eq!(ply.payload["vertex"].len(), 162);
+        let mesh = ply.read_mesh().unwrap();
+        assert_eq!(mesh.edges().len(), 480);
+    }
+    #[test]
+    fn subdivide_loop_shrinks_towards_sphere() {
+        let mut ply = fixtures::icosahedron();
+        let radius = norm(&ply.read_vertex_positions().unwrap()[0]);
+        ply.subdivide_loop(3).unwrap();
+        let radii: Vec<f64> = ply.read_vertex_positions().unwrap().iter().map(norm).collect();
+        let (min, max) = radii.iter().fold((f64::INFINITY, 0.0f64), |(a, b), &r| (a.min(r), b.max(r)));
+        assert!(max < radius);
+        assert!((max - min) / max < 0.05);
+    }
+    #[test]
+    fn subdivide_loop_flat_boundary() {
+        let mut ply = fixtures::grid(2);
+        let before = ply.read_vertex_positions().unwrap();
+        ply.subdivide_loop(1).unwrap();
+        let positions = ply.read_vertex_positions().unwrap();
+        assert!(positions.iter().all(|p| p[2] == 0.0));
+        // vertices on a straight boundary and in the flat interior stay in place
+        assert_eq!(positions[1], before[1]);
+        assert_eq!(positions[4], before[4]);
+        assert_eq!(ply.payload["face"].len(), 32);
+    }
+    #[test]
+    fn subdivide_loop_err_unchanged() {
+        // 642 vertices after three iterations don't fit into `uchar` indices
+        let mut ply = fixtures::with_uchar_indices(fixtures::icosahedron());
+        let before = ply.clone();
+        assert!(ply.subdivide_loop(3).is_err());
+        assert_eq!(ply, before);
+        assert!(ply.subdivide_loop(2).is_ok());
+    }
+    #[test]
+    fn subdivide_loop_polygon_err() {
+        let mut ply = fixtures::from_mesh(&[[0.0; 3]; 4], &[vec![0, 1, 2, 3]]);
+        assert!(ply.subdivide_loop(1).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! Visibility of faces from a viewpoint.
+
+use std::io::Result;
+use super::Ply;
+use super::PropertyAccess;
+use super::mesh::{ Vec3, add, sub, scale, dot, intersect_ray_triangle };
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Determines for each face whether it is visible from `viewpoint`.
+    ///
+    /// A face is visible if
+    ///
+    /// 1. it is front-facing, i.e. its normal points towards `viewpoint`, and
+    /// 2. the line of sight from `viewpoint` to the centroid of the face doesn't hit any other face first.
+    ///
+    /// Faces must be oriented counter-clockwise when seen from outside, polygons are split into triangle fans.
+    /// Returns one entry per face, in the order of the `face` elements.
+    ///
+    /// # Remarks
+    ///
+    /// Every line of sight is tested against every triangle, the complexity is `O(F²)`.
+    /// There is no spatial acceleration structure yet. For large meshes, consider enabling the `kdtree` feature
+    /// and building a spatial index over the triangles to only test candidates near each line of sight,
+    /// or cull the mesh beforehand.
+    pub fn compute_visibility(&self, viewpoint: [f64; 3]) -> Result<Vec<bool>> {
+        let mesh = self.read_mesh()?;
+        let mut triangles = Vec::<(usize, [usize; 3])>::new();
+        for (fi, f) in mesh.faces.iter().enumerate() {
+            for i in 1..f.len().saturating_sub(1) {
+                triangles.push((fi, [f[0], f[i], f[i + 1]]));
+            }
+        }
+        let mut visible = Vec::with_capacity(mesh.faces.len());
+        for (fi, f) in mesh.faces.iter().enumerate() {
+            if f.is_empty() {
+                visible.push(false);
+                continue;
+            }
+            let centroid: Vec3 = scale(&f.iter().fold([0.0; 3], |s, &v| add(&s, &mesh.vertices[v])), 1.0 / f.len() as f64);
+            let sight = sub(&centroid, &viewpoint);
+            if dot(&mesh.face_normal(f), &sight) >= 0.0 {
+                visible.push(false);
+                continue;
+            }
+            // the ray reaches the centroid at t = 1
+            let occluded = triangles.iter().filter(|&&(other, _)| other != fi).any(|(_, t)| {
+                let (a, b, c) = (&mesh.vertices[t[0]], &mesh.vertices[t[1]], &mesh.vertices[t[2]]);
+                matches!(intersect_ray_triangle(&viewpoint, &sight, a, b, c), Some(t) if t < 1.0 - 1e-9)
+            });
+            visible.push(!occluded);
+        }
+        Ok(visible)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mesh::fixtures;
+    #[test]
+    fn visibility_convex_front_faces() {
+        let ply = fixtures::icosahedron();
+        let visible = ply.compute_visibility([0.0, 0.0, 10.0]).unwrap();
+        assert_eq!(visible.len(), 20);
+        let count = visible.iter().filter(|&&v| v).count();
+        assert!(count > 0 && count < 20);
+    }
+    #[test]
+    fn visibility_occluded() {
+        // two parallel triangles facing +z, the upper one hides the lower one
+        let vertices = [
+            [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0],
+            [-1.0, -1.0, 1.0], [3.0, -1.0, 1.0], [-1.0, 3.0, 1.0],
+        ];
+        let ply = fixtures::from_mesh(&vertices, &[vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!(ply.compute_visibility([0.2, 0.2, 5.0]).unwrap(), vec![false, true]);
+        assert_eq!(ply.compute_visibility([0.2, 0.2, -5.0]).unwrap(), vec![false, false]);
+        assert_eq!(ply.compute_visibility([0.2, 0.2, 0.5]).unwrap(), vec![true, false]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Zstandard compressed PLY files.
+//!
+//! The PLY file is stored as a single Zstandard frame, without any additional envelope.
+//! Such files can be inflated with the `zstd` command line tool.
+
+use std::io::{ Read, Write, Result };
+use super::Ply;
+use super::PropertyAccess;
+use super::DefaultElement;
+use crate::parser::Parser;
+use crate::writer::Writer;
+
+impl<E: PropertyAccess> Ply<E> {
+    /// Writes the PLY file with `write_ply()` and compresses it with Zstandard at `level`.
+    ///
+    /// `level` ranges from `1` (fastest) to `22` (smallest), `0` selects the default level.
+    /// Returns the number of compressed bytes written to `out`.
+    ///
+    /// Requires the `zstd` feature.
+    pub fn write_zstd<W: Write>(&mut self, out: &mut W, level: i32) -> Result<usize> {
+        let mut raw = Vec::new();
+        Writer::new().write_ply(&mut raw, self)?;
+        let compressed = zstd::stream::encode_all(raw.as_slice(), level)?;
+        out.write_all(&compressed)?;
+        Ok(compressed.len())
+    }
+}
+
+impl Ply<DefaultElement> {
+    /// Reads a Zstandard compressed PLY file, e.g. created by `write_zstd()`.
+    ///
+    /// Requires the `zstd` feature.
+    pub fn read_zstd<R: Read>(reader: &mut R) -> Result<Ply<DefaultElement>> {
+        let mut decoder = zstd::stream::read::Decoder::new(reader)?;
+        Ok(Parser::<DefaultElement>::new().read_ply(&mut decoder)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::super::mesh::fixtures;
+    use crate::writer::Writer;
+    #[test]
+    fn zstd_round_trip() {
+        let mut ply = fixtures::icosahedron();
+        ply.header.encoding = Encoding::BinaryLittleEndian;
+        let mut raw = Vec::new();
+        Writer::new().write_ply(&mut raw, &mut ply).unwrap();
+        let mut compressed = Vec::new();
+        let written = ply.write_zstd(&mut compressed, 3).unwrap();
+        assert_eq!(written, compressed.len());
+        assert_eq!(zstd::stream::decode_all(compressed.as_slice()).unwrap(), raw);
+        let read = Ply::<DefaultElement>::read_zstd(&mut compressed.as_slice()).unwrap();
+        assert_eq!(read, ply);
+    }
+    #[test]
+    fn zstd_invalid_err() {
+        let mut data: &[u8] = b"ply\nformat ascii 1.0\nend_header\n";
+        assert!(Ply::<DefaultElement>::read_zstd(&mut data).is_err());
+    }
+}
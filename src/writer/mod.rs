@@ -39,19 +39,68 @@ use crate::ply::PropertyAccess;
 pub struct Writer<E: PropertyAccess> {
     /// Should be fairly efficient, se `as_bytes()` in https://doc.rust-lang.org/src/collections/string.rs.html#1001
     new_line: String,
+    float_format: FloatFormat,
     phantom: PhantomData<E>,
 }
 
+// Not derived, since the derived implementation would require `E: Clone`.
+impl<E: PropertyAccess> Clone for Writer<E> {
+    fn clone(&self) -> Self {
+        Writer {
+            new_line: self.new_line.clone(),
+            float_format: self.float_format,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Line ending used for the header and ascii payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NewLine {
+    /// `\n`, Unix and modern macOS.
+    N,
+    /// `\r`, Mac OS up to version 9.
+    R,
+    /// `\r\n`, Windows.
+    RN,
+}
+
+impl NewLine {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            NewLine::N => "\n",
+            NewLine::R => "\r",
+            NewLine::RN => "\r\n",
+        }
+    }
+}
+
+/// Representation of `float` and `double` values in ascii payloads.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FloatFormat {
+    /// Shortest decimal representation that reads back to the same value, e.g. `17.3` or `0.000001`.
+    Shortest,
+    /// Fixed number of digits after the decimal point, e.g. `17.300` for a precision of 3.
+    Decimal { precision: usize },
+    /// Scientific notation with a fixed number of digits after the decimal point, e.g. `1.730e1` for a precision of 3.
+    Scientific { precision: usize },
+}
+
 /*
 use std::marker::PhantomData;
 use writer::Writer;
 use ply::PropertyAccess;
 // */
 
+use std::fs::File;
 use std::io;
-use std::io::{ Write, Result, ErrorKind };
+use std::io::{ BufWriter, Write, Result, ErrorKind };
+use std::path::Path;
+use std::result;
 
 use crate::ply::Ply;
+use crate::error::Error;
+use crate::parser::PlyError;
 
 // ////////////////////////////
 // General
@@ -59,31 +108,59 @@ use crate::ply::Ply;
 impl<E: PropertyAccess> Writer<E> {
     /// Create a new `Writer<E>` where `E` is the element type. To get started quickly use `DefaultElement`.
     pub fn new() -> Self {
+        Self::new_with_newline(NewLine::N)
+    }
+    /// Create a new `Writer<E>` ending each line with `new_line`.
+    pub fn new_with_newline(new_line: NewLine) -> Self {
         Writer {
-            new_line: "\n".to_string(),
+            new_line: new_line.as_str().to_string(),
+            float_format: FloatFormat::Shortest,
             phantom: PhantomData,
         }
     }
+    /// Changes the line ending for all subsequent writes, the default is `NewLine::N`.
+    pub fn set_newline(&mut self, new_line: NewLine) {
+        self.new_line = new_line.as_str().to_string();
+    }
+    /// Changes how `float` and `double` values are written in ascii payloads, the default is `FloatFormat::Shortest`.
+    ///
+    /// Non-finite values are always written as `NaN`, `inf`, or `-inf`.
+    pub fn set_float_format(&mut self, float_format: FloatFormat) {
+        self.float_format = float_format;
+    }
+    /// Binary encoding matching the byte order of the target platform.
+    ///
+    /// Set it as `ply.header.encoding` to write binary files without byte swapping.
+    /// Same as `Header::native_binary_encoding()`.
+    pub fn native_binary_encoding() -> Encoding {
+        Header::native_binary_encoding()
+    }
     /// Writes an entire PLY file modeled by `ply` to `out`, performs consistency chekc.
     ///
     /// `ply` must be mutable since a consistency check is performed.
     /// If problems can be corrected automatically, `ply` will be modified accordingly.
     ///
     /// Returns number of bytes written.
-    pub fn write_ply<T: Write>(&self, out: &mut T, ply: &mut Ply<E>) -> Result<usize> {
-        match ply.make_consistent() {
-            Ok(()) => (),
-            Err(e) => return Err(io::Error::new(ErrorKind::InvalidInput, format!("The given ply isn't consistent: {:?}", e))),
-        };
+    pub fn write_ply<T: Write>(&self, out: &mut T, ply: &mut Ply<E>) -> result::Result<usize, Error> {
+        ply.make_consistent()?;
         self.write_ply_unchecked(out, ply)
     }
+    /// Creates or truncates the file at `path` and writes `ply` to it, see `write_ply()`.
+    ///
+    /// Returns number of bytes written.
+    pub fn write_ply_to_path<P: AsRef<Path>>(&self, path: P, ply: &mut Ply<E>) -> result::Result<usize, Error> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let written = self.write_ply(&mut out, ply)?;
+        out.flush()?;
+        Ok(written)
+    }
     /// Writes an entire PLY file modeled by `ply` to `out`, performes no consistency check.
     ///
     /// Like `write_ply` but doesn't check the input for inconsistency.
     /// The user is responsible to provide a consistent `Ply`,
     /// if not, behaviour is undefined and might result
     /// in a corrupted output.
-    pub fn write_ply_unchecked<T: Write>(&self, out: &mut T, ply: &Ply<E>) -> Result<usize> {
+    pub fn write_ply_unchecked<T: Write>(&self, out: &mut T, ply: &Ply<E>) -> result::Result<usize, Error> {
         let mut written = 0;
         written += self.write_header(out, &ply.header)?;
         written += self.write_payload(out, &ply.payload, &ply.header)?;
@@ -104,6 +181,19 @@ use ply::PropertyAccess;
 // */
 use crate::ply::{ Header, Encoding, Version, Comment, ObjInfo, ElementDef, PropertyDef, PropertyType, ScalarType };
 
+/// Discards all data and counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
 // ////////////////////////
 /// # Header
 // ////////////////////////
@@ -111,7 +201,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// Writes the magic number "ply" and a new line.
     ///
     /// Each PLY file must start with "ply\n".
-    pub fn write_line_magic_number<T: Write>(&self, out: &mut T) -> Result<usize> {
+    pub fn write_line_magic_number<T: Write>(&self, out: &mut T) -> result::Result<usize, Error> {
         let mut written = 0;
         written += out.write("ply".as_bytes())?;
         written += self.write_new_line(out)?;
@@ -120,7 +210,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// Writes "format <encoding> <version>".
     ///
     /// Each PLY file must define its format.
-    pub fn write_line_format<T: Write>(&self, out: &mut T, encoding: &Encoding, version: &Version) -> Result<usize> {
+    pub fn write_line_format<T: Write>(&self, out: &mut T, encoding: &Encoding, version: &Version) -> result::Result<usize, Error> {
         let mut written = 0;
         written += out.write("format ".as_bytes())?;
         written += self.write_encoding(out, encoding)?;
@@ -131,7 +221,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// Writes a comment line.
     ///
     /// A comment must not contain a line break and only consist of ascii characters.
-    pub fn write_line_comment<T: Write>(&self, out: &mut T, comment: &Comment) -> Result<usize> {
+    pub fn write_line_comment<T: Write>(&self, out: &mut T, comment: &Comment) -> result::Result<usize, Error> {
         let mut written = 0;
         written += out.write(format!("comment {}", comment).as_bytes())?;
         written += self.write_new_line(out)?;
@@ -140,7 +230,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// Writes an object information line.
     ///
     /// An object informatio line must not contain a line break an only consist of ascii characters.
-    pub fn write_line_obj_info<T: Write>(&self, out: &mut T, obj_info: &ObjInfo) -> Result<usize> {
+    pub fn write_line_obj_info<T: Write>(&self, out: &mut T, obj_info: &ObjInfo) -> result::Result<usize, Error> {
         let mut written = 0;
         written += out.write(format!("obj_info {}", obj_info).as_bytes())?;
         written += self.write_new_line(out)?;
@@ -152,7 +242,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// It is directly followed by its property definitions.
     ///
     /// Make sure the header is consistent with the payload.
-    pub fn write_line_element_definition<T: Write>(&self, out: &mut T, element: &ElementDef) -> Result<usize> {
+    pub fn write_line_element_definition<T: Write>(&self, out: &mut T, element: &ElementDef) -> result::Result<usize, Error> {
         let mut written = 0;
         written += out.write(format!("element {} {}", element.name, element.count).as_bytes())?;
         written += self.write_new_line(out)?;
@@ -161,10 +251,10 @@ impl<E: PropertyAccess> Writer<E> {
     /// Writes a property line form the header: "property [list <index_type> <scalar_type> | <scalar_type> ]"
     ///
     /// Make sure the property definition is consistent with the payload.
-    pub fn write_line_property_definition<T: Write>(&self, out: &mut T, property: &PropertyDef) -> Result<usize> {
+    pub fn write_line_property_definition<T: Write>(&self, out: &mut T, property: &PropertyDef) -> result::Result<usize, Error> {
         let mut written = 0;
         written += out.write("property ".as_bytes())?;
-        written += self.write_property_type(out, &property.data_type)?;
+        written += self.write_property_type(out, &property.data_type).map_err(|e| Error::header(1, e))?;
         written += out.write(" ".as_bytes())?;
         written += out.write(property.name.as_bytes())?;
         written += self.write_new_line(out)?;
@@ -175,16 +265,16 @@ impl<E: PropertyAccess> Writer<E> {
     /// Convenience method to call `write_line_element_definition` and `write_line_property_definition` in the correct way.
     ///
     /// Make sure the element definition is consistent with the payload.
-    pub fn write_element_definition<T: Write>(&self, out: &mut T, element: &ElementDef) -> Result<usize> {
+    pub fn write_element_definition<T: Write>(&self, out: &mut T, element: &ElementDef) -> result::Result<usize, Error> {
         let mut written = 0;
-        written += self.write_line_element_definition(out, &element)?;
-        for (_, p) in &element.properties {
-            written += self.write_line_property_definition(out, &p)?;
+        written += self.write_line_element_definition(out, element)?;
+        for (i, (_, p)) in element.properties.iter().enumerate() {
+            written += self.write_line_property_definition(out, p).map_err(|e| e.shift_line(i + 1))?;
         }
         Ok(written)
     }
     /// Writes `end_header\n`. This terminates the header. Each following byte belongs to the payload.
-    pub fn write_line_end_header<T: Write>(&self, out: &mut T) -> Result<usize> {
+    pub fn write_line_end_header<T: Write>(&self, out: &mut T) -> result::Result<usize, Error> {
         let mut written = 0;
         written += out.write("end_header".as_bytes())?;
         written += self.write_new_line(out)?;
@@ -195,7 +285,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// It starts with writing the magic number "ply\n" and ends with "end_header".
     ///
     /// Make sure the header is consistent with the payload.
-    pub fn write_header<T: Write>(&self, out: &mut T, header: &Header) -> Result<usize> {
+    pub fn write_header<T: Write>(&self, out: &mut T, header: &Header) -> result::Result<usize, Error> {
         let mut written = 0;
         written += self.write_line_magic_number(out)?;
         written += self.write_line_format(out, &header.encoding, &header.version)?;
@@ -205,12 +295,23 @@ impl<E: PropertyAccess> Writer<E> {
         for oi in &header.obj_infos {
             written += self.write_line_obj_info(out, oi)?;
         }
+        let mut lines = 2 + header.comments.len() + header.obj_infos.len();
         for (_, e) in &header.elements {
-            written += self.write_element_definition(out, &e)?;
+            written += self.write_element_definition(out, e).map_err(|err| err.shift_line(lines))?;
+            lines += 1 + e.properties.len();
         }
         written += self.write_line_end_header(out)?;
         Ok(written)
     }
+    /// Computes the number of bytes `write_header` would write for `header`, without writing anywhere.
+    ///
+    /// Useful to pre-allocate a buffer.
+    /// If the header can't be written, e.g. due to a float list index, the bytes up to the failure are counted.
+    pub fn estimate_header_bytes(&self, header: &Header) -> usize {
+        let mut counter = ByteCounter(0);
+        let _ = self.write_header(&mut counter, header);
+        counter.0
+    }
     fn write_encoding<T: Write>(&self, out: &mut T, encoding: &Encoding) -> Result<usize> {
         let s = match *encoding {
             Encoding::Ascii => "ascii",
@@ -237,16 +338,7 @@ impl<E: PropertyAccess> Writer<E> {
         }
     }
     fn write_scalar_type<T: Write>(&self, out: &mut T, scalar_type: &ScalarType) -> Result<usize> {
-        match *scalar_type {
-            ScalarType::Char => out.write("char".as_bytes()),
-            ScalarType::UChar => out.write("uchar".as_bytes()),
-            ScalarType::Short => out.write("short".as_bytes()),
-            ScalarType::UShort => out.write("ushort".as_bytes()),
-            ScalarType::Int => out.write("int".as_bytes()),
-            ScalarType::UInt => out.write("uint".as_bytes()),
-            ScalarType::Float => out.write("float".as_bytes()),
-            ScalarType::Double => out.write("double".as_bytes()),
-        }
+        out.write(scalar_type.to_string().as_bytes())
     }
 }
 /*
@@ -263,7 +355,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// Writes the payload of a `ply` (`ply.playload`).
     ///
     /// Make sure the Header is consistent with the payload.
-    pub fn write_payload<T: Write>(&self, out: &mut T, payload: &Payload<E>, header: &Header) -> Result<usize> {
+    pub fn write_payload<T: Write>(&self, out: &mut T, payload: &Payload<E>, header: &Header) -> result::Result<usize, Error> {
         let mut written = 0;
         let element_defs = &header.elements;
         for (k, element_list) in payload {
@@ -275,7 +367,7 @@ impl<E: PropertyAccess> Writer<E> {
     /// Write all elments as stored in the `element_list`.
     ///
     /// Make sure the header and the element definition is consistent with the payload.
-    pub fn write_payload_of_element<T: Write>(&self, out: &mut T, element_list: &Vec<E>, element_def: &ElementDef, header: &Header) -> Result<usize> {
+    pub fn write_payload_of_element<T: Write>(&self, out: &mut T, element_list: &Vec<E>, element_def: &ElementDef, header: &Header) -> result::Result<usize, Error> {
         let mut written = 0;
         match header.encoding {
             Encoding::Ascii => for element in element_list {
@@ -290,13 +382,43 @@ impl<E: PropertyAccess> Writer<E> {
         }
         Ok(written)
     }
+    /// Like `write_payload_of_element()`, but consumes the elements one at a time from `elements`.
+    ///
+    /// Allows writing elements that are generated lazily, without collecting them first.
+    /// Since the header has been written before, the number of elements must match `element_def.count`,
+    /// otherwise an error is returned, either as soon as there are too many elements or at the end of `elements`.
+    pub fn write_payload_element_streaming<T: Write, I: Iterator<Item = E>>(&self, out: &mut T, element_def: &ElementDef, header: &Header, elements: I) -> result::Result<usize, Error> {
+        let mut written = 0;
+        let mut count = 0;
+        for element in elements {
+            if count == element_def.count {
+                return Err(Error::payload(&element_def.name, io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Got more than the {} elements declared in the header.", element_def.count)
+                )));
+            }
+            count += 1;
+            written += match header.encoding {
+                Encoding::Ascii => self.write_ascii_element(out, &element, element_def)?,
+                Encoding::BinaryBigEndian => self.write_big_endian_element(out, &element, element_def)?,
+                Encoding::BinaryLittleEndian => self.write_little_endian_element(out, &element, element_def)?,
+            };
+        }
+        if count < element_def.count {
+            return Err(Error::payload(&element_def.name, io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("Got {} of the {} elements declared in the header.", count, element_def.count)
+            )));
+        }
+        Ok(written)
+    }
 }
 /*
 use std::io::{ Write, Result, ErrorKind };
 use ply::{ PropertyAccess, ElementDef, PropertyDef, PropertyType, ScalarType };
 use super::Writer;
 // */
-use std::fmt::Display;
+use std::fmt::{ Display, LowerExp };
 
 macro_rules! get_prop(
     ($e:expr) => (match $e {None => return Err(io::Error::new(ErrorKind::InvalidInput, "No property available for given key.")), Some(x) => x})
@@ -306,19 +428,15 @@ macro_rules! get_prop(
 impl<E: PropertyAccess> Writer<E> {
 
     /// Write a single ascii formatted element.
-    pub fn write_ascii_element<T: Write>(&self, out: &mut T, element: &E, element_def: &ElementDef) -> Result<usize> {
+    pub fn write_ascii_element<T: Write>(&self, out: &mut T, element: &E, element_def: &ElementDef) -> result::Result<usize, Error> {
+        self.__write_ascii_element(out, element, element_def).map_err(|e| Error::payload(&element_def.name, e))
+    }
+    fn __write_ascii_element<T: Write>(&self, out: &mut T, element: &E, element_def: &ElementDef) -> Result<usize> {
         let mut written = 0;
-        let mut p_iter = element_def.properties.iter();
-        let (_k, prop_type) = p_iter.next().unwrap();
-        written += self.write_ascii_property(out, element, &prop_type)?;
-        loop {
-            written += out.write(" ".as_bytes())?;
-            let n = p_iter.next();
-            if n == None {
-                break;
-            }
-            let (_name, prop_type) = n.unwrap();
+        // Elements without properties result in an empty line.
+        for (_name, prop_type) in &element_def.properties {
             written += self.write_ascii_property(out, element, prop_type)?;
+            written += out.write(" ".as_bytes())?;
         }
         written += self.write_new_line(out)?;
         Ok(written)
@@ -333,8 +451,10 @@ impl<E: PropertyAccess> Writer<E> {
                 ScalarType::UShort => self.write_ascii_scalar(out, get_prop!(element.get_ushort(k))),
                 ScalarType::Int => self.write_ascii_scalar(out, get_prop!(element.get_int(k))),
                 ScalarType::UInt => self.write_ascii_scalar(out, get_prop!(element.get_uint(k))),
-                ScalarType::Float => self.write_ascii_scalar(out, get_prop!(element.get_float(k))),
-                ScalarType::Double => self.write_ascii_scalar(out, get_prop!(element.get_double(k))),
+                ScalarType::Long => self.write_ascii_scalar(out, get_prop!(element.get_long(k))),
+                ScalarType::ULong => self.write_ascii_scalar(out, get_prop!(element.get_ulong(k))),
+                ScalarType::Float => self.write_ascii_float(out, get_prop!(element.get_float(k))),
+                ScalarType::Double => self.write_ascii_float(out, get_prop!(element.get_double(k))),
             },
             PropertyType::List(_, ref scalar_type) => match *scalar_type {
                 ScalarType::Char => self.write_ascii_list(get_prop!(element.get_list_char(k)), out),
@@ -343,8 +463,10 @@ impl<E: PropertyAccess> Writer<E> {
                 ScalarType::UShort => self.write_ascii_list(get_prop!(element.get_list_ushort(k)), out),
                 ScalarType::Int => self.write_ascii_list(get_prop!(element.get_list_int(k)), out),
                 ScalarType::UInt => self.write_ascii_list(get_prop!(element.get_list_uint(k)), out),
-                ScalarType::Float => self.write_ascii_list(get_prop!(element.get_list_float(k)), out),
-                ScalarType::Double => self.write_ascii_list(get_prop!(element.get_list_double(k)), out),
+                ScalarType::Long => self.write_ascii_list(get_prop!(element.get_list_long(k)), out),
+                ScalarType::ULong => self.write_ascii_list(get_prop!(element.get_list_ulong(k)), out),
+                ScalarType::Float => self.write_ascii_float_list(get_prop!(element.get_list_float(k)), out),
+                ScalarType::Double => self.write_ascii_float_list(get_prop!(element.get_list_double(k)), out),
             }
         };
         result
@@ -362,6 +484,26 @@ impl<E: PropertyAccess> Writer<E> {
         }
         Ok(written)
     }
+    fn format_float<V: Display + LowerExp>(&self, value: V) -> String {
+        match self.float_format {
+            FloatFormat::Shortest => value.to_string(),
+            FloatFormat::Decimal { precision } => format!("{:.*}", precision, value),
+            FloatFormat::Scientific { precision } => format!("{:.*e}", precision, value),
+        }
+    }
+    fn write_ascii_float<T: Write, V: Display + LowerExp>(&self, out: &mut T, value: V) -> Result<usize> {
+        out.write(self.format_float(value).as_bytes())
+    }
+    fn write_ascii_float_list<T: Write, V: Copy + Display + LowerExp>(&self, list: &[V], out: &mut T) -> Result<usize> {
+        let mut written = 0;
+        written += out.write(list.len().to_string().as_bytes())?;
+        let b = " ".as_bytes();
+        for &v in list {
+            written += out.write(b)?;
+            written += out.write(self.format_float(v).as_bytes())?;
+        }
+        Ok(written)
+    }
 }
 /*
 use ply::{ PropertyAccess, ElementDef, PropertyType, ScalarType };
@@ -371,6 +513,7 @@ use std::io;
 use std::io::{ Write, Result, ErrorKind };
 // */
 use byteorder::{ BigEndian, LittleEndian, WriteBytesExt, ByteOrder };
+use std::convert::TryFrom;
 
 /*
 macro_rules! get_prop(
@@ -382,12 +525,12 @@ macro_rules! get_prop(
 impl<E: PropertyAccess> Writer<E> {
     // private payload
     /// Write a single binary formatted element in big endian.
-    pub fn write_big_endian_element<T: Write> (&self, out: &mut T, element: &E, element_def: &ElementDef) -> Result<usize> {
-        self.__write_binary_element::<T, BigEndian>(out, element, element_def)
+    pub fn write_big_endian_element<T: Write> (&self, out: &mut T, element: &E, element_def: &ElementDef) -> result::Result<usize, Error> {
+        self.__write_binary_element::<T, BigEndian>(out, element, element_def).map_err(|e| Error::payload(&element_def.name, e))
     }
     /// Write a single binary formatted element in little endian.
-    pub fn write_little_endian_element<T: Write> (&self, out: &mut T, element: &E, element_def: &ElementDef) -> Result<usize> {
-        self.__write_binary_element::<T, LittleEndian>(out, element, element_def)
+    pub fn write_little_endian_element<T: Write> (&self, out: &mut T, element: &E, element_def: &ElementDef) -> result::Result<usize, Error> {
+        self.__write_binary_element::<T, LittleEndian>(out, element, element_def).map_err(|e| Error::payload(&element_def.name, e))
     }
     fn __write_binary_element<T: Write, B: ByteOrder>(&self, out: &mut T, element: &E, element_def: &ElementDef) -> Result<usize> {
         let mut written = 0;
@@ -401,43 +544,134 @@ impl<E: PropertyAccess> Writer<E> {
                         ScalarType::UShort => {out.write_u16::<B>(get_prop!(element.get_ushort(k)))?; 2},
                         ScalarType::Int => {out.write_i32::<B>(get_prop!(element.get_int(k)))?; 4},
                         ScalarType::UInt => {out.write_u32::<B>(get_prop!(element.get_uint(k)))?; 4},
+                        ScalarType::Long => {out.write_i64::<B>(get_prop!(element.get_long(k)))?; 8},
+                        ScalarType::ULong => {out.write_u64::<B>(get_prop!(element.get_ulong(k)))?; 8},
                         ScalarType::Float => {out.write_f32::<B>(get_prop!(element.get_float(k)))?; 4},
                         ScalarType::Double => {out.write_f64::<B>(get_prop!(element.get_double(k)))?; 8},
                     };
                 },
                 PropertyType::List(ref index_type, ref scalar_type) => {
-                    let vec_len = element_def.count;
-                    written += match *index_type {
-                        ScalarType::Char => {out.write_i8(vec_len as i8)?; 1},
-                        ScalarType::UChar => {out.write_u8(vec_len as u8)?; 1},
-                        ScalarType::Short => {out.write_i16::<B>(vec_len as i16)?; 2},
-                        ScalarType::UShort => {out.write_u16::<B>(vec_len as u16)?; 2},
-                        ScalarType::Int => {out.write_i32::<B>(vec_len as i32)?; 4},
-                        ScalarType::UInt => {out.write_u32::<B>(vec_len as u32)?; 4},
-                        ScalarType::Float => return Err(io::Error::new(ErrorKind::InvalidInput, "Index of list must be an integer type, float declared in PropertyType.")),
-                        ScalarType::Double => return Err(io::Error::new(ErrorKind::InvalidInput, "Index of list must be an integer type, double declared in PropertyType.")),
-                    };
-
                     written += match *scalar_type {
-                        ScalarType::Char => self.write_binary_list::<T, i8, B>(get_prop!(element.get_list_char(k)), out, &|o, x| {o.write_i8(*x)?; Ok(1)} )?,
-                        ScalarType::UChar => self.write_binary_list::<T, u8, B>(get_prop!(element.get_list_uchar(k)), out, &|o, x| {o.write_u8(*x)?; Ok(1)} )?,
-                        ScalarType::Short => self.write_binary_list::<T, i16, B>(get_prop!(element.get_list_short(k)), out, &|o, x| {o.write_i16::<B>(*x)?; Ok(2)} )?,
-                        ScalarType::UShort => self.write_binary_list::<T, u16, B>(get_prop!(element.get_list_ushort(k)), out, &|o, x| {o.write_u16::<B>(*x)?; Ok(2)} )?,
-                        ScalarType::Int => self.write_binary_list::<T, i32, B>(get_prop!(element.get_list_int(k)), out, &|o, x| {o.write_i32::<B>(*x)?; Ok(4)} )?,
-                        ScalarType::UInt => self.write_binary_list::<T, u32, B>(get_prop!(element.get_list_uint(k)), out, &|o, x| {o.write_u32::<B>(*x)?; Ok(4)} )?,
-                        ScalarType::Float => self.write_binary_list::<T, f32, B>(get_prop!(element.get_list_float(k)), out, &|o, x| {o.write_f32::<B>(*x)?; Ok(4)} )?,
-                        ScalarType::Double => self.write_binary_list::<T, f64, B>(get_prop!(element.get_list_double(k)), out, &|o, x| {o.write_f64::<B>(*x)?; Ok(8)} )?,
+                        ScalarType::Char => self.write_binary_list::<T, i8, B>(get_prop!(element.get_list_char(k)), index_type, out, &|o, x| {o.write_i8(*x)?; Ok(1)} )?,
+                        ScalarType::UChar => self.write_binary_list::<T, u8, B>(get_prop!(element.get_list_uchar(k)), index_type, out, &|o, x| {o.write_u8(*x)?; Ok(1)} )?,
+                        ScalarType::Short => self.write_binary_list::<T, i16, B>(get_prop!(element.get_list_short(k)), index_type, out, &|o, x| {o.write_i16::<B>(*x)?; Ok(2)} )?,
+                        ScalarType::UShort => self.write_binary_list::<T, u16, B>(get_prop!(element.get_list_ushort(k)), index_type, out, &|o, x| {o.write_u16::<B>(*x)?; Ok(2)} )?,
+                        ScalarType::Int => self.write_binary_list::<T, i32, B>(get_prop!(element.get_list_int(k)), index_type, out, &|o, x| {o.write_i32::<B>(*x)?; Ok(4)} )?,
+                        ScalarType::UInt => self.write_binary_list::<T, u32, B>(get_prop!(element.get_list_uint(k)), index_type, out, &|o, x| {o.write_u32::<B>(*x)?; Ok(4)} )?,
+                        ScalarType::Long => self.write_binary_list::<T, i64, B>(get_prop!(element.get_list_long(k)), index_type, out, &|o, x| {o.write_i64::<B>(*x)?; Ok(8)} )?,
+                        ScalarType::ULong => self.write_binary_list::<T, u64, B>(get_prop!(element.get_list_ulong(k)), index_type, out, &|o, x| {o.write_u64::<B>(*x)?; Ok(8)} )?,
+                        ScalarType::Float => self.write_binary_list::<T, f32, B>(get_prop!(element.get_list_float(k)), index_type, out, &|o, x| {o.write_f32::<B>(*x)?; Ok(4)} )?,
+                        ScalarType::Double => self.write_binary_list::<T, f64, B>(get_prop!(element.get_list_double(k)), index_type, out, &|o, x| {o.write_f64::<B>(*x)?; Ok(8)} )?,
                     }
                 }
             }
         };
         Ok(written)
     }
-    fn write_binary_list<T: Write, D, B: ByteOrder>(&self, list: &[D], out: &mut T, out_val: &dyn Fn(&mut T, &D) -> Result<usize>) -> Result<usize> {
-        let mut written = 0;
+    /// Writes the length of `list` as `index_type`, followed by its values.
+    fn write_binary_list<T: Write, D, B: ByteOrder>(&self, list: &[D], index_type: &ScalarType, out: &mut T, out_val: &dyn Fn(&mut T, &D) -> Result<usize>) -> Result<usize> {
+        let vec_len = list.len();
+        let mut written = match *index_type {
+            ScalarType::Char => {out.write_i8(list_index(vec_len, index_type)?)?; 1},
+            ScalarType::UChar => {out.write_u8(list_index(vec_len, index_type)?)?; 1},
+            ScalarType::Short => {out.write_i16::<B>(list_index(vec_len, index_type)?)?; 2},
+            ScalarType::UShort => {out.write_u16::<B>(list_index(vec_len, index_type)?)?; 2},
+            ScalarType::Int => {out.write_i32::<B>(list_index(vec_len, index_type)?)?; 4},
+            ScalarType::UInt => {out.write_u32::<B>(list_index(vec_len, index_type)?)?; 4},
+            ScalarType::Long => {out.write_i64::<B>(list_index(vec_len, index_type)?)?; 8},
+            ScalarType::ULong => {out.write_u64::<B>(list_index(vec_len, index_type)?)?; 8},
+            ScalarType::Float => return Err(io::Error::new(ErrorKind::InvalidInput, "Index of list must be an integer type, float declared in PropertyType.")),
+            ScalarType::Double => return Err(io::Error::new(ErrorKind::InvalidInput, "Index of list must be an integer type, double declared in PropertyType.")),
+        };
         for v in list {
             written += out_val(out, v)?;
         }
         Ok(written)
     }
 }
+
+// //////////////////////
+/// # Typed errors
+// //////////////////////
+impl<E: PropertyAccess> Writer<E> {
+    /// Same as `write_ply()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_ply_typed<T: Write>(&self, out: &mut T, ply: &mut Ply<E>) -> result::Result<usize, PlyError> {
+        Ok(self.write_ply(out, ply)?)
+    }
+    /// Same as `write_ply_to_path()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_ply_to_path_typed<P: AsRef<Path>>(&self, path: P, ply: &mut Ply<E>) -> result::Result<usize, PlyError> {
+        Ok(self.write_ply_to_path(path, ply)?)
+    }
+    /// Same as `write_ply_unchecked()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_ply_unchecked_typed<T: Write>(&self, out: &mut T, ply: &Ply<E>) -> result::Result<usize, PlyError> {
+        Ok(self.write_ply_unchecked(out, ply)?)
+    }
+    /// Same as `write_line_magic_number()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_line_magic_number_typed<T: Write>(&self, out: &mut T) -> result::Result<usize, PlyError> {
+        Ok(self.write_line_magic_number(out)?)
+    }
+    /// Same as `write_line_format()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_line_format_typed<T: Write>(&self, out: &mut T, encoding: &Encoding, version: &Version) -> result::Result<usize, PlyError> {
+        Ok(self.write_line_format(out, encoding, version)?)
+    }
+    /// Same as `write_line_comment()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_line_comment_typed<T: Write>(&self, out: &mut T, comment: &Comment) -> result::Result<usize, PlyError> {
+        Ok(self.write_line_comment(out, comment)?)
+    }
+    /// Same as `write_line_obj_info()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_line_obj_info_typed<T: Write>(&self, out: &mut T, obj_info: &ObjInfo) -> result::Result<usize, PlyError> {
+        Ok(self.write_line_obj_info(out, obj_info)?)
+    }
+    /// Same as `write_line_element_definition()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_line_element_definition_typed<T: Write>(&self, out: &mut T, element: &ElementDef) -> result::Result<usize, PlyError> {
+        Ok(self.write_line_element_definition(out, element)?)
+    }
+    /// Same as `write_line_property_definition()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_line_property_definition_typed<T: Write>(&self, out: &mut T, property: &PropertyDef) -> result::Result<usize, PlyError> {
+        Ok(self.write_line_property_definition(out, property)?)
+    }
+    /// Same as `write_element_definition()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_element_definition_typed<T: Write>(&self, out: &mut T, element: &ElementDef) -> result::Result<usize, PlyError> {
+        Ok(self.write_element_definition(out, element)?)
+    }
+    /// Same as `write_line_end_header()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_line_end_header_typed<T: Write>(&self, out: &mut T) -> result::Result<usize, PlyError> {
+        Ok(self.write_line_end_header(out)?)
+    }
+    /// Same as `write_header()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_header_typed<T: Write>(&self, out: &mut T, header: &Header) -> result::Result<usize, PlyError> {
+        Ok(self.write_header(out, header)?)
+    }
+    /// Same as `write_payload()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_payload_typed<T: Write>(&self, out: &mut T, payload: &Payload<E>, header: &Header) -> result::Result<usize, PlyError> {
+        Ok(self.write_payload(out, payload, header)?)
+    }
+    /// Same as `write_payload_of_element()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_payload_of_element_typed<T: Write>(&self, out: &mut T, element_list: &Vec<E>, element_def: &ElementDef, header: &Header) -> result::Result<usize, PlyError> {
+        Ok(self.write_payload_of_element(out, element_list, element_def, header)?)
+    }
+    /// Same as `write_payload_element_streaming()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_payload_element_streaming_typed<T: Write, I: Iterator<Item = E>>(&self, out: &mut T, element_def: &ElementDef, header: &Header, elements: I) -> result::Result<usize, PlyError> {
+        Ok(self.write_payload_element_streaming(out, element_def, header, elements)?)
+    }
+    /// Same as `write_ascii_element()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_ascii_element_typed<T: Write>(&self, out: &mut T, element: &E, element_def: &ElementDef) -> result::Result<usize, PlyError> {
+        Ok(self.write_ascii_element(out, element, element_def)?)
+    }
+    /// Same as `write_big_endian_element()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_big_endian_element_typed<T: Write>(&self, out: &mut T, element: &E, element_def: &ElementDef) -> result::Result<usize, PlyError> {
+        Ok(self.write_big_endian_element(out, element, element_def)?)
+    }
+    /// Same as `write_little_endian_element()`, but distinguishes IO errors from data that can't be written.
+    pub fn write_little_endian_element_typed<T: Write>(&self, out: &mut T, element: &E, element_def: &ElementDef) -> result::Result<usize, PlyError> {
+        Ok(self.write_little_endian_element(out, element, element_def)?)
+    }
+}
+
+/// Converts the length of a list to its declared index type, fails if it doesn't fit.
+fn list_index<I: TryFrom<usize>>(len: usize, index_type: &ScalarType) -> Result<I> {
+    I::try_from(len).map_err(|_| io::Error::new(
+        ErrorKind::InvalidInput,
+        format!("List of length {} exceeds the range of its index type {:?}.", len, index_type)
+    ))
+}
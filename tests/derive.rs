@@ -0,0 +1,59 @@
+#![cfg(feature = "derive")]
+extern crate ply_rs;
+use ply_rs::*;
+use ply_rs::ply::{ PropertyAccess, Property };
+
+#[derive(Debug, PartialEq, PropertyAccess)]
+struct Vertex {
+    x: f32,
+    y: f32,
+    #[ply(rename = "z")]
+    height: f64,
+    #[ply(skip)]
+    visited: bool,
+}
+
+#[derive(Debug, PartialEq, PropertyAccess)]
+#[ply(ignore_unknown)]
+struct Face {
+    #[ply(rename = "vertex_indices")]
+    indices: Vec<u32>,
+}
+
+#[test]
+fn derive_set_get() {
+    let mut v = Vertex::new();
+    v.set_property("x".to_string(), Property::Float(1.0));
+    v.set_property("z".to_string(), Property::Double(3.0));
+    assert_eq!(v, Vertex { x: 1.0, y: 0.0, height: 3.0, visited: false });
+    assert_eq!(v.get_float("x"), Some(1.0));
+    assert_eq!(v.get_double("z"), Some(3.0));
+    assert_eq!(v.get_double("height"), None);
+    assert_eq!(v.get_int("x"), None);
+}
+#[test]
+#[should_panic]
+fn derive_unknown_panics() {
+    let mut v = Vertex::new();
+    v.set_property("w".to_string(), Property::Float(1.0));
+}
+#[test]
+#[should_panic]
+fn derive_wrong_variant_panics() {
+    let mut v = Vertex::new();
+    v.set_property("x".to_string(), Property::Double(1.0));
+}
+#[test]
+fn derive_read_write() {
+    let txt = "ply\nformat ascii 1.0\nelement face 2\nproperty list uchar uint vertex_indices\nproperty uchar flags\nend_header\n3 0 1 2 7\n3 2 1 3 0\n";
+    let mut bytes = txt.as_bytes();
+    let p = parser::Parser::<Face>::new();
+    let mut ply = p.read_ply(&mut bytes).unwrap();
+    assert_eq!(ply.payload["face"][1], Face { indices: vec![2, 1, 3] });
+    assert_eq!(ply.payload["face"][0].get_list_uint("vertex_indices"), Some(&[0, 1, 2][..]));
+    ply.header.elements.get_mut("face").unwrap().properties.remove("flags");
+    let mut out = Vec::new();
+    writer::Writer::new().write_ply(&mut out, &mut ply).unwrap();
+    let read = p.read_ply(&mut out.as_slice()).unwrap();
+    assert_eq!(read.payload, ply.payload);
+}
@@ -181,3 +181,411 @@ mod struct_test_1 {
         }
     }
 }
+#[test]
+fn read_typed_parse_error() {
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let mut data = "ply\nformat ascii 1.0\nelement vertex one\nend_header\n".as_bytes();
+    match p.read_ply_typed(&mut data) {
+        Err(parser::PlyError::Parse(_)) => (),
+        r => panic!("Expected parse error, got {:?}", r),
+    }
+}
+#[test]
+fn read_typed_truncated_parse_error() {
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let mut data = "ply\nformat binary_little_endian 1.0\nelement vertex 1\nproperty float x\nend_header\n\x01".as_bytes();
+    match p.read_ply_typed(&mut data) {
+        Err(parser::PlyError::Parse(_)) => (),
+        r => panic!("Expected parse error, got {:?}", r),
+    }
+}
+#[test]
+fn read_typed_io_error() {
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    match p.read_ply_from_path_typed("example_plys/missing.ply") {
+        Err(parser::PlyError::Io(ref e)) if e.kind() == std::io::ErrorKind::NotFound => (),
+        r => panic!("Expected IO error, got {:?}", r),
+    }
+}
+#[test]
+fn read_typed_ok() {
+    let mut f = std::fs::File::open("example_plys/house_ok_ascii.ply").unwrap();
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let ply = p.read_ply_typed(&mut f).unwrap();
+    assert_eq!(ply.payload["face"].len(), 3);
+}
+#[test]
+fn iter_payload_equal() {
+    for path in &["example_plys/house_2_ok_ascii.ply", "example_plys/house_2_ok_little_endian.ply"] {
+        let expected = read_file(path);
+        let f = std::fs::File::open(path).unwrap();
+        let mut buf_read = std::io::BufReader::new(f);
+        let p = parser::Parser::<ply::DefaultElement>::new();
+        let header = p.read_header(&mut buf_read).unwrap();
+        for (name, element_def) in &header.elements {
+            let elements: Result<Vec<_>, _> = p.iter_payload_for_element(&mut buf_read, element_def, &header).collect();
+            assert_eq!(elements.unwrap(), expected.payload[name]);
+        }
+    }
+}
+#[test]
+fn iter_payload_truncated_err() {
+    let txt = "ply\nformat binary_big_endian 1.0\nelement point 3\nproperty int x\nend_header\n";
+    let mut bytes = txt.as_bytes().to_vec();
+    bytes.extend_from_slice(&[0, 0, 0, 1, 0, 0]);
+    let mut reader = bytes.as_slice();
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let header = p.read_header(&mut reader).unwrap();
+    let results: Vec<_> = p.iter_payload_for_element(&mut reader, &header.elements["point"], &header).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+#[test]
+fn property_access_str_keys() {
+    use ply::PropertyAccess;
+    let ply = read_file("example_plys/house_ok_ascii.ply");
+    let vertex = &ply.payload["vertex"][0];
+    assert!(vertex.get_float("x").is_some());
+    assert!(vertex.get_double("x").is_none());
+    assert_eq!(ply.payload["face"][0].get_list_uint("vertex_indices").map(|l| l.len()), Some(3));
+}
+#[test]
+fn property_access_get_property() {
+    use ply::PropertyAccess;
+    fn first_x<E: ply::PropertyAccess>(ply: &ply::Ply<E>) -> Option<ply::Property> {
+        ply.payload["vertex"][0].get_property("x").cloned()
+    }
+    let ply = read_file("example_plys/house_ok_ascii.ply");
+    assert_eq!(first_x(&ply), Some(ply.payload["vertex"][0]["x"].clone()));
+    assert!(ply.payload["vertex"][0].get_property("nothing").is_none());
+}
+#[test]
+fn property_access_keys() {
+    use ply::PropertyAccess;
+    let ply = read_file("example_plys/house_ok_ascii.ply");
+    let face = &ply.payload["face"][0];
+    assert_eq!(face.property_count(), 1);
+    assert_eq!(face.property_keys(), vec!["vertex_indices".to_string()]);
+    assert_eq!(ply.payload["vertex"][0].property_count(), 6);
+}
+#[test]
+fn property_access_list_len() {
+    use ply::PropertyAccess;
+    let ply = read_file("example_plys/house_ok_ascii.ply");
+    assert_eq!(ply.payload["face"][0].list_len("vertex_indices"), Some(3));
+    assert_eq!(ply.payload["vertex"][0].list_len("x"), None);
+    assert_eq!(ply.payload["face"][0].list_len("nothing"), None);
+    // the default implementation doesn't know any lists
+    struct Face;
+    impl ply::PropertyAccess for Face {
+        fn new() -> Self {
+            Face
+        }
+    }
+    assert_eq!(Face.list_len("vertex_indices"), None);
+}
+#[test]
+fn read_error_invalid_header_line() {
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let mut data = "ply\nformat ascii 1.0\nelement vertex one\nend_header\n".as_bytes();
+    match p.read_ply(&mut data) {
+        Err(Error::InvalidHeader { line: 3, .. }) => (),
+        r => panic!("Expected invalid header in line 3, got {:?}", r),
+    }
+}
+#[test]
+fn read_error_invalid_payload_element() {
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let mut data = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nelement face 1\nproperty uchar c\nend_header\n1.5\n300\n".as_bytes();
+    match p.read_ply(&mut data) {
+        Err(Error::InvalidPayload { ref element, .. }) if element == "face" => (),
+        r => panic!("Expected invalid payload in `face`, got {:?}", r),
+    }
+}
+#[test]
+fn read_error_io() {
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let mut data = "ply\nformat binary_little_endian 1.0\nelement vertex 1\nproperty float x\nend_header\n\x01".as_bytes();
+    match p.read_ply(&mut data) {
+        Err(Error::IoError(_)) => (),
+        r => panic!("Expected IO error, got {:?}", r),
+    }
+}
+#[test]
+fn read_header_only_equal() {
+    let ply = read_file("example_plys/house_ok_ascii.ply");
+    let mut f = std::fs::File::open("example_plys/house_ok_ascii.ply").unwrap();
+    let header = parser::Parser::<ply::DefaultElement>::new().read_header_only(&mut f).unwrap();
+    assert_eq!(header, ply.header);
+}
+#[test]
+fn header_has_element_property() {
+    let ply = read_file("example_plys/house_ok_ascii.ply");
+    assert!(ply.header.has_element("vertex"));
+    assert!(!ply.header.has_element("edge"));
+    assert!(ply.header.has_property("vertex", "x"));
+    assert!(!ply.header.has_property("vertex", "w"));
+    assert!(!ply.header.has_property("edge", "x"));
+}
+const LENIENT_TXT: &str = "ply\n\
+    format ascii 1.0\n\
+    element point 2\n\
+    property int x\n\
+    property int y\n\
+    end_header\n\
+    -7 5\n\
+    unknown element\n\
+    2 4\n\
+    trailing garbage\n";
+#[test]
+fn read_lenient_skips_unknown() {
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    assert!(p.read_ply(&mut LENIENT_TXT.as_bytes()).is_err());
+    let p = parser::Parser::<ply::DefaultElement>::lenient();
+    let ply = p.read_ply(&mut LENIENT_TXT.as_bytes()).unwrap();
+    assert_eq!(ply.payload["point"][1]["x"], ply::Property::Int(2));
+    let truncated = &LENIENT_TXT[..LENIENT_TXT.find("2 4").unwrap()];
+    match p.read_ply(&mut truncated.as_bytes()) {
+        Err(Error::IoError(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => (),
+        r => panic!("Expected unexpected end of file, got {:?}", r),
+    }
+}
+#[test]
+fn read_lenient_malformed_element_err() {
+    let header = "ply\nformat ascii 1.0\nelement vertex 3\nproperty int x\nproperty int y\nproperty int z\n\
+        element face 2\nproperty list uchar int vertex_indices\nend_header\n";
+    let p = parser::Parser::<ply::DefaultElement>::lenient();
+    // the malformed vertex is skipped, but the faces are not read as vertices in its place
+    let txt = format!("{}0 0 0\n1 0 oops\n0 1 0\n3 0 1 2\n3 0 1 2\n", header);
+    assert!(p.read_ply(&mut txt.as_bytes()).is_err());
+    // lines with more tokens than the element are skipped as unknown
+    let txt = format!("{}0 0 0\n1 0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n7\n3 0 1 2 5\n3 0 2 1\n", header);
+    let ply = p.read_ply(&mut txt.as_bytes()).unwrap();
+    assert_eq!(ply.payload["vertex"][1]["x"], ply::Property::Int(1));
+    assert_eq!(ply.payload["face"][1]["vertex_indices"], ply::Property::ListInt(vec![0, 2, 1]));
+}
+#[test]
+fn read_trailing_garbage_err() {
+    let txt = LENIENT_TXT.replace("unknown element\n", "");
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    assert!(p.read_ply(&mut txt.as_bytes()).is_ok());
+    let p = parser::Parser::<ply::DefaultElement>::with_options(parser::ParserOptions {
+        ignore_trailing_garbage: false,
+        ..parser::ParserOptions::default()
+    });
+    match p.read_ply(&mut txt.as_bytes()) {
+        Err(Error::InvalidPayload { ref element, .. }) if element == "point" => (),
+        r => panic!("Expected invalid payload in `point`, got {:?}", r),
+    }
+    let txt = txt.replace("trailing garbage\n", "\n \n");
+    assert!(p.read_ply(&mut txt.as_bytes()).is_ok());
+}
+#[test]
+fn skip_payload_equal() {
+    for path in &["example_plys/house_2_ok_ascii.ply", "example_plys/house_2_ok_little_endian.ply"] {
+        let expected = read_file(path);
+        for wanted in expected.header.elements.keys() {
+            let f = std::fs::File::open(path).unwrap();
+            let mut buf_read = std::io::BufReader::new(f);
+            let p = parser::Parser::<ply::DefaultElement>::new();
+            let header = p.read_header(&mut buf_read).unwrap();
+            for (name, element_def) in &header.elements {
+                if name == wanted {
+                    let elements = p.read_payload_for_element(&mut buf_read, element_def, &header).unwrap();
+                    assert_eq!(elements, expected.payload[name]);
+                } else {
+                    p.skip_payload_for_element(&mut buf_read, element_def, &header).unwrap();
+                }
+            }
+        }
+    }
+}
+#[test]
+fn read_ply_with_filter_ok() {
+    let files = [
+        "example_plys/house_2_ok_little_endian.ply",
+        "example_plys/house_2_ok_ascii.ply",
+        "example_plys/house_ok_ascii.ply",
+    ];
+    for path in &files {
+        let expected = read_file(path);
+        let mut f = std::fs::File::open(path).unwrap();
+        let p = parser::Parser::<ply::DefaultElement>::new();
+        let ply = p.read_ply_with_filter(&mut f, |name| name == "face").unwrap();
+        assert_eq!(ply.header, expected.header);
+        assert_eq!(ply.payload.len(), 1);
+        assert_eq!(ply.payload["face"], expected.payload["face"]);
+    }
+}
+#[test]
+fn read_ply_with_filter_error_line() {
+    let txt = "ply\nformat ascii 1.0\nelement point 2\nproperty int x\nelement face 1\nproperty int y\nend_header\n1\n2\nfoo\n";
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    match p.read_ply_with_filter(&mut txt.as_bytes(), |name| name == "face") {
+        Err(Error::InvalidPayload { ref element, ref message }) if element == "face" && message.contains("Line 10") => (),
+        r => panic!("Expected error in line 10, got {:?}", r),
+    }
+}
+#[test]
+fn read_ply_with_filter_skip_err() {
+    let txt = "ply\nformat binary_little_endian 1.0\nelement face 1\nproperty list char int vertex_indices\nelement point 1\nproperty uchar x\nend_header\n";
+    let mut bytes = txt.as_bytes().to_vec();
+    bytes.extend_from_slice(&[0xff, 7]);
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    match p.read_ply_with_filter(&mut bytes.as_slice(), |name| name == "point") {
+        Err(Error::InvalidPayload { ref element, .. }) if element == "face" => (),
+        r => panic!("Expected invalid payload of skipped element, got {:?}", r),
+    }
+}
+#[test]
+fn skip_payload_truncated_err() {
+    let txt = "ply\nformat binary_big_endian 1.0\nelement point 3\nproperty int x\nend_header\n";
+    let mut bytes = txt.as_bytes().to_vec();
+    bytes.extend_from_slice(&[0, 0, 0, 1, 0, 0]);
+    let mut reader = bytes.as_slice();
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let header = p.read_header(&mut reader).unwrap();
+    match p.skip_payload_for_element(&mut reader, &header.elements["point"], &header) {
+        Err(Error::IoError(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => (),
+        r => panic!("Expected unexpected end of file, got {:?}", r),
+    }
+}
+#[test]
+fn skip_payload_negative_list_length_err() {
+    let txt = "ply\nformat binary_little_endian 1.0\nelement face 1\nproperty list char int vertex_indices\nend_header\n";
+    let mut bytes = txt.as_bytes().to_vec();
+    bytes.push(0xff);
+    let mut reader = bytes.as_slice();
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let header = p.read_header(&mut reader).unwrap();
+    match p.skip_payload_for_element(&mut reader, &header.elements["face"], &header) {
+        Err(Error::InvalidPayload { ref element, ref message }) if element == "face" && message.contains("-1") => (),
+        r => panic!("Expected invalid payload, got {:?}", r),
+    }
+}
+#[test]
+fn read_binary_negative_int64_list_length_err() {
+    let txt = "ply\nformat binary_little_endian 1.0\nelement face 1\nproperty list int64 int vertex_indices\nend_header\n";
+    let mut bytes = txt.as_bytes().to_vec();
+    bytes.extend_from_slice(&(-1i64).to_le_bytes());
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    match p.read_ply(&mut bytes.as_slice()) {
+        Err(Error::InvalidPayload { ref element, ref message }) if element == "face" && message.contains("-1") => (),
+        r => panic!("Expected invalid payload, got {:?}", r),
+    }
+}
+#[test]
+fn skip_payload_size_overflow_err() {
+    let txt = format!("ply\nformat binary_little_endian 1.0\nelement point {}\nproperty double x\nend_header\n", usize::MAX);
+    let mut reader = txt.as_bytes();
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let header = p.read_header(&mut reader).unwrap();
+    match p.skip_payload_for_element(&mut reader, &header.elements["point"], &header) {
+        Err(Error::InvalidPayload { ref element, .. }) if element == "point" => (),
+        r => panic!("Expected invalid payload, got {:?}", r),
+    }
+}
+#[test]
+fn read_ply_from_path_equal() {
+    let path = "example_plys/house_2_ok_little_endian.ply";
+    let ply = parser::Parser::<ply::DefaultElement>::new().read_ply_from_path(path).unwrap();
+    assert_eq!(ply, read_file(path));
+    match parser::Parser::<ply::DefaultElement>::new().read_ply_from_path("example_plys/missing.ply") {
+        Err(Error::IoError(ref e)) if e.kind() == std::io::ErrorKind::NotFound => (),
+        r => panic!("Expected file not found, got {:?}", r),
+    }
+}
+#[test]
+fn element_property_names() {
+    let ply = read_file("example_plys/house_ok_ascii.ply");
+    assert_eq!(ply.element_names().collect::<Vec<_>>(), vec!["vertex", "face"]);
+    assert_eq!(ply.header.elements["vertex"].property_names().collect::<Vec<_>>(), vec!["x", "y", "z", "nx", "ny", "nz"]);
+}
+/// Only implements `Read`, like a socket or pipe.
+struct Stream<'a>(&'a [u8]);
+impl<'a> std::io::Read for Stream<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // hand out small chunks to exercise buffer refills
+        let n = std::cmp::min(3, buf.len());
+        std::io::Read::read(&mut self.0, &mut buf[..n])
+    }
+}
+#[test]
+fn read_big_endian_from_stream() {
+    let txt = "ply\nformat binary_big_endian 1.0\nelement point 2\nproperty short x\nproperty list uchar int l\nend_header\n";
+    let mut bytes = txt.as_bytes().to_vec();
+    bytes.extend_from_slice(&[0xFF, 0xFE, 2, 0, 0, 0, 1, 0, 0, 0, 2]);
+    bytes.extend_from_slice(&[0x00, 0x07, 0]);
+    let mut reader = std::io::BufReader::new(Stream(&bytes));
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    let header = p.read_header(&mut reader).unwrap();
+    // the same `BufRead` serves the header and the binary payload
+    let first = p.read_big_endian_element(&mut reader, &header.elements["point"]).unwrap();
+    assert_eq!(first["x"], ply::Property::Short(-2));
+    assert_eq!(first["l"], ply::Property::ListInt(vec![1, 2]));
+    let mut rest = header.elements["point"].clone();
+    rest.count = 1;
+    let second = p.read_payload_for_element(&mut reader, &rest, &header).unwrap();
+    assert_eq!(second[0]["x"], ply::Property::Short(7));
+    assert_eq!(second[0]["l"], ply::Property::ListInt(vec![]));
+}
+#[cfg(feature = "rayon")]
+#[test]
+fn read_payload_parallel_equal() {
+    let mut ply = read_file("example_plys/house_2_ok_ascii.ply");
+    for &encoding in &[ply::Encoding::BinaryBigEndian, ply::Encoding::BinaryLittleEndian] {
+        ply.header.encoding = encoding;
+        let mut header_bytes = Vec::new();
+        let w = writer::Writer::<ply::DefaultElement>::new();
+        w.write_header(&mut header_bytes, &ply.header).unwrap();
+        let mut payload_bytes = Vec::new();
+        w.write_payload(&mut payload_bytes, &ply.payload, &ply.header).unwrap();
+        let p = parser::Parser::<ply::DefaultElement>::new();
+        let mut data = &payload_bytes[..];
+        for (name, element_def) in &ply.header.elements {
+            let elements = match encoding {
+                ply::Encoding::BinaryBigEndian => p.read_big_endian_payload_parallel(data, element_def),
+                _ => p.read_little_endian_payload_parallel(data, element_def),
+            }.unwrap();
+            assert_eq!(elements, ply.payload[name]);
+            p.skip_payload_for_element(&mut data, element_def, &ply.header).unwrap();
+        }
+        assert!(data.is_empty());
+        let mut truncated = ply.header.elements["vertex"].clone();
+        truncated.count += 1000;
+        assert!(p.read_big_endian_payload_parallel(&payload_bytes, &truncated).is_err());
+    }
+    let mut negative = ply::ElementDef::new("face".to_string());
+    negative.add_property("vertex_indices", ply::PropertyType::List(ply::ScalarType::Char, ply::ScalarType::Int));
+    negative.count = 1;
+    let p = parser::Parser::<ply::DefaultElement>::new();
+    assert!(p.read_little_endian_payload_parallel(&[0xff], &negative).is_err());
+    negative.count = usize::MAX;
+    assert!(p.read_little_endian_payload_parallel(&[], &negative).is_err());
+}
+#[cfg(feature = "rayon")]
+#[test]
+fn read_payload_parallel_send_only() {
+    // `Cell` is `Send`, but not `Sync`
+    struct Point {
+        x: std::cell::Cell<i32>,
+    }
+    impl ply::PropertyAccess for Point {
+        fn new() -> Self {
+            Point { x: std::cell::Cell::new(0) }
+        }
+        fn set_property(&mut self, key: String, property: ply::Property) {
+            match (key.as_ref(), property) {
+                ("x", ply::Property::Int(v)) => self.x.set(v),
+                (k, _) => panic!("Point: Unexpected key/value combination: key: {}", k),
+            }
+        }
+    }
+    let mut element_def = ply::ElementDef::new("point".to_string());
+    element_def.add_property("x", ply::PropertyType::Scalar(ply::ScalarType::Int));
+    element_def.count = 2;
+    let p = parser::Parser::<Point>::new();
+    let points = p.read_little_endian_payload_parallel(&[1, 0, 0, 0, 2, 0, 0, 0], &element_def).unwrap();
+    assert_eq!(points.iter().map(|p| p.x.get()).collect::<Vec<_>>(), vec![1, 2]);
+}
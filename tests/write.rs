@@ -22,8 +22,12 @@ fn write_buff(ply: &Ply) -> Vec<u8> {
 fn read_write_ply(ply: &Ply) -> Ply {
     println!("writing ply:\n{:?}", ply);
     let ve : Vec<u8> = write_buff(&ply);
-    let txt = String::from_utf8(ve.clone()).unwrap();
-    println!("written ply:\n{}", txt);
+    if ply.header.encoding == Encoding::Ascii {
+        let txt = String::from_utf8(ve.clone()).unwrap();
+        println!("written ply:\n{}", txt);
+    } else {
+        println!("written ply: {} bytes", ve.len());
+    }
     let mut buff = BufReader::new(&(*ve));
     let new_ply = read_buff(&mut buff);
     println!("read ply:\n{:?}", new_ply);
@@ -129,3 +133,587 @@ fn write_list_elements() {
     let new_ply = read_write_ply(&ply);
     assert_eq!(ply, new_ply);
 }
+#[test]
+fn write_added_property() {
+    let mut ply = create_single_elements();
+    let def = PropertyDef::new("z".to_string(), PropertyType::Scalar(ScalarType::Float));
+    ply.add_property_to_element("point", def, vec![Property::Float(0.5), Property::Float(-1.5)]).unwrap();
+    assert_eq!(ply.payload["point"][1]["z"], Property::Float(-1.5));
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn add_property_wrong_count_err() {
+    let mut ply = create_single_elements();
+    let def = PropertyDef::new("z".to_string(), PropertyType::Scalar(ScalarType::Float));
+    let original = ply.clone();
+    assert!(ply.add_property_to_element("point", def.clone(), vec![Property::Float(0.5)]).is_err());
+    assert!(ply.add_property_to_element("nothing", def.clone(), vec![]).is_err());
+    assert_eq!(ply, original);
+    // a declared element without payload has no entries
+    ply.payload.remove("point");
+    let original = ply.clone();
+    assert!(ply.add_property_to_element("point", def, vec![Property::Float(0.5)]).is_err());
+    assert_eq!(ply, original);
+}
+#[test]
+fn write_list_elements_binary() {
+    let mut ply = create_list_elements();
+    ply.header.encoding = Encoding::BinaryLittleEndian;
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+    ply.header.encoding = Encoding::BinaryBigEndian;
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn write_inconsistent_err() {
+    let mut ply = create_min();
+    ply.header.comments.push("two\nlines".to_string());
+    let mut buf = Vec::<u8>::new();
+    match writer::Writer::new().write_ply(&mut buf, &mut ply) {
+        Err(Error::InconsistentData(_)) => (),
+        r => panic!("Expected inconsistent data, got {:?}", r),
+    }
+}
+#[test]
+fn write_missing_property_err() {
+    let mut ply = create_single_elements();
+    ply.payload.get_mut("point").unwrap()[0].remove("x");
+    let mut buf = Vec::<u8>::new();
+    match writer::Writer::new().write_ply(&mut buf, &mut ply) {
+        Err(Error::InvalidPayload { ref element, .. }) if element == "point" => (),
+        r => panic!("Expected invalid payload in `point`, got {:?}", r),
+    }
+}
+#[test]
+fn write_float_list_index_err() {
+    let mut ply = create_basic_header();
+    let mut e = ElementDef::new("face".to_string());
+    e.properties.add(PropertyDef::new("a".to_string(), PropertyType::Scalar(ScalarType::Int)));
+    e.properties.add(PropertyDef::new("b".to_string(), PropertyType::List(ScalarType::Float, ScalarType::Int)));
+    ply.header.elements.add(e);
+    let lines = 2 + ply.header.comments.len() + ply.header.obj_infos.len()
+        + ply.header.elements.values().map(|e| 1 + e.properties.len()).sum::<usize>();
+    let mut buf = Vec::<u8>::new();
+    match writer::Writer::<DefaultElement>::new().write_header(&mut buf, &ply.header) {
+        Err(Error::InvalidHeader { line, .. }) => assert_eq!(line, lines),
+        r => panic!("Expected invalid header, got {:?}", r),
+    }
+}
+#[test]
+fn write_newline_styles() {
+    let ply = create_list_elements();
+    for &(new_line, ending) in &[(writer::NewLine::N, "\n"), (writer::NewLine::RN, "\r\n"), (writer::NewLine::R, "\r")] {
+        let mut buf = Vec::<u8>::new();
+        let mut w = writer::Writer::new();
+        w.set_newline(new_line);
+        w.write_ply_unchecked(&mut buf, &ply).unwrap();
+        assert_eq!(buf, {
+            let mut b = Vec::<u8>::new();
+            writer::Writer::new_with_newline(new_line).write_ply_unchecked(&mut b, &ply).unwrap();
+            b
+        });
+        let txt = String::from_utf8(buf).unwrap();
+        assert!(txt.starts_with(&format!("ply{}format ascii 1.0{}", ending, ending)));
+        // `BufRead::read_line` only splits at `\n`, lone `\r` are handled by the grammar
+        let txt = if new_line == writer::NewLine::R { txt.replace('\r', "\r\n") } else { txt };
+        let new_ply = read_buff(&mut BufReader::new(txt.as_bytes()));
+        assert_eq!(ply, new_ply);
+    }
+}
+fn create_triangle(offset: f32) -> Ply {
+    let mut ply = Ply::new();
+    let mut vertex = ElementDef::new("vertex".to_string());
+    vertex.properties.add(PropertyDef::new("x".to_string(), PropertyType::Scalar(ScalarType::Float)));
+    let mut face = ElementDef::new("face".to_string());
+    face.properties.add(PropertyDef::new("vertex_indices".to_string(), PropertyType::List(ScalarType::UChar, ScalarType::UInt)));
+    ply.header.elements.add(vertex);
+    ply.header.elements.add(face);
+    let vertices = (0..3).map(|i| {
+        let mut v = KeyMap::new();
+        v.insert("x".to_string(), Property::Float(offset + i as f32));
+        v
+    }).collect();
+    let mut f = KeyMap::new();
+    f.insert("vertex_indices".to_string(), Property::ListUInt(vec![0, 1, 2]));
+    ply.payload.insert("vertex".to_string(), vertices);
+    ply.payload.insert("face".to_string(), vec![f]);
+    assert!(ply.make_consistent().is_ok());
+    ply
+}
+#[test]
+fn write_merged() {
+    let mut ply = create_triangle(0.0);
+    ply.merge(create_triangle(10.0)).unwrap();
+    assert_eq!(ply.header.elements["vertex"].count, 6);
+    assert_eq!(ply.header.elements["face"].count, 2);
+    assert_eq!(ply.payload["vertex"][4]["x"], Property::Float(11.0));
+    assert_eq!(ply.payload["face"][1]["vertex_indices"], Property::ListUInt(vec![3, 4, 5]));
+    ply.merge(create_list_elements()).unwrap();
+    assert_eq!(ply.header.elements["aList"].count, 2);
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn merge_mismatch_err() {
+    let mut ply = create_triangle(0.0);
+    let mut other = create_triangle(10.0);
+    other.header.elements.get_mut("vertex").unwrap().properties.add(PropertyDef::new("y".to_string(), PropertyType::Scalar(ScalarType::Float)));
+    assert!(ply.merge(other).is_err());
+    assert_eq!(ply, create_triangle(0.0));
+}
+#[test]
+fn merge_inconsistent_self_err() {
+    let mut ply = create_triangle(0.0);
+    ply.payload.insert("undeclared".to_string(), Vec::new());
+    let before = ply.clone();
+    assert!(ply.merge(create_triangle(10.0)).is_err());
+    assert_eq!(ply, before);
+}
+#[test]
+fn write_float_formats() {
+    let mut ply = Ply::new();
+    let mut e = ElementDef::new("point".to_string());
+    e.properties.add(PropertyDef::new("f".to_string(), PropertyType::Scalar(ScalarType::Float)));
+    e.properties.add(PropertyDef::new("d".to_string(), PropertyType::Scalar(ScalarType::Double)));
+    e.properties.add(PropertyDef::new("l".to_string(), PropertyType::List(ScalarType::UChar, ScalarType::Double)));
+    ply.header.elements.add(e);
+    let mut pe = KeyMap::new();
+    pe.insert("f".to_string(), Property::Float(17.3));
+    pe.insert("d".to_string(), Property::Double(-0.125));
+    pe.insert("l".to_string(), Property::ListDouble(vec![1234.5, f64::INFINITY]));
+    ply.payload.insert("point".to_string(), vec![pe]);
+    assert!(ply.make_consistent().is_ok());
+
+    for &(format, line) in &[
+        (writer::FloatFormat::Shortest, "17.3 -0.125 2 1234.5 inf \n"),
+        (writer::FloatFormat::Decimal { precision: 2 }, "17.30 -0.12 2 1234.50 inf \n"),
+        (writer::FloatFormat::Scientific { precision: 3 }, "1.730e1 -1.250e-1 2 1.234e3 inf \n"),
+    ] {
+        let mut w = writer::Writer::new();
+        w.set_float_format(format);
+        let mut buf = Vec::<u8>::new();
+        w.write_ply_unchecked(&mut buf, &ply).unwrap();
+        let txt = String::from_utf8(buf).unwrap();
+        assert!(txt.ends_with(&format!("end_header\n{}", line)), "{}", txt);
+        read_buff(&mut BufReader::new(txt.as_bytes()));
+    }
+}
+#[test]
+fn write_64_bit_integers() {
+    let mut ply = Ply::new();
+    let mut e = ElementDef::new("point".to_string());
+    e.add_property("offset", PropertyType::Scalar(ScalarType::Long))
+        .add_property("id", PropertyType::Scalar(ScalarType::ULong))
+        .add_property("ids", PropertyType::List(ScalarType::UChar, ScalarType::ULong));
+    ply.header.elements.add(e);
+    let mut pe = KeyMap::new();
+    pe.insert("offset".to_string(), Property::Long(i64::MIN));
+    pe.insert("id".to_string(), Property::ULong(u64::MAX));
+    pe.insert("ids".to_string(), Property::ListULong(vec![0, u64::MAX]));
+    ply.payload.insert("point".to_string(), vec![pe]);
+    ply.make_consistent().unwrap();
+    for encoding in [Encoding::Ascii, Encoding::BinaryBigEndian, Encoding::BinaryLittleEndian] {
+        ply.header.encoding = encoding;
+        let new_ply = read_write_ply(&ply);
+        assert_eq!(ply, new_ply);
+    }
+    let txt = String::from_utf8(write_buff(&ply.convert_encoding(Encoding::Ascii).unwrap())).unwrap();
+    assert!(txt.contains("property int64 offset\nproperty uint64 id\nproperty list uchar uint64 ids\n"), "{}", txt);
+}
+#[test]
+fn write_native_binary() {
+    let encoding = writer::Writer::<DefaultElement>::native_binary_encoding();
+    assert_eq!(encoding, if cfg!(target_endian = "little") { Encoding::BinaryLittleEndian } else { Encoding::BinaryBigEndian });
+    assert_eq!(Header::native_binary_encoding(), encoding);
+    let mut ply = create_list_elements();
+    ply.header.encoding = encoding;
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn estimate_header_bytes_exact() {
+    let mut ply = create_list_elements();
+    ply.header.comments.push("estimated".to_string());
+    ply.make_consistent().unwrap();
+    let mut w = writer::Writer::<DefaultElement>::new();
+    for nl in [writer::NewLine::N, writer::NewLine::RN] {
+        w.set_newline(nl);
+        let mut buf = Vec::<u8>::new();
+        let written = w.write_header(&mut buf, &ply.header).unwrap();
+        assert_eq!(w.estimate_header_bytes(&ply.header), written);
+        assert_eq!(written, buf.len());
+    }
+}
+#[test]
+fn write_element_without_properties() {
+    let mut ply = create_min();
+    ply.add_element_payload(ElementDef::new("marker".to_string()), vec![KeyMap::new(), KeyMap::new()]).unwrap();
+    let txt = String::from_utf8(write_buff(&ply)).unwrap();
+    assert!(txt.ends_with("end_header\n\n\n"), "{}", txt);
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn write_found_property_changed() {
+    let mut ply = create_triangle(0.0);
+    let face = ply.header.elements.get_mut("face").unwrap();
+    face.find_property_mut("vertex_indices").unwrap().data_type = PropertyType::List(ScalarType::UShort, ScalarType::UInt);
+    assert!(face.find_property_mut("nothing").is_none());
+    ply.header.encoding = Encoding::BinaryLittleEndian;
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(new_ply.header.elements["face"].find_property("vertex_indices").unwrap().data_type, PropertyType::List(ScalarType::UShort, ScalarType::UInt));
+}
+#[test]
+fn write_payload_mut_changed() {
+    let mut ply = create_triangle(0.0);
+    assert!(ply.payload_mut("nothing").is_none());
+    assert!(ply.payload_ref("nothing").is_none());
+    let vertex = ply.payload_mut("vertex").unwrap();
+    let first = vertex[0].clone();
+    vertex.push(first);
+    assert_eq!(ply.payload_ref("vertex").unwrap().len(), 4);
+    ply.make_consistent().unwrap();
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(new_ply.payload_ref("vertex"), ply.payload_ref("vertex"));
+}
+#[test]
+fn write_binary_list_length_independent_of_count() {
+    let mut ply = create_triangle(0.0);
+    ply.payload_mut("face").unwrap()[0].insert("vertex_indices".to_string(), Property::ListUInt(vec![0, 1, 2, 0, 1]));
+    ply.header.encoding = Encoding::BinaryBigEndian;
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(new_ply.payload["face"][0]["vertex_indices"], Property::ListUInt(vec![0, 1, 2, 0, 1]));
+}
+#[test]
+fn write_binary_list_too_long_err() {
+    let mut ply = create_triangle(0.0);
+    ply.payload_mut("face").unwrap()[0].insert("vertex_indices".to_string(), Property::ListUInt(vec![0; 300]));
+    ply.header.encoding = Encoding::BinaryLittleEndian;
+    let mut buf = Vec::<u8>::new();
+    let w = writer::Writer::<DefaultElement>::new();
+    match w.write_ply(&mut buf, &mut ply) {
+        Err(Error::InvalidPayload { ref element, ref message }) if element == "face" && message.contains("300") => (),
+        r => panic!("Expected invalid payload, got {:?}", r),
+    }
+    ply.header.encoding = Encoding::Ascii;
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(new_ply.payload["face"][0]["vertex_indices"], Property::ListUInt(vec![0; 300]));
+}
+#[test]
+fn write_binary_list_length_from_written_slice() {
+    // `list_len` disagrees with the declared getter, the prefix must match the written values
+    struct Face(Vec<u32>);
+    impl PropertyAccess for Face {
+        fn new() -> Self {
+            Face(Vec::new())
+        }
+        fn get_list_uint(&self, key: &str) -> Option<&[u32]> {
+            if key == "vertex_indices" { Some(&self.0) } else { None }
+        }
+        fn list_len(&self, _key: &str) -> Option<usize> {
+            Some(99)
+        }
+    }
+    let mut def = ElementDef::new("face".to_string());
+    def.add_property("vertex_indices", PropertyType::List(ScalarType::UChar, ScalarType::UInt));
+    let mut buf = Vec::<u8>::new();
+    let w = writer::Writer::<Face>::new();
+    let written = w.write_little_endian_element(&mut buf, &Face(vec![7, 8, 9]), &def).unwrap();
+    assert_eq!(written, 13);
+    assert_eq!(buf, vec![3, 7, 0, 0, 0, 8, 0, 0, 0, 9, 0, 0, 0]);
+}
+#[test]
+fn min_binary_bytes_per_element() {
+    let mut e = ElementDef::new("face".to_string());
+    assert_eq!(e.min_binary_bytes_per_element(), 0);
+    e.add_property("flag", PropertyType::Scalar(ScalarType::Double))
+        .add_property("vertex_indices", PropertyType::List(ScalarType::UChar, ScalarType::UInt));
+    assert_eq!(e.min_binary_bytes_per_element(), 9);
+
+    let mut ply = Ply::new();
+    ply.header.encoding = Encoding::BinaryLittleEndian;
+    ply.header.elements.add(e);
+    let mut f = KeyMap::new();
+    f.insert("flag".to_string(), Property::Double(1.0));
+    f.insert("vertex_indices".to_string(), Property::ListUInt(Vec::new()));
+    ply.payload.insert("face".to_string(), vec![f]);
+    assert!(ply.make_consistent().is_ok());
+    let mut header = Vec::<u8>::new();
+    writer::Writer::<DefaultElement>::new().write_header(&mut header, &ply.header).unwrap();
+    assert_eq!(write_buff(&ply).len(), header.len() + 9);
+}
+struct FailingWrite;
+impl std::io::Write for FailingWrite {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "read only"))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+#[test]
+fn write_typed_errors() {
+    let w = writer::Writer::<DefaultElement>::new();
+    let mut ply = create_triangle(0.0);
+    match w.write_ply_typed(&mut FailingWrite, &mut ply) {
+        Err(parser::PlyError::Io(_)) => (),
+        r => panic!("Expected IO error, got {:?}", r),
+    }
+    ply.header.comments.push("two\nlines".to_string());
+    match w.write_ply_typed(&mut Vec::<u8>::new(), &mut ply) {
+        Err(parser::PlyError::Parse(_)) => (),
+        r => panic!("Expected parse error, got {:?}", r),
+    }
+    let list = PropertyDef::new("l".to_string(), PropertyType::List(ScalarType::Float, ScalarType::Int));
+    match w.write_line_property_definition_typed(&mut Vec::<u8>::new(), &list) {
+        Err(parser::PlyError::Parse(_)) => (),
+        r => panic!("Expected parse error, got {:?}", r),
+    }
+}
+#[test]
+fn header_display() {
+    let ply = create_list_elements();
+    let mut buf = Vec::<u8>::new();
+    writer::Writer::<DefaultElement>::new().write_header(&mut buf, &ply.header).unwrap();
+    let txt = ply.header.to_string();
+    assert_eq!(txt.as_bytes(), &buf[..]);
+    assert!(txt.starts_with("ply\nformat ascii 1.0\n"));
+    assert!(txt.ends_with("end_header\n"));
+}
+#[test]
+fn header_display_invalid() {
+    let mut header = Header::new();
+    let mut e = ElementDef::new("point".to_string());
+    e.add_property("l", PropertyType::List(ScalarType::Float, ScalarType::ULong));
+    header.elements.add(e);
+    assert!(writer::Writer::<DefaultElement>::new().write_header(&mut Vec::<u8>::new(), &header).is_err());
+    assert_eq!(header.to_string(), "ply\nformat ascii 1.0\nelement point 0\nproperty list float uint64 l\nend_header\n");
+}
+#[test]
+fn total_binary_payload_size() {
+    let mut ply = create_list_elements();
+    assert_eq!(ply.total_binary_payload_size(), None);
+    for &encoding in &[Encoding::BinaryLittleEndian, Encoding::BinaryBigEndian] {
+        ply.header.encoding = encoding;
+        let mut header = Vec::<u8>::new();
+        writer::Writer::<DefaultElement>::new().write_header(&mut header, &ply.header).unwrap();
+        assert_eq!(ply.total_binary_payload_size(), Some(write_buff(&ply).len() - header.len()));
+    }
+    ply.payload.get_mut("aList").unwrap()[0].remove("x");
+    assert_eq!(ply.total_binary_payload_size(), None);
+}
+#[test]
+fn total_binary_payload_size_custom_element() {
+    // only the getter of the declared type is implemented
+    struct Face(Vec<u32>);
+    impl PropertyAccess for Face {
+        fn new() -> Self {
+            Face(Vec::new())
+        }
+        fn get_list_uint(&self, key: &str) -> Option<&[u32]> {
+            if key == "vertex_indices" { Some(&self.0) } else { None }
+        }
+    }
+    let mut def = ElementDef::new("face".to_string());
+    def.add_property("vertex_indices", PropertyType::List(ScalarType::UChar, ScalarType::UInt));
+    let mut ply = ply::Ply::<Face>::new();
+    ply.header.encoding = Encoding::BinaryLittleEndian;
+    ply.header.elements.add(def);
+    ply.payload.insert("face".to_string(), vec![Face(vec![0, 1, 2]), Face(vec![0, 1, 2, 3])]);
+    assert_eq!(ply.total_binary_payload_size(), Some(2 + 7 * 4));
+}
+#[test]
+fn write_removed_element() {
+    let mut ply = create_triangle(0.0);
+    let faces = ply.remove_element("face").unwrap();
+    assert_eq!(faces.len(), 1);
+    assert!(!ply.header.has_element("face"));
+    assert!(ply.remove_element("face").is_none());
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn write_ply_to_path() {
+    let mut ply = create_list_elements();
+    let path = std::env::temp_dir().join(format!("ply_rs_write_ply_to_path_{}.ply", std::process::id()));
+    let written = writer::Writer::new().write_ply_to_path(&path, &mut ply).unwrap();
+    let mut f = std::fs::File::open(&path).unwrap();
+    let new_ply = read_buff(&mut f);
+    assert_eq!(std::fs::metadata(&path).unwrap().len() as usize, written);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn write_mapped_payload() {
+    struct Vertex {
+        x: f32,
+    }
+    impl PropertyAccess for Vertex {
+        fn new() -> Self {
+            Vertex { x: 0.0 }
+        }
+        fn get_float(&self, key: &str) -> Option<f32> {
+            match key {
+                "x" => Some(self.x),
+                _ => None,
+            }
+        }
+    }
+    let ply = create_triangle(0.0);
+    let header = ply.header.clone();
+    let mut mapped = ply.map_payload("vertex", |v| match v["x"] {
+        Property::Float(x) => Vertex { x: 2.0 * x },
+        _ => panic!("Expected float."),
+    });
+    assert_eq!(mapped.header, header);
+    assert_eq!(mapped.payload["vertex"].iter().map(|v| v.x).collect::<Vec<_>>(), vec![0.0, 2.0, 4.0]);
+    assert!(!mapped.payload.contains_key("face"));
+    mapped.remove_element("face");
+    let mut buf = Vec::<u8>::new();
+    writer::Writer::new().write_ply(&mut buf, &mut mapped).unwrap();
+    let new_ply = read_buff(&mut BufReader::new(&buf[..]));
+    assert_eq!(new_ply.payload["vertex"][2]["x"], Property::Float(4.0));
+}
+#[test]
+fn write_modified_elements() {
+    let mut ply = create_triangle(0.0);
+    ply.for_each_element_mut("vertex", |v| {
+        if let Some(Property::Float(ref mut x)) = v.get_mut("x") {
+            *x += 10.0;
+        }
+    });
+    ply.for_each_element_mut("nothing", |_| panic!("No elements expected."));
+    let mut xs = Vec::new();
+    ply.for_each_element("vertex", |v| xs.push(v["x"].clone()));
+    assert_eq!(xs, vec![Property::Float(10.0), Property::Float(11.0), Property::Float(12.0)]);
+    assert_eq!(ply, create_triangle(10.0));
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn clone_parser_writer() {
+    // Deliberately not `Clone`.
+    struct Point;
+    impl PropertyAccess for Point {
+        fn new() -> Self {
+            Point
+        }
+    }
+    #[derive(Clone)]
+    struct Io {
+        parser: parser::Parser<Point>,
+        writer: writer::Writer<Point>,
+    }
+    let io = Io { parser: parser::Parser::lenient(), writer: writer::Writer::new() };
+    let copy = io.clone();
+    assert_eq!(copy.parser.options(), io.parser.options());
+    assert_eq!(copy.writer.estimate_header_bytes(&Header::new()), io.writer.estimate_header_bytes(&Header::new()));
+
+    let mut w = writer::Writer::<DefaultElement>::new();
+    w.set_newline(writer::NewLine::RN);
+    let ply = create_basic_header();
+    let mut expected = Vec::new();
+    w.write_header(&mut expected, &ply.header).unwrap();
+    let mut buf = Vec::new();
+    w.clone().write_header(&mut buf, &ply.header).unwrap();
+    assert_eq!(buf, expected);
+}
+#[test]
+fn default_values() {
+    assert_eq!(Ply::default(), Ply::new());
+    assert_eq!(Header::default(), Header::new());
+    assert_eq!(Version::default(), Version { major: 1, minor: 0 });
+    let e = ElementDef { name: "vertex".to_string(), ..Default::default() };
+    assert_eq!(e, ElementDef::new("vertex".to_string()));
+    let p = PropertyDef { name: "x".to_string(), ..Default::default() };
+    assert_eq!(p, PropertyDef::new("x".to_string(), PropertyType::Scalar(ScalarType::Char)));
+    let mut ply = create_min();
+    ply.header.elements.add(e);
+    assert!(ply.make_consistent().is_ok());
+    read_write_ply(&ply);
+}
+#[test]
+fn write_added_element_payload() {
+    let mut ply = create_triangle(0.0);
+    let mut def = ElementDef::new("edge".to_string());
+    def.add_property("vertex1", PropertyType::Scalar(ScalarType::Int))
+        .add_property("vertex2", PropertyType::Scalar(ScalarType::Int));
+    let mut edge = KeyMap::new();
+    edge.insert("vertex1".to_string(), Property::Int(0));
+    edge.insert("vertex2".to_string(), Property::Int(2));
+    ply.add_element_payload(def, vec![edge.clone(), edge]).unwrap();
+    assert_eq!(ply.header.elements["edge"].count, 2);
+    assert!(ply.is_consistent());
+    let before = ply.clone();
+    assert!(ply.add_element_payload(ElementDef::new("face".to_string()), Vec::new()).is_err());
+    assert_eq!(ply, before);
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn write_multiple_obj_infos() {
+    let mut ply = create_list_elements();
+    ply.header.obj_infos.push("vertex_count 0".to_string());
+    ply.header.obj_infos.push("scanner: unknown".to_string());
+    ply.header.comments.push("second comment".to_string());
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(new_ply.header.obj_infos.len(), 3);
+    assert_eq!(ply, new_ply);
+    let txt = String::from_utf8(write_buff(&ply)).unwrap();
+    let lines: Vec<&str> = txt.lines().take(8).collect();
+    assert_eq!(lines, vec![
+        "ply",
+        "format ascii 1.0",
+        "comment Hi, I'm your friendly comment.",
+        "comment second comment",
+        "obj_info And I'm your object information.",
+        "obj_info vertex_count 0",
+        "obj_info scanner: unknown",
+        "element aList 2",
+    ]);
+}
+#[test]
+fn write_streamed_elements() {
+    let mut ply = create_triangle(0.0);
+    let vertex = |i: u8| {
+        let mut v = KeyMap::new();
+        v.insert("x".to_string(), Property::Float(i as f32));
+        v
+    };
+    let w = writer::Writer::new();
+    for encoding in [Encoding::Ascii, Encoding::BinaryBigEndian, Encoding::BinaryLittleEndian] {
+        ply.header.encoding = encoding;
+        let mut buf = Vec::<u8>::new();
+        let mut written = w.write_header(&mut buf, &ply.header).unwrap();
+        written += w.write_payload_element_streaming(&mut buf, &ply.header.elements["vertex"], &ply.header, (0..3).map(vertex)).unwrap();
+        written += w.write_payload_element_streaming(&mut buf, &ply.header.elements["face"], &ply.header, ply.payload["face"].clone().into_iter()).unwrap();
+        assert_eq!(written, buf.len());
+        assert_eq!(buf, write_buff(&ply));
+    }
+    let def = &ply.header.elements["vertex"];
+    assert!(w.write_payload_element_streaming(&mut Vec::new(), def, &ply.header, (0..2).map(vertex)).is_err());
+    assert!(w.write_payload_element_streaming(&mut Vec::new(), def, &ply.header, (0..4).map(vertex)).is_err());
+}
+#[test]
+fn write_retained_elements() {
+    let mut ply = create_triangle(0.0);
+    ply.retain_elements_by("vertex", |v| v["x"] != Property::Float(1.0)).unwrap();
+    assert_eq!(ply.header.elements["vertex"].count, 2);
+    assert_eq!(ply.payload["vertex"][1]["x"], Property::Float(2.0));
+    ply.retain_elements_by("face", |_| false).unwrap();
+    assert_eq!(ply.header.elements["face"].count, 0);
+    let new_ply = read_write_ply(&ply);
+    assert_eq!(ply, new_ply);
+}
+#[test]
+fn write_converted_encoding() {
+    let ascii = read_buff(&mut std::fs::File::open("example_plys/house_2_ok_ascii.ply").unwrap());
+    let binary = ascii.convert_encoding(Encoding::BinaryBigEndian).unwrap();
+    assert_eq!(ascii.header.encoding, Encoding::Ascii);
+    assert_eq!(binary.header.encoding, Encoding::BinaryBigEndian);
+    assert_eq!(binary.payload, ascii.payload);
+    let new_ply = read_write_ply(&binary);
+    assert_eq!(new_ply.convert_encoding(Encoding::Ascii).unwrap(), ascii);
+}